@@ -0,0 +1,452 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::config::{Config, ExportFormat, ScreenshotFormat};
+use crate::ipc::{self, Request};
+use crate::terminal::screenshot::ScreenRegion;
+use crate::terminal::{SessionId, SessionOptions};
+
+/// 명령줄 인자
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "sterm", version, about = "A modern terminal emulator")]
+pub struct Cli {
+    /// Run a command instead of the shell; everything after it is passed to the command
+    #[arg(
+        short = 'e',
+        long = "command",
+        value_name = "COMMAND",
+        num_args = 1..,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+
+    /// Start the first tab in this directory
+    #[arg(long, value_name = "DIR")]
+    pub working_directory: Option<PathBuf>,
+
+    /// Start the first tab with a profile from the `[profiles]` config section
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Title of the first tab
+    #[arg(short = 'T', long)]
+    pub title: Option<String>,
+
+    /// Read the configuration from this file instead of ~/.config/sterm/config.toml
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Override a config value, e.g. `-o ui.font_size=16` (repeatable)
+    #[arg(short = 'o', long = "option", value_name = "KEY=VALUE")]
+    pub options: Vec<String>,
+
+    /// Keep the tab open after the command exits
+    #[arg(long)]
+    pub hold: bool,
+
+    /// Window class reported to the window manager (X11 WM_CLASS / Wayland app_id)
+    #[arg(long)]
+    pub class: Option<String>,
+
+    /// Print the default configuration and exit
+    #[arg(long)]
+    pub print_default_config: bool,
+
+    /// Check the configuration file and overrides, then exit
+    #[arg(long)]
+    pub check_config: bool,
+
+    #[command(subcommand)]
+    pub subcommand: Option<CliCommand>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCommand {
+    /// Control a running sterm through its remote control socket
+    Msg(MsgArgs),
+}
+
+/// `sterm msg` 인자
+#[derive(Debug, Clone, Args)]
+pub struct MsgArgs {
+    /// Socket of the instance to control (default: $STERM_SOCKET, then the first instance)
+    #[arg(long, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+
+    /// Auth token (default: $STERM_IPC_TOKEN, then `ipc.auth_token` from the config)
+    #[arg(long)]
+    pub token: Option<String>,
+
+    #[command(subcommand)]
+    pub action: MsgAction,
+}
+
+/// Tab-targeting actions default to the tab `sterm msg` runs in, or the active tab
+#[derive(Debug, Clone, Subcommand)]
+pub enum MsgAction {
+    /// List open tabs as JSON
+    List,
+    /// Open a tab and print its id
+    Create {
+        #[arg(long, value_name = "DIR")]
+        working_directory: Option<PathBuf>,
+        #[arg(short = 'T', long)]
+        title: Option<String>,
+        /// Keep the tab open after the command exits
+        #[arg(long)]
+        hold: bool,
+        /// Command to run instead of the shell
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Close a tab without asking
+    Close {
+        #[arg(long)]
+        id: Option<SessionId>,
+    },
+    /// Send text to the program in a tab as if it was typed
+    Write {
+        #[arg(long)]
+        id: Option<SessionId>,
+        text: String,
+    },
+    /// Resize a tab's terminal grid
+    Resize {
+        #[arg(long)]
+        id: Option<SessionId>,
+        cols: u16,
+        rows: u16,
+    },
+    /// Print the text on a tab's screen
+    GetText {
+        #[arg(long)]
+        id: Option<SessionId>,
+        /// Include the whole scrollback
+        #[arg(long)]
+        scrollback: bool,
+    },
+    /// Switch to a tab
+    Focus {
+        #[arg(long)]
+        id: Option<SessionId>,
+    },
+    /// Set a tab's title
+    SetTitle {
+        #[arg(long)]
+        id: Option<SessionId>,
+        title: String,
+    },
+    /// Print a tab's scrollback as text, ANSI (colors for `cat`) or HTML
+    Export {
+        #[arg(long)]
+        id: Option<SessionId>,
+        /// text, ansi or html
+        #[arg(long, default_value = "text")]
+        format: ExportFormat,
+        /// Only the selected text
+        #[arg(long)]
+        selection: bool,
+        /// Write to a file instead of printing
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Copy to the clipboard instead of printing
+        #[arg(long, conflicts_with = "output")]
+        clipboard: bool,
+    },
+    /// Save a picture of a tab's screen as SVG or PNG
+    Screenshot {
+        #[arg(long)]
+        id: Option<SessionId>,
+        /// svg or png (default: from the output file's extension, else svg)
+        #[arg(long)]
+        format: Option<ScreenshotFormat>,
+        /// Only part of the screen
+        #[arg(long, value_name = "LINE,COL,LINES,COLS")]
+        region: Option<ScreenRegion>,
+        /// Write to a file instead of printing (required for png)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+impl MsgAction {
+    /// 보낼 요청. 탭을 지정하지 않으면 이 명령이 실행 중인 탭($STERM_SESSION_ID)
+    pub fn request(&self) -> Request {
+        let target = |id: &Option<SessionId>| {
+            id.or_else(|| std::env::var(ipc::SESSION_ENV).ok()?.parse().ok())
+        };
+        match self.clone() {
+            MsgAction::List => Request::List,
+            MsgAction::Create {
+                working_directory,
+                title,
+                hold,
+                command,
+            } => Request::Create {
+                command,
+                working_directory,
+                title,
+                env: Default::default(),
+                hold,
+                profile: None,
+                raise: false,
+            },
+            MsgAction::Close { id } => Request::Close { id: target(&id) },
+            MsgAction::Write { id, text } => Request::Write {
+                id: target(&id),
+                text,
+            },
+            MsgAction::Resize { id, cols, rows } => Request::Resize {
+                id: target(&id),
+                cols,
+                rows,
+            },
+            MsgAction::GetText { id, scrollback } => Request::GetText {
+                id: target(&id),
+                scrollback,
+            },
+            MsgAction::Focus { id } => Request::Focus { id: target(&id) },
+            MsgAction::SetTitle { id, title } => Request::SetTitle {
+                id: target(&id),
+                title,
+            },
+            MsgAction::Export {
+                id,
+                format,
+                selection,
+                output,
+                clipboard,
+            } => Request::Export {
+                id: target(&id),
+                format,
+                selection,
+                path: output.map(absolute_path),
+                clipboard,
+            },
+            MsgAction::Screenshot {
+                id,
+                format,
+                region,
+                output,
+            } => {
+                let format = format.unwrap_or_else(|| {
+                    let png = output
+                        .as_ref()
+                        .and_then(|path| path.extension())
+                        .is_some_and(|extension| {
+                            extension.eq_ignore_ascii_case(ScreenshotFormat::Png.extension())
+                        });
+                    if png {
+                        ScreenshotFormat::Png
+                    } else {
+                        ScreenshotFormat::Svg
+                    }
+                });
+                Request::Screenshot {
+                    id: target(&id),
+                    format,
+                    region,
+                    path: output.map(absolute_path),
+                }
+            }
+        }
+    }
+}
+
+/// 파일은 실행 중인 인스턴스가 쓰므로 이 프로세스 기준 절대 경로로
+fn absolute_path(path: PathBuf) -> PathBuf {
+    match std::env::current_dir() {
+        Ok(current_dir) => current_dir.join(path),
+        Err(_) => path,
+    }
+}
+
+impl Cli {
+    /// 설정 파일을 읽고 명령줄 덮어쓰기를 적용
+    pub async fn load_config(&self) -> Result<Config> {
+        let mut config = match &self.config {
+            Some(path) => Config::load_from(path).await?,
+            None => Config::load().await?,
+        };
+        for option in &self.options {
+            config.apply_override(option)?;
+        }
+        if let Some(class) = &self.class {
+            config.ui.window_class = Some(class.clone());
+        }
+        Ok(config)
+    }
+
+    /// 첫 번째 탭을 시작할 옵션. 프로필 위에 명령줄 값이 덮어씀
+    pub fn session_options(&self, config: &Config) -> Result<SessionOptions> {
+        let mut options = SessionOptions::default();
+        if let Some(name) = &self.profile {
+            let profile = config
+                .profiles
+                .get(name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown profile {:?}", name))?;
+            options.command = profile.command.clone();
+            options.working_directory = profile.working_directory.clone();
+            options.title = profile.title.clone();
+            options.env = profile.env.clone();
+            options.profile = Some(name.clone());
+        }
+
+        if !self.command.is_empty() {
+            options.command = self.command.clone();
+        }
+        if let Some(dir) = &self.working_directory {
+            options.working_directory = Some(dir.clone());
+        }
+        if let Some(title) = &self.title {
+            options.title = Some(title.clone());
+        }
+        options.hold = self.hold;
+        Ok(options)
+    }
+
+    /// 실행 중인 인스턴스에 보낼 새 탭 요청. 디렉토리는 이 프로세스 기준으로 절대 경로화
+    pub fn open_request(&self, config: &Config) -> Result<Request> {
+        let options = self.session_options(config)?;
+        let current_dir = std::env::current_dir()?;
+        let working_directory = match options.working_directory {
+            Some(dir) => current_dir.join(dir),
+            None => current_dir,
+        };
+        Ok(Request::Create {
+            command: options.command,
+            working_directory: Some(working_directory),
+            title: options.title,
+            env: options.env,
+            hold: options.hold,
+            profile: options.profile,
+            raise: true,
+        })
+    }
+
+    /// 탭 복원 대신 명령줄에서 지정한 탭으로 시작해야 하는지
+    pub fn overrides_first_session(&self) -> bool {
+        !self.command.is_empty()
+            || self.working_directory.is_some()
+            || self.profile.is_some()
+            || self.title.is_some()
+            || self.hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProfileConfig;
+
+    #[test]
+    fn test_command_takes_remaining_args() {
+        let cli = Cli::parse_from(["sterm", "--hold", "-e", "vim", "-u", "NONE", "file.txt"]);
+        assert!(cli.hold);
+        assert_eq!(cli.command, vec!["vim", "-u", "NONE", "file.txt"]);
+    }
+
+    #[test]
+    fn test_session_options_from_profile() {
+        let mut config = Config::default();
+        config.profiles.insert(
+            "logs".to_string(),
+            ProfileConfig {
+                command: vec!["tail".to_string(), "-f".to_string()],
+                title: Some("Logs".to_string()),
+                ..ProfileConfig::default()
+            },
+        );
+
+        let cli = Cli::parse_from(["sterm", "--profile", "logs", "--title", "App logs"]);
+        let options = cli.session_options(&config).unwrap();
+        assert_eq!(options.command, vec!["tail", "-f"]);
+        assert_eq!(options.title.as_deref(), Some("App logs"));
+        assert_eq!(options.profile.as_deref(), Some("logs"));
+        assert!(cli.overrides_first_session());
+
+        let cli = Cli::parse_from(["sterm", "--profile", "missing"]);
+        assert!(cli.session_options(&config).is_err());
+    }
+
+    #[test]
+    fn test_open_request_uses_absolute_directory() {
+        let cli = Cli::parse_from(["sterm", "--working-directory", "src", "-e", "make"]);
+        let request = cli.open_request(&Config::default()).unwrap();
+        let Request::Create {
+            command,
+            working_directory,
+            raise,
+            ..
+        } = request
+        else {
+            panic!("expected create request");
+        };
+        assert_eq!(command, vec!["make"]);
+        assert_eq!(
+            working_directory,
+            Some(std::env::current_dir().unwrap().join("src"))
+        );
+        assert!(raise);
+    }
+
+    #[test]
+    fn test_msg_subcommand() {
+        let cli = Cli::parse_from(["sterm", "msg", "write", "--id", "2", "make test\n"]);
+        let Some(CliCommand::Msg(msg)) = cli.subcommand else {
+            panic!("expected msg subcommand");
+        };
+        assert_eq!(
+            msg.action.request(),
+            Request::Write {
+                id: Some(2),
+                text: "make test\n".to_string()
+            }
+        );
+
+        let cli = Cli::parse_from(["sterm", "msg", "create", "-T", "top", "htop", "-d", "5"]);
+        let Some(CliCommand::Msg(msg)) = cli.subcommand else {
+            panic!("expected msg subcommand");
+        };
+        assert!(matches!(
+            msg.action.request(),
+            Request::Create { command, .. } if command == ["htop", "-d", "5"]
+        ));
+
+        let cli = Cli::parse_from([
+            "sterm", "msg", "export", "--format", "html", "-o", "out.html",
+        ]);
+        let Some(CliCommand::Msg(msg)) = cli.subcommand else {
+            panic!("expected msg subcommand");
+        };
+        let Request::Export { format, path, .. } = msg.action.request() else {
+            panic!("expected export request");
+        };
+        assert_eq!(format, ExportFormat::Html);
+        assert_eq!(
+            path,
+            Some(std::env::current_dir().unwrap().join("out.html"))
+        );
+        assert!(Cli::try_parse_from(["sterm", "msg", "export", "--format", "pdf"]).is_err());
+
+        let cli = Cli::parse_from([
+            "sterm",
+            "msg",
+            "screenshot",
+            "--region",
+            "0,0,5,80",
+            "-o",
+            "shot.PNG",
+        ]);
+        let Some(CliCommand::Msg(msg)) = cli.subcommand else {
+            panic!("expected msg subcommand");
+        };
+        let Request::Screenshot { format, region, .. } = msg.action.request() else {
+            panic!("expected screenshot request");
+        };
+        assert_eq!(format, ScreenshotFormat::Png);
+        assert_eq!(region.map(|region| region.cols), Some(80));
+        assert!(Cli::try_parse_from(["sterm", "msg", "screenshot", "--region", "0,0"]).is_err());
+    }
+}
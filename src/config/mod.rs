@@ -1,13 +1,55 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+use crate::utils::platform::Platform;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub terminal: TerminalConfig,
     pub ui: UIConfig,
     pub keybindings: KeyBindings,
+    #[serde(default)]
+    pub links: LinkConfig,
+    #[serde(default)]
+    pub hints: HintsConfig,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub ipc: IpcConfig,
+    /// 모든 세션의 출력에 적용하는 트리거
+    #[serde(default)]
+    pub triggers: Vec<TriggerConfig>,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub graphics: GraphicsConfig,
+    /// `--profile <이름>`으로 고르는 셸 실행 설정
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+/// 이름 붙인 셸 실행 설정 (`[profiles.<이름>]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// 셸 대신 실행할 프로그램과 인자
+    pub command: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    pub title: Option<String>,
+    pub env: HashMap<String, String>,
+    /// 이 프로필로 연 세션에만 적용하는 트리거
+    pub triggers: Vec<TriggerConfig>,
+    /// 이 프로필로 연 세션은 시작할 때부터 출력을 기록
+    pub logging: bool,
+    /// 이 프로필로 연 세션의 OSC 52 정책 (비워 둔 항목은 `[clipboard]`를 따름)
+    pub clipboard: ClipboardOverrides,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +58,31 @@ pub struct TerminalConfig {
     pub scrollback_lines: usize,
     pub enable_bold: bool,
     pub enable_italic: bool,
+    #[serde(default)]
+    pub exit_behavior: ExitBehavior,
+    /// 실행 중이어도 탭/창을 닫을 때 확인하지 않는 프로세스 이름
+    #[serde(default = "default_close_without_confirm")]
+    pub close_without_confirm: Vec<String>,
+}
+
+fn default_close_without_confirm() -> Vec<String> {
+    ["bash", "zsh", "fish", "sh", "dash", "tmux", "screen"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// 셸이 종료됐을 때 탭을 어떻게 할지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitBehavior {
+    /// 항상 탭을 닫음
+    Close,
+    /// 종료 코드가 0이 아니면 탭을 열어 두고 재시작 안내 표시
+    #[default]
+    HoldOnError,
+    /// 항상 탭을 열어 둠
+    Hold,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +91,55 @@ pub struct UIConfig {
     pub font_size: f32,
     pub background_color: String,
     pub foreground_color: String,
+    /// 커서 색 (없으면 글자색)
+    #[serde(default)]
+    pub cursor_color: Option<String>,
     pub cursor_style: CursorStyle,
+    #[serde(default)]
+    pub cursor_blinking: bool,
+    #[serde(default = "default_cursor_blink_interval_ms")]
+    pub cursor_blink_interval_ms: u64,
     pub theme: String,
+    /// 저장된 창 상태가 없을 때 처음 여는 창의 크기 (열 × 줄)
+    #[serde(default = "default_initial_columns")]
+    pub initial_columns: u16,
+    #[serde(default = "default_initial_rows")]
+    pub initial_rows: u16,
+    /// 종료할 때의 창 크기/위치/최대화 상태를 다음 실행 때 복원
+    #[serde(default = "default_true")]
+    pub remember_window_state: bool,
+    #[serde(default)]
+    pub zoom_scope: ZoomScope,
+    /// 창 관리자에 알리는 창 클래스 (X11 WM_CLASS / Wayland app_id)
+    #[serde(default)]
+    pub window_class: Option<String>,
+}
+
+/// 폰트 확대/축소가 적용되는 범위
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoomScope {
+    /// 모든 탭이 같은 크기
+    #[default]
+    Global,
+    /// 현재 탭만
+    Tab,
+}
+
+fn default_cursor_blink_interval_ms() -> u64 {
+    530
+}
+
+fn default_initial_columns() -> u16 {
+    120
+}
+
+fn default_initial_rows() -> u16 {
+    40
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +157,436 @@ pub struct KeyBindings {
     pub copy: String,
     pub paste: String,
     pub find: String,
+    /// 스크롤백을 vi 키로 이동하며 복사하는 모드
+    #[serde(default = "default_vi_mode_binding")]
+    pub vi_mode: String,
+    #[serde(default = "default_fullscreen_binding")]
+    pub toggle_fullscreen: String,
+    #[serde(default = "default_zoom_in_binding")]
+    pub zoom_in: String,
+    #[serde(default = "default_zoom_out_binding")]
+    pub zoom_out: String,
+    #[serde(default = "default_zoom_reset_binding")]
+    pub zoom_reset: String,
+    /// 키보드 입력을 모든 탭에 같이 보내기 켜기/끄기
+    #[serde(default = "default_broadcast_all_binding")]
+    pub broadcast_all: String,
+    /// 현재 탭을 브로드캐스트 그룹에 넣기/빼기
+    #[serde(default = "default_broadcast_toggle_binding")]
+    pub broadcast_toggle: String,
+    /// 현재 탭의 출력 기록 시작/중지
+    #[serde(default = "default_toggle_logging_binding")]
+    pub toggle_logging: String,
+    /// 스크롤백 전체(선택 영역이 있으면 선택 영역)를 파일로 내보내기
+    #[serde(default = "default_export_scrollback_binding")]
+    pub export_scrollback: String,
+    /// 현재 화면을 이미지로 저장
+    #[serde(default = "default_screenshot_binding")]
+    pub screenshot: String,
+}
+
+fn default_vi_mode_binding() -> String {
+    "ctrl+shift+space".to_string()
+}
+
+/// macOS는 Cmd, 그 외는 Ctrl 조합
+fn platform_binding(key: &str) -> String {
+    if cfg!(target_os = "macos") {
+        format!("cmd+{}", key)
+    } else {
+        format!("ctrl+{}", key)
+    }
+}
+
+fn default_zoom_in_binding() -> String {
+    platform_binding("=")
+}
+
+fn default_zoom_out_binding() -> String {
+    platform_binding("-")
+}
+
+fn default_zoom_reset_binding() -> String {
+    platform_binding("0")
+}
+
+fn default_broadcast_all_binding() -> String {
+    platform_binding("shift+b")
+}
+
+fn default_broadcast_toggle_binding() -> String {
+    platform_binding("shift+g")
+}
+
+fn default_toggle_logging_binding() -> String {
+    platform_binding("shift+l")
+}
+
+fn default_export_scrollback_binding() -> String {
+    platform_binding("shift+e")
+}
+
+fn default_screenshot_binding() -> String {
+    platform_binding("shift+s")
+}
+
+fn default_fullscreen_binding() -> String {
+    if cfg!(target_os = "macos") {
+        "ctrl+cmd+f".to_string()
+    } else {
+        "f11".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkConfig {
+    /// URL과 경로를 여는 명령 (예: "xdg-open", "open")
+    pub opener: String,
+    /// `file:line:col` 경로를 여는 명령 템플릿. `{path}`, `{line}`, `{column}`을 치환하며,
+    /// 없으면 `opener`로 경로만 연다 (예: "code -g {path}:{line}:{column}")
+    #[serde(default)]
+    pub file_opener: Option<String>,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self {
+            opener: Platform::default_opener().to_string(),
+            file_opener: None,
+        }
+    }
+}
+
+/// 힌트 모드에서 선택한 항목으로 할 일
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintAction {
+    Copy,
+    Paste,
+    Open,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HintConfig {
+    pub name: String,
+    /// 화면에 보이는 줄에서 찾을 정규식
+    pub regex: String,
+    pub action: HintAction,
+    /// 힌트 모드를 시작하는 단축키 (예: "ctrl+shift+u")
+    pub binding: String,
+}
+
+/// 출력 줄이 정규식과 맞으면 실행할 동작 (`[[triggers]]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerConfig {
+    pub regex: String,
+    #[serde(flatten)]
+    pub action: TriggerAction,
+    /// 줄이 끝나기 전에도 검사 (줄바꿈 없이 입력을 기다리는 프롬프트용)
+    #[serde(default)]
+    pub instant: bool,
+}
+
+/// 문자열 값에는 `$1`, `${name}` 같은 캡처 그룹을 쓸 수 있음
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TriggerAction {
+    /// 맞은 부분의 글자색/배경색 변경 ("#rrggbb")
+    Highlight {
+        #[serde(default)]
+        foreground: Option<String>,
+        #[serde(default)]
+        background: Option<String>,
+    },
+    Bell,
+    /// 데스크톱 알림. 메시지가 없으면 맞은 줄
+    Notify {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// 프로그램에 입력으로 보냄 (예: 프롬프트 자동 응답 "yes\r")
+    Send {
+        text: String,
+    },
+    /// 탭에 배지 표시
+    Badge {
+        text: String,
+    },
+}
+
+/// URL schemes recognized in plain text (same set alacritty highlights by default)
+pub const URL_PATTERN: &str = "(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file:|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>\"\\s{-}\\^⟨⟩`]+";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HintsConfig {
+    /// 라벨에 쓰는 글자들
+    pub alphabet: String,
+    pub enabled: Vec<HintConfig>,
+}
+
+impl Default for HintsConfig {
+    fn default() -> Self {
+        let hint = |name: &str, regex: &str, action, binding: &str| HintConfig {
+            name: name.to_string(),
+            regex: regex.to_string(),
+            action,
+            binding: binding.to_string(),
+        };
+
+        Self {
+            alphabet: "jfkdls;ahgurieowpq".to_string(),
+            enabled: vec![
+                hint("url", URL_PATTERN, HintAction::Open, "ctrl+shift+u"),
+                hint(
+                    "path",
+                    r"(?:~|\.{1,2})?/?(?:[\w.\-]+/)+[\w.\-]+(?::\d+(?::\d+)?)?",
+                    HintAction::Copy,
+                    "ctrl+shift+p",
+                ),
+                hint(
+                    "hash",
+                    r"\b[0-9a-f]{7,40}\b",
+                    HintAction::Copy,
+                    "ctrl+shift+h",
+                ),
+                hint(
+                    "ip",
+                    r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b",
+                    HintAction::Copy,
+                    "ctrl+shift+i",
+                ),
+            ],
+        }
+    }
+}
+
+/// 프로그램이 OSC 52로 클립보드에 접근할 때의 정책
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardAccess {
+    Allow,
+    /// 요청마다 사용자에게 확인
+    Ask,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// 프로그램이 클립보드에 쓰기 (원격 Neovim/tmux의 복사)
+    pub osc52_write: ClipboardAccess,
+    /// 프로그램이 클립보드 내용 읽기
+    pub osc52_read: ClipboardAccess,
+    /// 한 번에 주고받을 수 있는 최대 바이트 수
+    pub osc52_max_bytes: usize,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            osc52_write: ClipboardAccess::Allow,
+            osc52_read: ClipboardAccess::Ask,
+            osc52_max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl ClipboardConfig {
+    /// This policy with the values a profile sets replaced
+    pub fn with_overrides(&self, overrides: &ClipboardOverrides) -> Self {
+        Self {
+            osc52_write: overrides.osc52_write.unwrap_or(self.osc52_write),
+            osc52_read: overrides.osc52_read.unwrap_or(self.osc52_read),
+            osc52_max_bytes: overrides.osc52_max_bytes.unwrap_or(self.osc52_max_bytes),
+        }
+    }
+
+    /// Whether `text` is small enough to pass through OSC 52
+    pub fn fits(&self, text: &str) -> bool {
+        text.len() <= self.osc52_max_bytes
+    }
+}
+
+/// 프로필별 OSC 52 정책 (`[profiles.<이름>.clipboard]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardOverrides {
+    pub osc52_write: Option<ClipboardAccess>,
+    pub osc52_read: Option<ClipboardAccess>,
+    pub osc52_max_bytes: Option<usize>,
+}
+
+/// 종료 시 탭을 저장하고 다음 실행 때 복원
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub restore: bool,
+    /// 스크롤백 텍스트도 저장 (화면에 있던 비밀번호 등이 파일에 남을 수 있음)
+    pub save_scrollback: bool,
+    /// 탭마다 저장할 최대 줄 수
+    pub scrollback_lines: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            restore: true,
+            save_scrollback: false,
+            scrollback_lines: 1000,
+        }
+    }
+}
+
+/// 세션 출력을 파일로 기록 (`[logging]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// 모든 세션을 시작할 때부터 기록
+    pub enabled: bool,
+    pub format: LogFormat,
+    /// 파일 이름. `{date}`, `{time}`, `{profile}`, `{title}`, `{id}`를 바꿔 넣음
+    pub file_name: String,
+    /// 기록 파일을 둘 디렉토리 (기본: 데이터 디렉토리의 logs)
+    pub directory: Option<PathBuf>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: LogFormat::default(),
+            file_name: "{date}_{time}_{profile}_{title}.log".to_string(),
+            directory: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// 프로그램이 쓴 바이트 그대로 (이스케이프 시퀀스 포함)
+    Raw,
+    /// 이스케이프 시퀀스를 뺀 텍스트
+    #[default]
+    Plain,
+    /// 텍스트 줄마다 시각을 붙임
+    Timestamped,
+}
+
+/// 스크롤백 내보내기와 스크린샷 단축키 설정 (`[export]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    pub format: ExportFormat,
+    pub screenshot_format: ScreenshotFormat,
+    /// 내보낸 파일을 둘 디렉토리 (기본: 데이터 디렉토리의 exports)
+    pub directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotFormat {
+    Svg,
+    /// SVG를 소프트웨어로 래스터화 (GPU 불필요)
+    #[default]
+    Png,
+}
+
+impl ScreenshotFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Svg => "svg",
+            ScreenshotFormat::Png => "png",
+        }
+    }
+}
+
+impl std::str::FromStr for ScreenshotFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "svg" => Ok(ScreenshotFormat::Svg),
+            "png" => Ok(ScreenshotFormat::Png),
+            _ => Err(anyhow::anyhow!(
+                "Unknown screenshot format {:?} (svg, png)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// 공백을 그대로 둔 텍스트
+    Text,
+    /// `cat`으로 출력하면 색이 재현되는 ANSI 이스케이프 텍스트
+    Ansi,
+    /// 인라인 스타일을 쓴 단독 HTML 문서
+    #[default]
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "txt",
+            ExportFormat::Ansi => "ans",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(ExportFormat::Text),
+            "ansi" => Ok(ExportFormat::Ansi),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(anyhow::anyhow!(
+                "Unknown export format {:?} (text, ansi, html)",
+                s
+            )),
+        }
+    }
+}
+
+/// 터미널 안에 그리는 이미지 (`[graphics]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsConfig {
+    /// Sixel 이미지를 그림. 끄면 Sixel 시퀀스를 무시하고 DA1에도 알리지 않음
+    pub sixel: bool,
+    /// kitty 그래픽 프로토콜 (APC `_G`) 이미지를 받아 그리고 질의에 답함
+    pub kitty: bool,
+    /// 세션마다 이미지에 쓰는 최대 메모리 (MB). 넘으면 오래된 이미지부터 지움
+    pub memory_limit_mb: usize,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self {
+            sixel: true,
+            kitty: true,
+            memory_limit_mb: 256,
+        }
+    }
+}
+
+/// `sterm msg`로 실행 중인 인스턴스를 제어하는 소켓
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpcConfig {
+    /// 같은 사용자의 어떤 프로세스든 탭을 열고 입력을 보낼 수 있으므로 기본은 꺼짐
+    pub enabled: bool,
+    /// 설정하면 같은 토큰을 보낸 요청만 처리
+    pub auth_token: Option<String>,
+    /// 이미 실행 중인 인스턴스가 있으면 새 프로세스 대신 그 창에 탭을 엶
+    pub single_instance: bool,
 }
 
 impl Default for Config {
@@ -53,14 +597,24 @@ impl Default for Config {
                 scrollback_lines: 10000,
                 enable_bold: true,
                 enable_italic: true,
+                exit_behavior: ExitBehavior::default(),
+                close_without_confirm: default_close_without_confirm(),
             },
             ui: UIConfig {
                 font_family: "Monaco".to_string(),
                 font_size: 14.0,
                 background_color: "#1e1e1e".to_string(),
                 foreground_color: "#ffffff".to_string(),
+                cursor_color: None,
                 cursor_style: CursorStyle::Block,
+                cursor_blinking: false,
+                cursor_blink_interval_ms: default_cursor_blink_interval_ms(),
                 theme: "dark".to_string(),
+                initial_columns: default_initial_columns(),
+                initial_rows: default_initial_rows(),
+                remember_window_state: true,
+                zoom_scope: ZoomScope::default(),
+                window_class: None,
             },
             keybindings: KeyBindings {
                 new_tab: "cmd+t".to_string(),
@@ -69,7 +623,27 @@ impl Default for Config {
                 copy: "cmd+c".to_string(),
                 paste: "cmd+v".to_string(),
                 find: "cmd+f".to_string(),
+                vi_mode: default_vi_mode_binding(),
+                toggle_fullscreen: default_fullscreen_binding(),
+                zoom_in: default_zoom_in_binding(),
+                zoom_out: default_zoom_out_binding(),
+                zoom_reset: default_zoom_reset_binding(),
+                broadcast_all: default_broadcast_all_binding(),
+                broadcast_toggle: default_broadcast_toggle_binding(),
+                toggle_logging: default_toggle_logging_binding(),
+                export_scrollback: default_export_scrollback_binding(),
+                screenshot: default_screenshot_binding(),
             },
+            links: LinkConfig::default(),
+            hints: HintsConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            session: SessionConfig::default(),
+            ipc: IpcConfig::default(),
+            triggers: Vec::new(),
+            logging: LoggingConfig::default(),
+            export: ExportConfig::default(),
+            graphics: GraphicsConfig::default(),
+            profiles: HashMap::new(),
         }
     }
 }
@@ -89,6 +663,48 @@ impl Config {
         }
     }
 
+    /// 지정한 설정 파일을 읽음 (`--config`). 기본 경로와 달리 파일이 없으면 오류
+    pub async fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path.display(), e))?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// `ui.font_size=14` 형식의 설정 값 덮어쓰기 (`--option`)
+    ///
+    /// 값은 TOML로 해석하고, 해석되지 않으면 문자열로 취급합니다.
+    pub fn apply_override(&mut self, option: &str) -> Result<()> {
+        let (key, value) = option
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected key=value, got {:?}", option))?;
+        let value: toml::Value = toml::from_str::<toml::Table>(&format!("v = {}", value.trim()))
+            .ok()
+            .and_then(|mut table| table.remove("v"))
+            .unwrap_or_else(|| toml::Value::String(value.trim().to_string()));
+
+        let mut root = toml::Value::try_from(&*self)?;
+        let mut target = &mut root;
+        let mut parts = key.trim().split('.').peekable();
+        while let Some(part) = parts.next() {
+            let table = target
+                .as_table_mut()
+                .ok_or_else(|| anyhow::anyhow!("{:?} is not a table in {:?}", part, key))?;
+            if parts.peek().is_none() {
+                table.insert(part.to_string(), value);
+                break;
+            }
+            target = table
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        }
+
+        *self = root
+            .try_into()
+            .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+        Ok(())
+    }
+
     pub async fn save(&self) -> Result<()> {
         let config_path = Self::config_file_path()?;
 
@@ -102,7 +718,7 @@ impl Config {
         Ok(())
     }
 
-    fn config_file_path() -> Result<PathBuf> {
+    pub fn config_file_path() -> Result<PathBuf> {
         let home_dir =
             dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
 
@@ -113,3 +729,65 @@ impl Config {
         std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_override() {
+        let mut config = Config::default();
+        config.apply_override("ui.font_size=16").unwrap();
+        config.apply_override("terminal.shell = /bin/bash").unwrap();
+        config
+            .apply_override("ui.window_class=\"sterm-dev\"")
+            .unwrap();
+        assert_eq!(config.ui.font_size, 16.0);
+        assert_eq!(config.terminal.shell, "/bin/bash");
+        assert_eq!(config.ui.window_class.as_deref(), Some("sterm-dev"));
+
+        assert!(config.apply_override("ui.font_size").is_err());
+        assert!(config.apply_override("ui.font_size=big").is_err());
+    }
+
+    #[test]
+    fn test_profile_clipboard_overrides() {
+        let config = Config::default();
+        let profile: ProfileConfig = toml::from_str(
+            r#"
+            [clipboard]
+            osc52_read = "deny"
+            osc52_max_bytes = 8
+            "#,
+        )
+        .unwrap();
+        let clipboard = config.clipboard.with_overrides(&profile.clipboard);
+        // 프로필에 없는 쓰기 정책은 전역 값
+        assert_eq!(clipboard.osc52_write, ClipboardAccess::Allow);
+        assert_eq!(clipboard.osc52_read, ClipboardAccess::Deny);
+        assert!(clipboard.fits("12345678"));
+        assert!(!clipboard.fits("123456789"));
+        assert!(!clipboard.fits("한글한"));
+
+        let plain = config
+            .clipboard
+            .with_overrides(&ClipboardOverrides::default());
+        assert_eq!(plain, config.clipboard);
+    }
+
+    #[test]
+    fn test_partial_tables_use_defaults() {
+        let session: SessionConfig = toml::from_str("restore = false").unwrap();
+        assert!(!session.restore);
+        assert!(!session.save_scrollback);
+        assert_eq!(session.scrollback_lines, 1000);
+
+        let clipboard: ClipboardConfig = toml::from_str(r#"osc52_read = "deny""#).unwrap();
+        assert_eq!(clipboard.osc52_write, ClipboardAccess::Allow);
+        assert_eq!(clipboard.osc52_read, ClipboardAccess::Deny);
+
+        let hints: HintsConfig = toml::from_str(r#"alphabet = "asdf""#).unwrap();
+        assert_eq!(hints.alphabet, "asdf");
+        assert!(!hints.enabled.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use super::{default_socket_path, Message, Request, Response, SOCKET_ENV};
+
+/// Socket to talk to: `explicit`, else the instance this shell runs in, else the first instance
+pub fn socket_path(explicit: Option<&Path>) -> Result<PathBuf> {
+    if let Some(path) = explicit {
+        return Ok(path.to_path_buf());
+    }
+    match std::env::var_os(SOCKET_ENV) {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => default_socket_path(),
+    }
+}
+
+/// Send one request and wait for its response
+pub async fn send(path: &Path, token: Option<String>, request: Request) -> Result<Response> {
+    let stream = UnixStream::connect(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Could not connect to sterm at {}: {}", path.display(), e))?;
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = serde_json::to_string(&Message { token, request })?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let mut reply = String::new();
+    BufReader::new(reader).read_line(&mut reply).await?;
+    if reply.is_empty() {
+        return Err(anyhow::anyhow!(
+            "sterm closed the connection without a response"
+        ));
+    }
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// Print a response for `sterm msg`; error responses become `Err`
+pub fn print_response(response: Response) -> Result<()> {
+    match response {
+        Response::Ok => {}
+        Response::Sessions { sessions } => {
+            println!("{}", serde_json::to_string_pretty(&sessions)?)
+        }
+        Response::Created { id } => println!("{}", id),
+        Response::Text { text } => println!("{}", text),
+        Response::Error { message } => return Err(anyhow::anyhow!(message)),
+    }
+    Ok(())
+}
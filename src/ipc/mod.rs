@@ -0,0 +1,170 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::config::{ExportFormat, ScreenshotFormat};
+use crate::terminal::screenshot::ScreenRegion;
+use crate::terminal::SessionId;
+use crate::utils::platform::Platform;
+
+pub mod client;
+pub mod server;
+
+// 원격 제어 프로토콜: 소켓에서 한 줄이 JSON 메시지 하나.
+// 클라이언트가 Message를 보내면 서버가 Response로 답하고, 연결은 계속 열어 둠
+
+/// Socket of the instance a shell runs in, set for every child process
+pub const SOCKET_ENV: &str = "STERM_SOCKET";
+/// Session a shell runs in, the default target of `sterm msg`
+pub const SESSION_ENV: &str = "STERM_SESSION_ID";
+/// Auth token used by `sterm msg` when `--token` is not given
+pub const TOKEN_ENV: &str = "STERM_IPC_TOKEN";
+
+/// What a client asks the running instance to do. A missing `id` means the active tab.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// All open tabs
+    List,
+    /// Open a tab, running `command` instead of the shell when given
+    Create {
+        #[serde(default)]
+        command: Vec<String>,
+        working_directory: Option<PathBuf>,
+        title: Option<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+        #[serde(default)]
+        hold: bool,
+        /// `[profiles]` entry whose per-session settings (e.g. triggers) apply
+        #[serde(default)]
+        profile: Option<String>,
+        /// Bring the window to the front, as for a new `sterm` launch
+        #[serde(default)]
+        raise: bool,
+    },
+    Close {
+        id: Option<SessionId>,
+    },
+    /// Send text to the program as if it was typed
+    Write {
+        id: Option<SessionId>,
+        text: String,
+    },
+    Resize {
+        id: Option<SessionId>,
+        cols: u16,
+        rows: u16,
+    },
+    /// Plain text of the screen, or of the whole scrollback
+    GetText {
+        id: Option<SessionId>,
+        #[serde(default)]
+        scrollback: bool,
+    },
+    /// Switch to a tab
+    Focus {
+        id: Option<SessionId>,
+    },
+    SetTitle {
+        id: Option<SessionId>,
+        title: String,
+    },
+    /// Scrollback (or the selection) as text, ANSI or HTML.
+    /// Written to `path` or copied to the clipboard when asked, returned as text otherwise.
+    Export {
+        id: Option<SessionId>,
+        format: ExportFormat,
+        #[serde(default)]
+        selection: bool,
+        path: Option<PathBuf>,
+        #[serde(default)]
+        clipboard: bool,
+    },
+    /// Picture of the visible screen (or `region` of it).
+    /// Written to `path`; SVG can also be returned as text.
+    Screenshot {
+        id: Option<SessionId>,
+        format: ScreenshotFormat,
+        region: Option<ScreenRegion>,
+        path: Option<PathBuf>,
+    },
+}
+
+/// A request as sent over the socket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// One open tab, as listed by [`Request::List`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub title: String,
+    pub active: bool,
+    pub cols: u16,
+    pub rows: u16,
+    pub working_directory: Option<PathBuf>,
+    /// Foreground program, unless it is the shell itself
+    pub running: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Sessions {
+        sessions: Vec<SessionInfo>,
+    },
+    /// Tab opened by [`Request::Create`]
+    Created {
+        id: SessionId,
+    },
+    Text {
+        text: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl Response {
+    pub fn error(message: impl Into<String>) -> Self {
+        Response::Error {
+            message: message.into(),
+        }
+    }
+}
+
+/// Well-known socket path of the first instance
+pub fn default_socket_path() -> Result<PathBuf> {
+    Ok(Platform::runtime_dir()?.join("sterm.sock"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_wire_format() {
+        let message: Message =
+            serde_json::from_str(r#"{"type": "write", "text": "ls\n", "token": "secret"}"#)
+                .unwrap();
+        assert_eq!(message.token.as_deref(), Some("secret"));
+        assert_eq!(
+            message.request,
+            Request::Write {
+                id: None,
+                text: "ls\n".to_string()
+            }
+        );
+
+        let json = serde_json::to_string(&Response::Created { id: 3 }).unwrap();
+        assert_eq!(json, r#"{"type":"created","id":3}"#);
+    }
+}
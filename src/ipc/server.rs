@@ -0,0 +1,244 @@
+use anyhow::Result;
+use std::fs::{self, DirBuilder, Permissions};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{default_socket_path, Message, Request, Response};
+
+/// A request waiting to be handled by the UI, answered through `reply`
+pub struct IpcRequest {
+    pub request: Request,
+    pub reply: oneshot::Sender<Response>,
+}
+
+/// Listening socket, removed again when dropped
+pub struct IpcServer {
+    path: PathBuf,
+}
+
+impl IpcServer {
+    /// Listen on `path`, sending every authorized request to the returned receiver.
+    /// Fails when another instance is already listening there.
+    pub fn bind(path: &Path, token: Option<String>) -> Result<(Self, mpsc::Receiver<IpcRequest>)> {
+        if let Some(parent) = path.parent() {
+            DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(parent)?;
+            check_private_dir(parent)?;
+        }
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(anyhow::anyhow!(
+                    "{} is in use by another instance",
+                    path.display()
+                ));
+            }
+            // 비정상 종료한 이전 실행이 남긴 소켓
+            fs::remove_file(path)?;
+        }
+
+        // 권한을 바꾸기 전에 다른 사용자가 연결하지 못하도록 처음부터 0600으로 만듦
+        let mask = unsafe { libc::umask(0o177) };
+        let listener = UnixListener::bind(path);
+        unsafe { libc::umask(mask) };
+        let listener = listener?;
+        fs::set_permissions(path, Permissions::from_mode(0o600))?;
+
+        let (sender, receiver) = mpsc::channel(16);
+        let token: Option<Arc<str>> = token.map(Into::into);
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let token = token.clone();
+                        let sender = sender.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_connection(stream, token, sender).await {
+                                log::debug!("IPC connection closed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        log::error!("Failed to accept IPC connection: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        log::info!("Listening for remote control on {}", path.display());
+        Ok((
+            Self {
+                path: path.to_path_buf(),
+            },
+            receiver,
+        ))
+    }
+
+    /// Listen on the well-known socket, or on a per-process one when another instance owns it
+    pub fn bind_default(token: Option<String>) -> Result<(Self, mpsc::Receiver<IpcRequest>)> {
+        let path = default_socket_path()?;
+        match Self::bind(&path, token.clone()) {
+            Ok(bound) => Ok(bound),
+            Err(e) => {
+                log::info!("{}; using a per-process socket", e);
+                let path = path.with_file_name(format!("sterm-{}.sock", std::process::id()));
+                Self::bind(&path, token)
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 연결이 닫힐 때까지 한 줄씩 요청을 읽고 응답
+async fn serve_connection(
+    stream: UnixStream,
+    token: Option<Arc<str>>,
+    sender: mpsc::Sender<IpcRequest>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Message>(&line) {
+            Err(e) => Response::error(format!("Invalid request: {}", e)),
+            Ok(message) if !authorized(token.as_deref(), message.token.as_deref()) => {
+                log::warn!("Rejected IPC request with a wrong auth token");
+                Response::error("Invalid or missing auth token")
+            }
+            Ok(message) => dispatch(&sender, message.request).await,
+        };
+
+        let mut json = serde_json::to_string(&response)?;
+        json.push('\n');
+        writer.write_all(json.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// The socket directory must be a real directory only the current user can enter,
+/// since `/tmp/sterm-<uid>` may have been created by someone else first
+fn check_private_dir(dir: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(dir)?;
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(anyhow::anyhow!(
+            "{} is not a directory owned by the current user",
+            dir.display()
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        return Err(anyhow::anyhow!(
+            "{} must only be accessible by its owner (mode 0700)",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// 토큰 비교. 길이가 같으면 내용과 상관없이 같은 시간이 걸림
+fn authorized(expected: Option<&str>, given: Option<&str>) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    let given = given.unwrap_or_default();
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn dispatch(sender: &mpsc::Sender<IpcRequest>, request: Request) -> Response {
+    let (reply, response) = oneshot::channel();
+    if sender.send(IpcRequest { request, reply }).await.is_err() {
+        return Response::error("sterm is shutting down");
+    }
+    response
+        .await
+        .unwrap_or_else(|_| Response::error("Request was dropped"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::client;
+
+    #[tokio::test]
+    async fn test_request_roundtrip_and_token() {
+        let path = std::env::temp_dir()
+            .join(format!("sterm-test-{}", std::process::id()))
+            .join("ipc.sock");
+        let (server, mut requests) = IpcServer::bind(&path, Some("secret".to_string())).unwrap();
+        assert!(IpcServer::bind(&path, None).is_err());
+
+        tokio::spawn(async move {
+            while let Some(IpcRequest { request, reply }) = requests.recv().await {
+                let response = match request {
+                    Request::GetText { id: Some(id), .. } => Response::Text {
+                        text: id.to_string(),
+                    },
+                    _ => Response::Ok,
+                };
+                let _ = reply.send(response);
+            }
+        });
+
+        let get_text = Request::GetText {
+            id: Some(7),
+            scrollback: false,
+        };
+        let response = client::send(server.path(), Some("secret".to_string()), get_text.clone());
+        assert_eq!(
+            response.await.unwrap(),
+            Response::Text {
+                text: "7".to_string()
+            }
+        );
+
+        let response = client::send(server.path(), None, get_text).await.unwrap();
+        assert!(matches!(response, Response::Error { .. }));
+
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_socket_dir_must_be_private() {
+        let dir = std::env::temp_dir().join(format!("sterm-test-dir-{}", std::process::id()));
+        DirBuilder::new().mode(0o700).create(&dir).unwrap();
+        assert!(check_private_dir(&dir).is_ok());
+
+        fs::set_permissions(&dir, Permissions::from_mode(0o755)).unwrap();
+        assert!(IpcServer::bind(&dir.join("ipc.sock"), None).is_err());
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_token_check() {
+        assert!(authorized(None, None));
+        assert!(authorized(None, Some("anything")));
+        assert!(authorized(Some("secret"), Some("secret")));
+        assert!(!authorized(Some("secret"), Some("secreT")));
+        assert!(!authorized(Some("secret"), Some("secret2")));
+        assert!(!authorized(Some("secret"), None));
+    }
+}
@@ -1,9 +1,12 @@
+mod cli;
 mod config;
+mod ipc;
 mod terminal;
 mod ui;
 mod utils;
 
 use anyhow::Result;
+use clap::Parser;
 use log::info;
 use slint::ComponentHandle;
 use std::sync::Arc;
@@ -11,7 +14,11 @@ use tokio::sync::Mutex;
 
 slint::include_modules!();
 
+use crate::cli::{Cli, CliCommand};
 use crate::config::Config;
+use crate::ipc::server::IpcServer;
+use crate::ipc::Response;
+use crate::terminal::persistence::{SavedSessions, WindowState};
 use crate::terminal::TerminalManager;
 use crate::ui::UIManager;
 
@@ -19,17 +26,78 @@ use crate::ui::UIManager;
 async fn main() -> Result<()> {
     // 로깅 초기화
     env_logger::init();
-    info!("STerm starting...");
+    let cli = Cli::parse();
+
+    // `sterm msg`: 실행 중인 인스턴스에 요청을 보내고 바로 종료
+    if let Some(CliCommand::Msg(msg)) = &cli.subcommand {
+        // --token, 환경 변수, 설정 파일 순
+        let mut token = msg
+            .token
+            .clone()
+            .or_else(|| std::env::var(ipc::TOKEN_ENV).ok());
+        if token.is_none() {
+            let config = cli.load_config().await.ok();
+            token = config.and_then(|config| config.ipc.auth_token);
+        }
+        let socket = ipc::client::socket_path(msg.socket.as_deref())?;
+        let response = ipc::client::send(&socket, token, msg.action.request()).await?;
+        return ipc::client::print_response(response);
+    }
+
+    if cli.print_default_config {
+        print!("{}", toml::to_string_pretty(&Config::default())?);
+        return Ok(());
+    }
 
-    // 설정 로드
-    let config = Config::load().await?;
+    // 설정 로드 (--config, --option 반영)
+    let config = cli.load_config().await;
+    if cli.check_config {
+        let path = match &cli.config {
+            Some(path) => path.clone(),
+            None => Config::config_file_path()?,
+        };
+        match config {
+            Ok(_) => println!("Configuration OK: {}", path.display()),
+            Err(e) => {
+                eprintln!("Invalid configuration {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    let config = config?;
     info!("Configuration loaded");
 
+    // 단일 인스턴스: 실행 중인 인스턴스가 있으면 그 창에 탭을 열고 종료
+    if config.ipc.enabled && config.ipc.single_instance {
+        let socket = ipc::client::socket_path(None)?;
+        let request = cli.open_request(&config)?;
+        let sent = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            ipc::client::send(&socket, config.ipc.auth_token.clone(), request),
+        )
+        .await;
+        match sent {
+            Ok(Ok(Response::Created { id })) => {
+                info!("Opened tab {} in the running instance", id);
+                return Ok(());
+            }
+            Ok(Ok(response)) => log::warn!("Running instance did not open a tab: {:?}", response),
+            // 소켓이 없거나 남아 있기만 한 경우
+            Ok(Err(e)) => info!("No running instance: {}", e),
+            Err(_) => log::warn!("Running instance did not answer in time"),
+        }
+        info!("Starting standalone");
+    }
+
+    info!("STerm starting...");
+
     // 터미널 매니저 생성
     let terminal_manager = Arc::new(Mutex::new(TerminalManager::new(config.clone())?));
     info!("Terminal manager created");
 
     // UI 생성
+    UIManager::select_backend(&config)?;
     let main_window = MainWindow::new()?;
     let mut ui_manager = UIManager::new(main_window.as_weak(), terminal_manager.clone())?;
     info!("UI manager created");
@@ -38,17 +106,82 @@ async fn main() -> Result<()> {
     ui_manager.setup_event_handlers().await?;
     info!("Event handlers setup complete");
 
-    // 첫 번째 터미널 세션 시작
-    {
+    // 원격 제어 소켓. 셸에서 실행한 `sterm msg`가 이 인스턴스를 찾도록 환경 변수로 알림
+    let _ipc_server = if config.ipc.enabled {
+        match IpcServer::bind_default(config.ipc.auth_token.clone()) {
+            Ok((server, requests)) => {
+                std::env::set_var(ipc::SOCKET_ENV, server.path());
+                ui_manager.start_ipc_processing(requests);
+                Some(server)
+            }
+            Err(e) => {
+                log::warn!("Remote control is unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // 지난번 창 크기/위치 복원
+    let window_state = if config.ui.remember_window_state {
+        WindowState::load().await.unwrap_or_else(|e| {
+            log::warn!("Failed to load window state: {}", e);
+            None
+        })
+    } else {
+        None
+    };
+    ui_manager.apply_window_state(&config, window_state);
+
+    // 명령줄로 지정한 탭, 지난번 탭 복원, 둘 다 없으면 첫 번째 터미널 세션 시작
+    let saved = if config.session.restore && !cli.overrides_first_session() {
+        SavedSessions::load().await.unwrap_or_else(|e| {
+            log::warn!("Failed to load saved sessions: {}", e);
+            None
+        })
+    } else {
+        None
+    };
+    let restored = match &saved {
+        Some(saved) => ui_manager.restore_sessions(saved).await?,
+        None => false,
+    };
+    if !restored {
         let mut tm = terminal_manager.lock().await;
-        tm.create_new_session()?;
+        let session_id = tm.create_session(cli.session_options(&config)?)?;
+        if let Some(session) = tm.get_session(session_id) {
+            UIManager::show_tabs(
+                &main_window,
+                &[(session_id, session.title.clone())],
+                session_id,
+            );
+            UIManager::show_logging(&main_window, &tm.logging_sessions());
+        }
+        info!("Initial terminal session created");
     }
-    info!("Initial terminal session created");
 
     // UI 실행
     info!("Starting UI event loop");
     main_window.run()?;
 
+    // 다음 실행 때 복원할 수 있도록 열린 탭 저장
+    if config.session.restore {
+        let saved = terminal_manager.lock().await.save_state();
+        if let Err(e) = saved.save().await {
+            log::error!("Failed to save sessions: {}", e);
+        }
+    } else if let Err(e) = SavedSessions::clear().await {
+        log::warn!("Failed to remove saved sessions: {}", e);
+    }
+    if config.ui.remember_window_state {
+        if let Some(state) = ui_manager.window_state(window_state) {
+            if let Err(e) = state.save().await {
+                log::error!("Failed to save window state: {}", e);
+            }
+        }
+    }
+
     info!("STerm shutting down...");
     Ok(())
 }
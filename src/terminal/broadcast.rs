@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use super::SessionId;
+
+/// Sessions that receive keyboard input typed into any one of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Broadcast {
+    /// Every session in the window
+    All,
+    /// Sessions picked one by one
+    Group(BTreeSet<SessionId>),
+}
+
+impl Broadcast {
+    /// Sessions currently receiving broadcast input, in tab order
+    pub fn members(&self, sessions: &[SessionId]) -> Vec<SessionId> {
+        let mut members: Vec<SessionId> = match self {
+            Broadcast::All => sessions.to_vec(),
+            Broadcast::Group(group) => sessions
+                .iter()
+                .copied()
+                .filter(|id| group.contains(id))
+                .collect(),
+        };
+        members.sort_unstable();
+        members
+    }
+
+    /// Where input typed into `source` goes. Input typed outside the group stays in its session.
+    pub fn targets(&self, source: SessionId, sessions: &[SessionId]) -> Vec<SessionId> {
+        let members = self.members(sessions);
+        if members.contains(&source) {
+            members
+        } else {
+            vec![source]
+        }
+    }
+
+    /// Add `session` to the hand-picked group, or remove it when it is already receiving
+    pub fn toggle_member(
+        current: Option<Broadcast>,
+        session: SessionId,
+        sessions: &[SessionId],
+    ) -> Option<Broadcast> {
+        let mut group: BTreeSet<SessionId> = match current {
+            Some(broadcast) => broadcast.members(sessions).into_iter().collect(),
+            None => BTreeSet::new(),
+        };
+        if !group.remove(&session) {
+            group.insert(session);
+        }
+        // 마지막 세션을 빼면 브로드캐스트 종료
+        (!group.is_empty()).then_some(Broadcast::Group(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets() {
+        let sessions = [0, 1, 2];
+        assert_eq!(Broadcast::All.targets(1, &sessions), vec![0, 1, 2]);
+
+        let group = Broadcast::Group([0, 2, 5].into_iter().collect());
+        assert_eq!(group.members(&sessions), vec![0, 2]);
+        assert_eq!(group.targets(2, &sessions), vec![0, 2]);
+        assert_eq!(group.targets(1, &sessions), vec![1]);
+    }
+
+    #[test]
+    fn test_toggle_member() {
+        let sessions = [0, 1, 2];
+        let group = Broadcast::toggle_member(None, 1, &sessions);
+        assert_eq!(group, Some(Broadcast::Group([1].into_iter().collect())));
+
+        let group = Broadcast::toggle_member(group, 2, &sessions);
+        assert_eq!(group, Some(Broadcast::Group([1, 2].into_iter().collect())));
+
+        // 전체에서 하나를 빼면 나머지가 그룹이 됨
+        let group = Broadcast::toggle_member(Some(Broadcast::All), 0, &sessions);
+        assert_eq!(group, Some(Broadcast::Group([1, 2].into_iter().collect())));
+
+        let group = Broadcast::toggle_member(group, 1, &sessions);
+        let group = Broadcast::toggle_member(group, 2, &sessions);
+        assert_eq!(group, None);
+    }
+}
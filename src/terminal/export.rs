@@ -0,0 +1,407 @@
+use alacritty_terminal::{
+    grid::{Dimensions, Grid, Row},
+    index::{Column, Line, Point},
+    selection::SelectionRange,
+    term::{
+        cell::{Cell, Flags},
+        color::Colors,
+    },
+    vte::ansi::{Color as AnsiColor, NamedColor},
+};
+use std::fmt::Write;
+
+use crate::config::ExportFormat;
+use crate::utils::color::ColorTheme;
+
+/// Cell flags that change how exported text looks
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+/// Cells to export: the whole history, or a selection
+#[derive(Debug, Clone, Copy)]
+pub struct ExportRange {
+    start: Point,
+    end: Point,
+    block: bool,
+}
+
+impl ExportRange {
+    /// Scrollback and screen
+    pub fn all(grid: &Grid<Cell>) -> Self {
+        Self {
+            start: Point::new(grid.topmost_line(), Column(0)),
+            end: Point::new(grid.bottommost_line(), grid.last_column()),
+            block: false,
+        }
+    }
+
+    pub fn selection(range: SelectionRange) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+            block: range.is_block,
+        }
+    }
+}
+
+/// Colors and names used by the styled formats
+pub struct ExportStyle<'a> {
+    pub theme: &'a ColorTheme,
+    pub colors: &'a Colors,
+    pub title: &'a str,
+    pub font_family: &'a str,
+}
+
+/// Consecutive cells of one row with the same look
+struct Run {
+    text: String,
+    fg: AnsiColor,
+    bg: AnsiColor,
+    flags: Flags,
+}
+
+/// One exported row; `wrapped` rows continue on the next row without a line break
+struct ExportedRow {
+    runs: Vec<Run>,
+    wrapped: bool,
+}
+
+pub fn export(
+    grid: &Grid<Cell>,
+    range: ExportRange,
+    format: ExportFormat,
+    style: &ExportStyle,
+) -> String {
+    let rows = rows(grid, range);
+    match format {
+        ExportFormat::Text => join_rows(&rows, |run, out| out.push_str(&run.text)),
+        ExportFormat::Ansi => ansi(&rows),
+        ExportFormat::Html => html(&rows, style),
+    }
+}
+
+fn rows(grid: &Grid<Cell>, range: ExportRange) -> Vec<ExportedRow> {
+    let start_line = range.start.line.max(grid.topmost_line());
+    let end_line = range.end.line.min(grid.bottommost_line());
+    let last_column = grid.last_column();
+    let mut rows = Vec::new();
+
+    for line in start_line.0..=end_line.0 {
+        let line = Line(line);
+        let first = if range.block || line == range.start.line {
+            range.start.column
+        } else {
+            Column(0)
+        };
+        let last = if range.block || line == range.end.line {
+            range.end.column.min(last_column)
+        } else {
+            last_column
+        };
+
+        let row = &grid[line];
+        // 뒤쪽 빈 칸은 내보내지 않음 (배경색이나 반전이 있는 칸은 남김)
+        let mut last_used = None;
+        for col in first.0..=last.0 {
+            let cell = &row[Column(col)];
+            let blank = (cell.c == ' ' || cell.c == '\0')
+                && cell.bg == AnsiColor::Named(NamedColor::Background)
+                && !cell
+                    .flags
+                    .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES);
+            if !blank {
+                last_used = Some(col);
+            }
+        }
+
+        let runs = last_used
+            .map(|last_used| row_runs(row, first.0..=last_used))
+            .unwrap_or_default();
+        let wrapped =
+            !range.block && last == last_column && row[last_column].flags.contains(Flags::WRAPLINE);
+        rows.push(ExportedRow { runs, wrapped });
+    }
+
+    // 화면 아래쪽의 빈 줄은 버림
+    while rows.last().is_some_and(|row| row.runs.is_empty()) {
+        rows.pop();
+    }
+    rows
+}
+
+fn row_runs(row: &Row<Cell>, columns: std::ops::RangeInclusive<usize>) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for col in columns {
+        let cell = &row[Column(col)];
+        if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            continue;
+        }
+        let flags = cell.flags & STYLE_FLAGS;
+        let c = if cell.c == '\0' { ' ' } else { cell.c };
+        match runs.last_mut() {
+            Some(run) if run.fg == cell.fg && run.bg == cell.bg && run.flags == flags => {
+                run.text.push(c);
+                run.text.extend(cell.zerowidth().unwrap_or(&[]).iter());
+            }
+            _ => {
+                let mut text = c.to_string();
+                text.extend(cell.zerowidth().unwrap_or(&[]).iter());
+                runs.push(Run {
+                    text,
+                    fg: cell.fg,
+                    bg: cell.bg,
+                    flags,
+                });
+            }
+        }
+    }
+    runs
+}
+
+/// Concatenate rows, writing each run with `write_run`
+fn join_rows(rows: &[ExportedRow], mut write_run: impl FnMut(&Run, &mut String)) -> String {
+    let mut out = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        for run in &row.runs {
+            write_run(run, &mut out);
+        }
+        if !row.wrapped && index + 1 < rows.len() {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn ansi(rows: &[ExportedRow]) -> String {
+    let mut out = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let mut styled = false;
+        for run in &row.runs {
+            if styled || !is_default(run) {
+                out.push_str(&sgr(run));
+                styled = !is_default(run);
+            }
+            out.push_str(&run.text);
+        }
+        // 줄마다 속성을 초기화해서 줄 단위로 잘라 봐도 색이 번지지 않게 함
+        if styled {
+            out.push_str("\x1b[0m");
+        }
+        if !row.wrapped && index + 1 < rows.len() {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn is_default(run: &Run) -> bool {
+    run.flags.is_empty()
+        && run.fg == AnsiColor::Named(NamedColor::Foreground)
+        && run.bg == AnsiColor::Named(NamedColor::Background)
+}
+
+/// SGR sequence that sets exactly the look of `run`
+fn sgr(run: &Run) -> String {
+    let mut params = vec!["0".to_string()];
+    for (flag, param) in [
+        (Flags::BOLD, "1"),
+        (Flags::DIM, "2"),
+        (Flags::ITALIC, "3"),
+        (Flags::UNDERLINE, "4"),
+        (Flags::DOUBLE_UNDERLINE, "4:2"),
+        (Flags::UNDERCURL, "4:3"),
+        (Flags::DOTTED_UNDERLINE, "4:4"),
+        (Flags::DASHED_UNDERLINE, "4:5"),
+        (Flags::INVERSE, "7"),
+        (Flags::HIDDEN, "8"),
+        (Flags::STRIKEOUT, "9"),
+    ] {
+        if run.flags.contains(flag) {
+            params.push(param.to_string());
+        }
+    }
+    params.extend(color_param(run.fg, 30));
+    params.extend(color_param(run.bg, 40));
+    format!("\x1b[{}m", params.join(";"))
+}
+
+/// SGR parameter for a foreground (`base` 30) or background (`base` 40) color
+fn color_param(color: AnsiColor, base: u8) -> Option<String> {
+    match color {
+        AnsiColor::Named(named) => {
+            let index = named as usize;
+            let dim = NamedColor::DimBlack as usize..=NamedColor::DimWhite as usize;
+            match index {
+                0..=7 => Some((base as usize + index).to_string()),
+                8..=15 => Some((base as usize + 60 + index - 8).to_string()),
+                i if dim.contains(&i) => {
+                    Some((base as usize + i - NamedColor::DimBlack as usize).to_string())
+                }
+                // 기본 전경/배경색은 0으로 이미 초기화됨
+                _ => None,
+            }
+        }
+        AnsiColor::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        AnsiColor::Spec(rgb) => Some(format!("{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b)),
+    }
+}
+
+fn html(rows: &[ExportedRow], style: &ExportStyle) -> String {
+    let theme = style.theme;
+    let colors = style.colors;
+    let foreground = theme.resolve_index(NamedColor::Foreground as usize, colors);
+    let background = theme.resolve_index(NamedColor::Background as usize, colors);
+
+    let body = join_rows(rows, |run, out| {
+        let mut fg = theme.convert_ansi_color(&run.fg, colors);
+        let mut bg = theme.convert_ansi_color(&run.bg, colors);
+        if run.flags.contains(Flags::INVERSE) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+        if run.flags.contains(Flags::DIM) {
+            fg = fg.blend(&bg, 0.3);
+        }
+        if run.flags.contains(Flags::HIDDEN) {
+            fg = bg;
+        }
+
+        let mut css = String::new();
+        if fg != foreground {
+            let _ = write!(css, "color:{};", fg.to_hex());
+        }
+        if bg != background {
+            let _ = write!(css, "background-color:{};", bg.to_hex());
+        }
+        if run.flags.contains(Flags::BOLD) {
+            css.push_str("font-weight:bold;");
+        }
+        if run.flags.contains(Flags::ITALIC) {
+            css.push_str("font-style:italic;");
+        }
+        let mut decorations = Vec::new();
+        if run.flags.intersects(Flags::ALL_UNDERLINES) {
+            decorations.push("underline");
+        }
+        if run.flags.contains(Flags::STRIKEOUT) {
+            decorations.push("line-through");
+        }
+        if !decorations.is_empty() {
+            let _ = write!(css, "text-decoration:{};", decorations.join(" "));
+        }
+
+        if css.is_empty() {
+            out.push_str(&escape_html(&run.text));
+        } else {
+            let _ = write!(
+                out,
+                "<span style=\"{}\">{}</span>",
+                css,
+                escape_html(&run.text)
+            );
+        }
+    });
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+         <body style=\"margin:0;background-color:{bg};\">\n\
+         <pre style=\"margin:0;padding:8px;color:{fg};background-color:{bg};font-family:{font},monospace;\">\
+         {body}</pre>\n</body>\n</html>\n",
+        title = escape_html(style.title),
+        fg = foreground.to_hex(),
+        bg = background.to_hex(),
+        font = css_font_family(style.font_family),
+        body = body,
+    )
+}
+
+fn css_font_family(family: &str) -> String {
+    format!("'{}'", family.replace(['\'', '"', '<', '>', ';'], ""))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::vte::ansi::Rgb;
+
+    fn grid_with(lines: &[&str]) -> Grid<Cell> {
+        let mut grid: Grid<Cell> = Grid::new(4, 12, 0);
+        for (line, text) in lines.iter().enumerate() {
+            for (col, c) in text.chars().enumerate() {
+                grid[Line(line as i32)][Column(col)].c = c;
+            }
+        }
+        grid
+    }
+
+    fn export_all(grid: &Grid<Cell>, format: ExportFormat) -> String {
+        let style = ExportStyle {
+            theme: &ColorTheme::default(),
+            colors: &Colors::default(),
+            title: "a <b>",
+            font_family: "D2Coding",
+        };
+        export(grid, ExportRange::all(grid), format, &style)
+    }
+
+    #[test]
+    fn test_text_keeps_inner_spaces() {
+        let mut grid = grid_with(&["a    b", "", "0123456789ab", "cd"]);
+        grid[Line(2)][Column(11)].flags.insert(Flags::WRAPLINE);
+        assert_eq!(
+            export_all(&grid, ExportFormat::Text),
+            "a    b\n\n0123456789abcd"
+        );
+
+        let range = ExportRange {
+            start: Point::new(Line(0), Column(2)),
+            end: Point::new(Line(2), Column(3)),
+            block: true,
+        };
+        let style = ExportStyle {
+            theme: &ColorTheme::default(),
+            colors: &Colors::default(),
+            title: "",
+            font_family: "",
+        };
+        assert_eq!(export(&grid, range, ExportFormat::Text, &style), "\n\n23");
+    }
+
+    #[test]
+    fn test_ansi_and_html_colors() {
+        let mut grid = grid_with(&["ok <err>"]);
+        let red = Rgb { r: 255, g: 0, b: 0 };
+        for col in 3..8 {
+            grid[Line(0)][Column(col)].fg = AnsiColor::Spec(red);
+            grid[Line(0)][Column(col)].flags.insert(Flags::BOLD);
+        }
+
+        assert_eq!(
+            export_all(&grid, ExportFormat::Ansi),
+            "ok \x1b[0;1;38;2;255;0;0m<err>\x1b[0m"
+        );
+        let html = export_all(&grid, ExportFormat::Html);
+        assert!(html.contains("<title>a &lt;b&gt;</title>"));
+        assert!(html.contains(
+            "ok <span style=\"color:#ff0000;font-weight:bold;\">&lt;err&gt;</span></pre>"
+        ));
+    }
+}
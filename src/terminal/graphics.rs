@@ -0,0 +1,2169 @@
+use alacritty_terminal::event::{Event, EventListener, WindowSize};
+use alacritty_terminal::grid::{Dimensions, Grid, GridCell};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Hyperlink as CellHyperlink};
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vte::ansi::{
+    Attr, CharsetIndex, ClearMode, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
+    KeyboardModesApplyBehavior, LineClearMode, Mode, NamedPrivateMode, PrivateMode, Rgb,
+    StandardCharset, TabulationClearMode,
+};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use unicode_width::UnicodeWidthChar;
+
+use super::kitty::{self, Action, KittyCommand, PlaceholderCell};
+use super::{queries, sixel};
+use crate::config::GraphicsConfig;
+
+/// URI prefix of the links that mark anchor cells while the grid moves in ways not
+/// followed call by call (reflow on resize, the main screen behind the alternate one)
+const MARK_LINK_PREFIX: &str = "sterm-anchor:";
+
+/// Stands in the filtered output for what the [`GraphicsFilter`] left for [`ImageAnchors`]
+/// to do at that point; the same sequence from the program is dropped
+const DEFERRED_MARKER: &[u8] = b"\x1b[<c";
+
+/// Longest DCS header (`ESC P` and parameters) or CSI looked at before giving up on it
+const MAX_HEADER: usize = 64;
+
+/// Largest Sixel sequence decoded; anything longer is dropped
+const MAX_SIXEL_BYTES: usize = 32 * 1024 * 1024;
+
+/// Largest kitty graphics command, all chunks together; anything longer is dropped
+const MAX_KITTY_BYTES: usize = 128 * 1024 * 1024;
+
+/// Longest XTGETTCAP request answered
+const MAX_TERMCAP_BYTES: usize = 4096;
+
+/// Most cells a kitty placement may be scaled into (`c`, `r`)
+const MAX_PLACEMENT_CELLS: u32 = 1000;
+
+/// Image and placement ids are unique across sessions so the UI can cache decoded images by id
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_id() -> u64 {
+    NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Decoded image, drawn through one or more [`Placement`]s
+pub struct GraphicsImage {
+    pub id: u64,
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+impl fmt::Debug for GraphicsImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphicsImage")
+            .field("id", &self.id)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+/// Image (or part of it) drawn from an anchor cell, one cell row at a time
+#[derive(Debug)]
+pub struct Placement {
+    pub id: u64,
+    pub image: Arc<GraphicsImage>,
+    /// Part of the image drawn: x, y, width, height in image pixels
+    pub source: [u32; 4],
+    // 기준 셀 모서리에서의 위치와 그릴 크기 (배치할 때의 셀 크기 기준 픽셀)
+    pub offset: (u32, u32),
+    pub size: (u32, u32),
+    /// Cell size when placed; the placement scales with the font after that
+    pub cell_size: (u32, u32),
+    /// Negative values draw under the text
+    pub z_index: i32,
+    /// kitty image and placement ids, 0 when not given (and for Sixel)
+    pub client_id: u32,
+    pub client_placement: u32,
+}
+
+impl Placement {
+    pub fn rows(&self) -> u32 {
+        (self.offset.1 + self.size.1).div_ceil(self.cell_size.1)
+    }
+
+    pub fn columns(&self) -> u32 {
+        (self.offset.0 + self.size.0).div_ceil(self.cell_size.0)
+    }
+}
+
+/// One cell row of a placement as drawn on the screen
+#[derive(Debug, Clone)]
+pub struct ImageSlice {
+    pub image: Arc<GraphicsImage>,
+    // 화면 위치와 크기 (픽셀, 현재 셀 크기에 맞춰 확대/축소)
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    /// Part of the image shown in this slice
+    pub source_x: u32,
+    pub source_y: u32,
+    pub source_width: u32,
+    pub source_height: u32,
+    pub z_index: i32,
+}
+
+impl ImageSlice {
+    /// Slice for `row` of `placement` whose anchor cell is at `x`, `y`, scaled to the current cell size
+    pub fn new(
+        placement: &Placement,
+        row: u32,
+        x: i32,
+        y: i32,
+        cell_width: i32,
+        cell_height: i32,
+    ) -> Option<Self> {
+        let (placed_width, placed_height) = placement.cell_size;
+        let (offset_x, offset_y) = placement.offset;
+        let (width, height) = placement.size;
+        // 이 줄에 걸친 부분 (배치 당시 픽셀, 기준 셀 위쪽 기준)
+        let row_top = row.checked_mul(placed_height)?;
+        let top = row_top.max(offset_y);
+        let bottom = (row_top + placed_height).min(offset_y + height);
+        if top >= bottom {
+            return None;
+        }
+
+        let [source_x, source_y, source_width, source_height] = placement.source;
+        let to_source = |placed_y: u32| {
+            source_y + ((placed_y - offset_y) as u64 * source_height as u64 / height as u64) as u32
+        };
+        let scale_x = cell_width as f32 / placed_width as f32;
+        let scale_y = cell_height as f32 / placed_height as f32;
+        let slice_source_y = to_source(top);
+        Some(Self {
+            image: placement.image.clone(),
+            x: x + (offset_x as f32 * scale_x).round() as i32,
+            y: y + ((top - row_top) as f32 * scale_y).round() as i32,
+            width: (width as f32 * scale_x).round() as i32,
+            height: ((bottom - top) as f32 * scale_y).round() as i32,
+            source_x,
+            source_y: slice_source_y,
+            source_width,
+            source_height: (to_source(bottom) - slice_source_y).max(1),
+            z_index: placement.z_index,
+        })
+    }
+}
+
+/// Placement row tied to a grid cell, which it is drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    pub placement: u64,
+    pub row: u32,
+    /// Grid line, negative in the scrollback
+    pub line: i32,
+    pub column: usize,
+}
+
+/// Anchors whose cells carry marker links in place of their own while the grid moves
+/// in ways the parser calls don't show (reflow on resize, the main screen hidden behind
+/// the alternate one); the markers are found again afterwards
+struct MarkedAnchors {
+    /// 프로그램이 같은 링크를 미리 써 둘 수 없도록 추측할 수 없는 번호
+    nonce: u64,
+    /// 표시 번호 순서: 표시할 때의 앵커와 셀의 원래 링크 (다시 찾으면 비움)
+    anchors: Vec<Option<(Anchor, Option<CellHyperlink>)>>,
+}
+
+impl MarkedAnchors {
+    fn mark(grid: &mut Grid<Cell>, anchors: Vec<Anchor>) -> Self {
+        let nonce = RandomState::new().build_hasher().finish();
+        let mut marked = Vec::with_capacity(anchors.len());
+        for anchor in anchors {
+            let Some(point) = grid_point(grid, &anchor) else {
+                continue;
+            };
+            let cell = &mut grid[point];
+            let uri = format!("{}{}:{}", MARK_LINK_PREFIX, nonce, marked.len());
+            marked.push(Some((anchor, cell.hyperlink())));
+            cell.set_hyperlink(Some(CellHyperlink::new(None::<String>, uri)));
+        }
+        Self {
+            nonce,
+            anchors: marked,
+        }
+    }
+
+    fn placements(&self) -> Vec<u64> {
+        self.anchors
+            .iter()
+            .flatten()
+            .map(|(anchor, _)| anchor.placement)
+            .collect()
+    }
+
+    /// Put the cells' own links back, returning where the marked anchors are now.
+    /// The whole grid is searched only for markers that moved.
+    fn unmark(mut self, grid: &mut Grid<Cell>) -> Vec<Anchor> {
+        let prefix = format!("{}{}:", MARK_LINK_PREFIX, self.nonce);
+        let mut found = Vec::new();
+        let unmark = |cell: &mut Cell, point: Point, marked: &mut Self, found: &mut Vec<_>| {
+            let link = cell.hyperlink()?;
+            let index = link
+                .uri()
+                .strip_prefix(prefix.as_str())?
+                .parse::<usize>()
+                .ok()?;
+            let (anchor, hyperlink) = marked.anchors.get_mut(index)?.take()?;
+            cell.set_hyperlink(hyperlink);
+            found.push(Anchor {
+                line: point.line.0,
+                column: point.column.0,
+                ..anchor
+            });
+            Some(())
+        };
+        // 격자가 움직이지 않았으면 표시한 자리에 그대로 있음
+        let marked_at: Vec<Anchor> = self.anchors.iter().flatten().map(|(a, _)| *a).collect();
+        for anchor in marked_at {
+            if let Some(point) = grid_point(grid, &anchor) {
+                unmark(&mut grid[point], point, &mut self, &mut found);
+            }
+        }
+        if self.anchors.iter().any(Option::is_some) {
+            for line in grid.topmost_line().0..=grid.bottommost_line().0 {
+                for column in 0..grid.columns() {
+                    let point = Point::new(Line(line), Column(column));
+                    unmark(&mut grid[point], point, &mut self, &mut found);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Cell of `anchor`, if the grid still has it
+fn grid_point(grid: &Grid<Cell>, anchor: &Anchor) -> Option<Point> {
+    let point = Point::new(Line(anchor.line), Column(anchor.column));
+    let lines = grid.topmost_line()..=grid.bottommost_line();
+    (lines.contains(&point.line) && point.column < grid.columns()).then_some(point)
+}
+
+/// Images and placements of one session, and where the placements are anchored.
+/// Shared between the PTY reader, which adds them and keeps the anchors in step with the
+/// grid as it parses, and the session, which draws them.
+#[derive(Clone)]
+pub struct ImageStore {
+    inner: Arc<Mutex<StoreInner>>,
+}
+
+struct StoreInner {
+    /// id 순서 = 추가된 순서
+    images: BTreeMap<u64, Arc<GraphicsImage>>,
+    placements: BTreeMap<u64, Arc<Placement>>,
+    /// 셀에 고정하지 않고 자리 표시 문자(U=1)로 그리는 kitty 배치. 격자 정리 대상이 아님
+    virtual_placements: BTreeMap<u64, Arc<Placement>>,
+    /// kitty 이미지 id → 이미지
+    client_images: HashMap<u32, u64>,
+    /// kitty 이미지 번호(I) → 그 번호로 마지막에 받은 이미지의 kitty id
+    numbers: HashMap<u32, u32>,
+    bytes: usize,
+    memory_limit: usize,
+    /// 지금 화면의 셀에 고정된 배치 줄
+    anchors: Vec<Anchor>,
+    /// 보조 화면을 쓰는 동안 표시해 둔 주 화면의 앵커
+    hidden: Option<MarkedAnchors>,
+    /// Term의 스크롤 영역 (None이면 화면 전체)
+    scroll_region: Option<Range<i32>>,
+    /// 화면 위치로 고르는 kitty 삭제 명령 (격자를 아는 세션이 처리)
+    deletes: Vec<KittyCommand>,
+    /// 동기화 출력(mode 2026) 중. 파서가 쌓아 둔 출력을 나중에 다시 읽을 때도 켜져 있음
+    synchronized: bool,
+    /// 필터가 출력에 표시를 남기고 미뤄 둔 일 (표시 순서대로)
+    deferred: VecDeque<Deferred>,
+}
+
+impl StoreInner {
+    fn remove_image(&mut self, id: u64) {
+        if let Some(image) = self.images.remove(&id) {
+            self.bytes -= image.rgba.len();
+        }
+        self.placements
+            .retain(|_, placement| placement.image.id != id);
+        self.virtual_placements
+            .retain(|_, placement| placement.image.id != id);
+        self.client_images.retain(|_, image| *image != id);
+        self.forget_removed();
+    }
+
+    fn forget_removed(&mut self) {
+        let placements = &self.placements;
+        self.anchors
+            .retain(|anchor| placements.contains_key(&anchor.placement));
+    }
+
+    /// Move the anchors with `moved`, dropping those it returns false for, then the
+    /// placements left without any
+    fn move_anchors(&mut self, mut moved: impl FnMut(&mut Anchor) -> bool) {
+        let mut lost = Vec::new();
+        self.anchors.retain_mut(|anchor| {
+            let kept = moved(anchor);
+            if !kept {
+                lost.push(anchor.placement);
+            }
+            kept
+        });
+        self.forget_unanchored(lost);
+    }
+
+    /// Remove those of `ids` that are no longer anchored anywhere
+    fn forget_unanchored(&mut self, mut ids: Vec<u64>) {
+        if ids.is_empty() {
+            return;
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        let hidden = self.hidden.as_ref().map(MarkedAnchors::placements);
+        ids.retain(|id| {
+            !self.anchors.iter().any(|anchor| anchor.placement == *id)
+                && !hidden.as_ref().is_some_and(|hidden| hidden.contains(id))
+        });
+        if !ids.is_empty() {
+            log::debug!("{} image placements lost their anchors", ids.len());
+            self.remove_placements(&ids, false);
+        }
+    }
+
+    /// Remove placements, then the images they leave without any placement.
+    /// kitty images stay for later placements unless `free` is set.
+    fn remove_placements(&mut self, ids: &[u64], free: bool) {
+        let mut orphans = HashSet::new();
+        for id in ids {
+            let removed = self
+                .placements
+                .remove(id)
+                .or_else(|| self.virtual_placements.remove(id));
+            if let Some(placement) = removed {
+                if free || placement.client_id == 0 {
+                    orphans.insert(placement.image.id);
+                }
+            }
+        }
+        for placement in self
+            .placements
+            .values()
+            .chain(self.virtual_placements.values())
+        {
+            orphans.remove(&placement.image.id);
+        }
+        for id in orphans {
+            self.remove_image(id);
+        }
+        self.forget_removed();
+    }
+}
+
+impl ImageStore {
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(StoreInner {
+                images: BTreeMap::new(),
+                placements: BTreeMap::new(),
+                virtual_placements: BTreeMap::new(),
+                client_images: HashMap::new(),
+                numbers: HashMap::new(),
+                bytes: 0,
+                memory_limit,
+                anchors: Vec::new(),
+                hidden: None,
+                scroll_region: None,
+                deletes: Vec::new(),
+                synchronized: false,
+                deferred: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Keep an image, dropping the oldest ones while over the memory limit.
+    /// An image sent with a kitty id replaces the one that had it, along with its placements.
+    pub fn insert(
+        &self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+        client_id: u32,
+    ) -> Result<Arc<GraphicsImage>> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Image store is poisoned"))?;
+        if rgba.len() > inner.memory_limit {
+            return Err(anyhow::anyhow!(
+                "{}x{} image is over the memory limit",
+                width,
+                height
+            ));
+        }
+        let image = Arc::new(GraphicsImage {
+            id: next_id(),
+            width,
+            height,
+            rgba,
+        });
+        inner.bytes += image.rgba.len();
+        inner.images.insert(image.id, image.clone());
+        if client_id != 0 {
+            if let Some(old) = inner.client_images.insert(client_id, image.id) {
+                inner.remove_image(old);
+            }
+        }
+        while inner.bytes > inner.memory_limit && inner.images.len() > 1 {
+            if let Some(&oldest) = inner.images.keys().next() {
+                log::debug!("Image memory limit reached, dropping image {}", oldest);
+                inner.remove_image(oldest);
+            }
+        }
+        Ok(image)
+    }
+
+    /// Pick a kitty id for an image sent with only a number (`I`)
+    pub fn assign_client_id(&self, number: u32) -> u32 {
+        let Ok(mut inner) = self.inner.lock() else {
+            return 0;
+        };
+        let id = inner
+            .client_images
+            .keys()
+            .chain(inner.numbers.values())
+            .max()
+            .map_or(1, |max| max.saturating_add(1));
+        inner.numbers.insert(number, id);
+        id
+    }
+
+    /// kitty image by id, or by number when the id is 0
+    pub fn client_image(&self, client_id: u32, number: u32) -> Option<(u32, Arc<GraphicsImage>)> {
+        let inner = self.inner.lock().ok()?;
+        let client_id = match client_id {
+            0 => *inner.numbers.get(&number)?,
+            id => id,
+        };
+        let image = inner.images.get(inner.client_images.get(&client_id)?)?;
+        Some((client_id, image.clone()))
+    }
+
+    /// Add a placement; a kitty placement replaces the one with the same image and placement ids
+    pub fn place(&self, placement: Placement) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if placement.client_id != 0 && placement.client_placement != 0 {
+            let replaced: Vec<u64> = inner
+                .placements
+                .values()
+                .filter(|old| {
+                    old.client_id == placement.client_id
+                        && old.client_placement == placement.client_placement
+                })
+                .map(|old| old.id)
+                .collect();
+            inner.remove_placements(&replaced, false);
+        }
+        inner.placements.insert(placement.id, Arc::new(placement));
+    }
+
+    pub fn placement(&self, id: u64) -> Option<Arc<Placement>> {
+        self.inner.lock().ok()?.placements.get(&id).cloned()
+    }
+
+    /// Add a placement drawn where placeholder cells point at it (kitty `U=1`),
+    /// replacing the one with the same image and placement ids
+    pub fn place_virtual(&self, placement: Placement) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let replaced: Vec<u64> = inner
+            .virtual_placements
+            .values()
+            .filter(|old| {
+                old.client_id == placement.client_id
+                    && old.client_placement == placement.client_placement
+            })
+            .map(|old| old.id)
+            .collect();
+        inner.remove_placements(&replaced, false);
+        inner
+            .virtual_placements
+            .insert(placement.id, Arc::new(placement));
+    }
+
+    /// Placeholder placement of a kitty image; placement id 0 picks the newest one
+    pub fn virtual_placement(&self, client_id: u32, placement_id: u32) -> Option<Arc<Placement>> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .virtual_placements
+            .values()
+            .rev()
+            .find(|placement| {
+                placement.client_id == client_id
+                    && (placement_id == 0 || placement.client_placement == placement_id)
+            })
+            .cloned()
+    }
+
+    /// Remove the placements `matches` picks; `free` also drops the kitty images left unplaced
+    pub fn delete(&self, matches: impl Fn(&Placement) -> bool, free: bool) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let ids: Vec<u64> = inner
+            .placements
+            .values()
+            .chain(inner.virtual_placements.values())
+            .filter(|placement| matches(placement))
+            .map(|placement| placement.id)
+            .collect();
+        inner.remove_placements(&ids, free);
+    }
+
+    /// Drop a kitty image and all its placements
+    pub fn free_client_image(&self, client_id: u32) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if let Some(id) = inner.client_images.get(&client_id).copied() {
+            inner.remove_image(id);
+        }
+    }
+
+    /// Leave a deletion by screen position for the session to apply
+    pub fn defer_delete(&self, command: KittyCommand) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.deletes.push(command);
+        }
+    }
+
+    pub fn take_deletes(&self) -> Vec<KittyCommand> {
+        self.inner
+            .lock()
+            .map(|mut inner| std::mem::take(&mut inner.deletes))
+            .unwrap_or_default()
+    }
+
+    fn defer(&self, deferred: Deferred) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.deferred.push_back(deferred);
+        }
+    }
+
+    /// Anchors on the screen in use
+    pub fn anchors(&self) -> Vec<Anchor> {
+        self.inner
+            .lock()
+            .map(|inner| inner.anchors.clone())
+            .unwrap_or_default()
+    }
+
+    /// Resize `term`, taking the anchors along as lines are rewrapped or pushed into the
+    /// scrollback. Must be called with the terminal locked, like the parser runs.
+    pub fn resize<T, S: Dimensions>(&self, term: &mut Term<T>, size: S) {
+        let Ok(mut inner) = self.inner.lock() else {
+            term.resize(size);
+            return;
+        };
+        // Term은 크기가 바뀔 때만 스크롤 영역을 되돌림
+        if (size.columns(), size.screen_lines()) != (term.columns(), term.screen_lines()) {
+            inner.scroll_region = None;
+        }
+        if inner.anchors.is_empty() {
+            term.resize(size);
+            return;
+        }
+        let marked = MarkedAnchors::mark(term.grid_mut(), std::mem::take(&mut inner.anchors));
+        let placements = marked.placements();
+        term.resize(size);
+        inner.anchors = marked.unmark(term.grid_mut());
+        inner.forget_unanchored(placements);
+    }
+}
+
+/// Whether a deletion by position (`d=c`, `p`, `q`, `x`, `y`) hits `placement`,
+/// whose top-left cell is at screen `line`, `column`. `cursor` is (line, column).
+pub fn deleted_at(
+    command: &KittyCommand,
+    placement: &Placement,
+    line: i32,
+    column: usize,
+    cursor: (i32, usize),
+) -> bool {
+    let lines = line..line + placement.rows() as i32;
+    let columns = column..column + placement.columns() as usize;
+    // x, y는 1부터 세는 셀 좌표
+    let x = (command.source_x as usize).wrapping_sub(1);
+    let y = command.source_y as i32 - 1;
+    match command.delete.to_ascii_lowercase() {
+        b'c' => lines.contains(&cursor.0) && columns.contains(&cursor.1),
+        b'p' => lines.contains(&y) && columns.contains(&x),
+        b'q' => lines.contains(&y) && columns.contains(&x) && placement.z_index == command.z_index,
+        b'x' => columns.contains(&x),
+        b'y' => lines.contains(&y),
+        _ => false,
+    }
+}
+
+/// Forward parser calls to the terminal unchanged
+macro_rules! forward {
+    ($($name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $name(&mut self, $($arg: $ty),*) {
+                Handler::$name(self.term, $($arg),*)
+            }
+        )*
+    };
+}
+
+/// The terminal as the parser sees it while reading PTY output. Placement rows the
+/// [`GraphicsFilter`] left a marker for are anchored to the cursor cell here, and the
+/// anchors follow the calls that scroll lines or move cells the way [`Term`] moves them,
+/// going with the cells they're on. Replies the filter left behind are written when the
+/// parser reaches their marker, and DA2, DA3 and synchronized output (mode 2026) are
+/// answered here, all through `events` like the terminal's own replies so they go back in
+/// the order the queries came in. The store stays locked while parsing.
+pub struct ImageAnchors<'a, T> {
+    term: &'a mut Term<T>,
+    store: MutexGuard<'a, StoreInner>,
+    events: &'a T,
+}
+
+impl<'a, T: EventListener> ImageAnchors<'a, T> {
+    pub fn new(term: &'a mut Term<T>, store: &'a ImageStore, events: &'a T) -> Self {
+        Self {
+            term,
+            store: store.inner.lock().unwrap_or_else(PoisonError::into_inner),
+            events,
+        }
+    }
+
+    fn reply(&self, reply: String) {
+        self.events.send_event(Event::PtyWrite(reply));
+    }
+
+    fn cursor(&self) -> Point {
+        self.term.grid().cursor.point
+    }
+
+    fn region(&self) -> Range<i32> {
+        let screen_lines = self.term.screen_lines() as i32;
+        self.store.scroll_region.clone().unwrap_or(0..screen_lines)
+    }
+
+    /// Anchor the placement's rows going down a line between them, then move the cursor
+    fn place(&mut self, placement: u64, rows: u32, cursor: CursorMove) {
+        for row in 0..rows {
+            if row > 0 {
+                self.linefeed();
+            }
+            // 표시를 읽기 전에 지워진 배치는 자리만 차지함
+            if self.store.placements.contains_key(&placement) {
+                let point = self.cursor();
+                self.store.anchors.push(Anchor {
+                    placement,
+                    row,
+                    line: point.line.0,
+                    column: point.column.0,
+                });
+            }
+        }
+        match cursor {
+            CursorMove::Below => self.linefeed(),
+            CursorMove::Right(columns) => self.term.move_forward(columns as usize),
+            // 내려간 만큼 올라감 (스크롤됐어도 기준 셀 줄로 돌아감)
+            CursorMove::Stay => {
+                for _ in 1..rows {
+                    self.reverse_index();
+                }
+            }
+        }
+    }
+
+    /// Follow lines `origin..` of the scrolling region going up by `lines`.
+    /// From the top of the screen they go into the scrollback, otherwise they're gone.
+    fn scrolled_up(&mut self, origin: i32, lines: usize) {
+        let region = self.region();
+        let lines = lines.min((region.end - region.start) as usize) as i32;
+        let (moved, top) = if origin == 0 {
+            (i32::MIN..region.end, -(self.term.history_size() as i32))
+        } else {
+            (origin..region.end, origin)
+        };
+        self.store.move_anchors(|anchor| {
+            if !moved.contains(&anchor.line) {
+                return true;
+            }
+            anchor.line -= lines;
+            anchor.line >= top
+        });
+    }
+
+    /// Follow lines `origin..` of the scrolling region going down by `lines`
+    fn scrolled_down(&mut self, origin: i32, lines: usize) {
+        let region = self.region();
+        let lines = lines
+            .min((region.end - region.start) as usize)
+            .min((region.end - origin).max(0) as usize) as i32;
+        self.store.move_anchors(|anchor| {
+            if !(origin..region.end).contains(&anchor.line) {
+                return true;
+            }
+            anchor.line += lines;
+            anchor.line < region.end
+        });
+    }
+
+    /// Drop the anchors on the cells of screen `lines` in `columns`
+    fn cleared(&mut self, lines: Range<i32>, columns: Range<usize>) {
+        self.store.move_anchors(|anchor| {
+            !(lines.contains(&anchor.line) && columns.contains(&anchor.column))
+        });
+    }
+
+    /// Follow the cells of the cursor line from `column` on moving by `by` columns;
+    /// the ones pushed past the end are gone
+    fn shifted(&mut self, column: usize, by: isize) {
+        let (line, columns) = (self.cursor().line.0, self.term.columns());
+        self.store.move_anchors(|anchor| {
+            if anchor.line != line || anchor.column < column {
+                return true;
+            }
+            anchor.column = anchor.column.wrapping_add_signed(by);
+            anchor.column < columns
+        });
+    }
+
+    /// Run a call that feeds a line, which scrolls at the bottom of the scrolling region
+    fn feeding(&mut self, call: impl FnOnce(&mut Term<T>)) {
+        let region = self.region();
+        let scrolls = self.cursor().line.0 + 1 == region.end;
+        call(self.term);
+        if scrolls {
+            self.scrolled_up(region.start, 1);
+        }
+    }
+
+    /// Show the main screen's anchors again when leaving the alternate screen,
+    /// dropping those of the alternate screen
+    fn show_main_screen(&mut self) {
+        let mut lost: Vec<u64> = self.store.anchors.drain(..).map(|a| a.placement).collect();
+        if let Some(hidden) = self.store.hidden.take() {
+            lost.extend(hidden.placements());
+            self.store.anchors = hidden.unmark(self.term.grid_mut());
+        }
+        self.store.forget_unanchored(lost);
+    }
+
+    /// DECCOLM resets the scrolling region and clears the screen
+    fn column_mode(&mut self) {
+        self.store.scroll_region = None;
+        let screen_lines = self.term.screen_lines() as i32;
+        self.cleared(0..screen_lines, 0..usize::MAX);
+    }
+
+    /// Forget everything the terminal resets
+    fn reset(&mut self) {
+        self.store.scroll_region = None;
+        let hidden = self.store.hidden.take();
+        self.store.move_anchors(|_| false);
+        if let Some(hidden) = hidden {
+            self.store.forget_unanchored(hidden.placements());
+        }
+    }
+}
+
+impl<T: EventListener> Handler for ImageAnchors<'_, T> {
+    fn input(&mut self, c: char) {
+        let width = c.width().unwrap_or(0);
+        if self.store.anchors.is_empty() || width == 0 {
+            return self.term.input(c);
+        }
+        let region = self.region();
+        let (cursor, columns) = (&self.term.grid().cursor, self.term.columns());
+        let (mut line, mut column) = (cursor.point.line.0, cursor.point.column.0);
+        let line_wrap = self.term.mode().contains(TermMode::LINE_WRAP);
+        let insert = self.term.mode().contains(TermMode::INSERT);
+        // 줄 끝에서 다음 줄로 넘어가면 스크롤될 수 있음
+        let wraps = line_wrap && (cursor.input_needs_wrap || width == 2 && column + 1 >= columns);
+        let scrolls = wraps && line + 1 == region.end;
+        if line_wrap && cursor.input_needs_wrap {
+            if line + 1 != region.end && line + 1 < self.term.screen_lines() as i32 {
+                line += 1;
+            }
+            column = 0;
+        }
+        self.term.input(c);
+        if scrolls {
+            self.scrolled_up(region.start, 1);
+        }
+        // 삽입 모드에서는 글자를 쓰기 전에 줄의 나머지를 밀어냄
+        if insert && column + width < columns && self.cursor().line.0 == line {
+            self.shifted(column, width as isize);
+        }
+    }
+
+    fn linefeed(&mut self) {
+        self.feeding(|term| term.linefeed());
+    }
+
+    fn newline(&mut self) {
+        self.feeding(|term| term.newline());
+    }
+
+    fn reverse_index(&mut self) {
+        let region = self.region();
+        let scrolls = self.cursor().line.0 == region.start;
+        self.term.reverse_index();
+        if scrolls {
+            self.scrolled_down(region.start, 1);
+        }
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        self.term.scroll_up(lines);
+        self.scrolled_up(self.region().start, lines);
+    }
+
+    fn scroll_down(&mut self, lines: usize) {
+        self.term.scroll_down(lines);
+        self.scrolled_down(self.region().start, lines);
+    }
+
+    fn insert_blank_lines(&mut self, lines: usize) {
+        let line = self.cursor().line.0;
+        self.term.insert_blank_lines(lines);
+        if self.region().contains(&line) {
+            self.scrolled_down(line, lines);
+        }
+    }
+
+    fn delete_lines(&mut self, lines: usize) {
+        let line = self.cursor().line.0;
+        let lines = lines.min(self.term.screen_lines() - line as usize);
+        self.term.delete_lines(lines);
+        if lines > 0 && self.region().contains(&line) {
+            self.scrolled_up(line, lines);
+        }
+    }
+
+    fn insert_blank(&mut self, count: usize) {
+        let column = self.cursor().column.0;
+        let count = count.min(self.term.columns() - column);
+        self.term.insert_blank(count);
+        self.shifted(column, count as isize);
+    }
+
+    fn erase_chars(&mut self, count: usize) {
+        let Point { line, column } = self.cursor();
+        let end = (column.0 + count).min(self.term.columns());
+        self.term.erase_chars(count);
+        self.cleared(line.0..line.0 + 1, column.0..end);
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let Point { line, column } = self.cursor();
+        let columns = self.term.columns();
+        let count = count.min(columns);
+        let end = (column.0 + count).min(columns - 1);
+        self.term.delete_chars(count);
+        self.cleared(line.0..line.0 + 1, column.0..end);
+        self.shifted(end, column.0 as isize - end as isize);
+        self.cleared(line.0..line.0 + 1, columns - count..columns);
+    }
+
+    fn clear_line(&mut self, mode: LineClearMode) {
+        let Point { line, column } = self.cursor();
+        let columns = match mode {
+            LineClearMode::Right if self.term.grid().cursor.input_needs_wrap => 0..0,
+            LineClearMode::Right => column.0..usize::MAX,
+            LineClearMode::Left => 0..column.0 + 1,
+            LineClearMode::All => 0..usize::MAX,
+        };
+        self.term.clear_line(mode);
+        self.cleared(line.0..line.0 + 1, columns);
+    }
+
+    fn clear_screen(&mut self, mode: ClearMode) {
+        let Point { line, column } = self.cursor();
+        let screen_lines = self.term.screen_lines() as i32;
+        let alt_screen = self.term.mode().contains(TermMode::ALT_SCREEN);
+        let history = self.term.history_size();
+        let cursor_line = line.0..line.0 + 1;
+        let all = 0..usize::MAX;
+        // 주 화면 전체를 지우면 내용이 있는 줄까지 먼저 스크롤백으로 올라감
+        let (scrolled, cleared) = match &mode {
+            ClearMode::Above if line.0 > 1 => {
+                (0, vec![(0..line.0, all), (cursor_line, 0..column.0 + 1)])
+            }
+            ClearMode::Above => (0, vec![(cursor_line, 0..column.0 + 1)]),
+            ClearMode::Below => (
+                0,
+                vec![
+                    (cursor_line, column.0..usize::MAX),
+                    (line.0 + 1..screen_lines, all),
+                ],
+            ),
+            ClearMode::All if alt_screen || self.store.anchors.is_empty() => {
+                (0, vec![(0..screen_lines, all)])
+            }
+            ClearMode::All => (
+                written_lines(self.term, history),
+                vec![(0..screen_lines, all)],
+            ),
+            ClearMode::Saved if history > 0 => (0, vec![(i32::MIN..0, all)]),
+            ClearMode::Saved => (0, Vec::new()),
+        };
+        self.term.clear_screen(mode);
+        if scrolled > 0 {
+            self.scrolled_up(0, scrolled);
+        }
+        for (lines, columns) in cleared {
+            self.cleared(lines, columns);
+        }
+    }
+
+    fn reset_state(&mut self) {
+        self.term.reset_state();
+        self.reset();
+    }
+
+    fn decaln(&mut self) {
+        self.term.decaln();
+        let screen_lines = self.term.screen_lines() as i32;
+        self.cleared(0..screen_lines, 0..usize::MAX);
+    }
+
+    fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
+        let screen_lines = self.term.screen_lines();
+        let bottom = bottom.unwrap_or(screen_lines);
+        if top < bottom {
+            let start = (top as i32 - 1).min(screen_lines as i32);
+            self.store.scroll_region = Some(start..bottom.min(screen_lines) as i32);
+        }
+        self.term.set_scrolling_region(top, bottom.into());
+    }
+
+    // 보조 화면에 있는 동안 주 화면의 앵커는 셀에 표시해 두고, 떠날 때 다시 찾음.
+    // 파서는 동기화 출력의 시작과 끝(시간 초과 포함)을 mode 2026으로 알려 줌
+    fn set_private_mode(&mut self, mode: PrivateMode) {
+        let alt_screen = self.term.mode().contains(TermMode::ALT_SCREEN);
+        match mode {
+            PrivateMode::Named(NamedPrivateMode::SyncUpdate) => self.store.synchronized = true,
+            PrivateMode::Named(NamedPrivateMode::SwapScreenAndSetRestoreCursor)
+                if !alt_screen && !self.store.anchors.is_empty() =>
+            {
+                let anchors = std::mem::take(&mut self.store.anchors);
+                self.store.hidden = Some(MarkedAnchors::mark(self.term.grid_mut(), anchors));
+            }
+            _ => {}
+        }
+        self.term.set_private_mode(mode);
+        if mode == PrivateMode::Named(NamedPrivateMode::ColumnMode) {
+            self.column_mode();
+        }
+    }
+
+    fn unset_private_mode(&mut self, mode: PrivateMode) {
+        let alt_screen = self.term.mode().contains(TermMode::ALT_SCREEN);
+        self.term.unset_private_mode(mode);
+        match mode {
+            PrivateMode::Named(NamedPrivateMode::SyncUpdate) => self.store.synchronized = false,
+            PrivateMode::Named(NamedPrivateMode::SwapScreenAndSetRestoreCursor) if alt_screen => {
+                self.show_main_screen();
+            }
+            PrivateMode::Named(NamedPrivateMode::ColumnMode) => self.column_mode(),
+            _ => {}
+        }
+    }
+
+    fn identify_terminal(&mut self, intermediate: Option<char>) {
+        match intermediate {
+            // 필터가 남긴 표시
+            Some('<') => match self.store.deferred.pop_front() {
+                Some(Deferred::Reply(reply)) => self.reply(reply),
+                Some(Deferred::Place {
+                    placement,
+                    rows,
+                    cursor,
+                }) => self.place(placement, rows, cursor),
+                None => log::debug!("Nothing deferred left for a marker"),
+            },
+            Some(intermediate) => match queries::answer_attributes(intermediate) {
+                Some(reply) => self.reply(reply),
+                None => self.term.identify_terminal(Some(intermediate)),
+            },
+            None => self.term.identify_terminal(None),
+        }
+    }
+
+    // 동기화 중에 쌓인 조회는 끝날 때 차례대로 읽히며, 그때까지는 켜져 있음
+    fn report_private_mode(&mut self, mode: PrivateMode) {
+        if mode == PrivateMode::Named(NamedPrivateMode::SyncUpdate) && self.store.synchronized {
+            self.reply("\x1b[?2026;1$y".to_string());
+        } else {
+            self.term.report_private_mode(mode);
+        }
+    }
+
+    forward! {
+        set_title(title: Option<String>);
+        set_cursor_style(style: Option<CursorStyle>);
+        set_cursor_shape(shape: CursorShape);
+        goto(line: i32, col: usize);
+        goto_line(line: i32);
+        goto_col(col: usize);
+        move_up(lines: usize);
+        move_down(lines: usize);
+        device_status(arg: usize);
+        move_forward(cols: usize);
+        move_backward(cols: usize);
+        move_down_and_cr(lines: usize);
+        move_up_and_cr(lines: usize);
+        put_tab(count: u16);
+        backspace();
+        carriage_return();
+        bell();
+        substitute();
+        set_horizontal_tabstop();
+        move_backward_tabs(count: u16);
+        move_forward_tabs(count: u16);
+        save_cursor_position();
+        restore_cursor_position();
+        clear_tabs(mode: TabulationClearMode);
+        terminal_attribute(attr: Attr);
+        set_mode(mode: Mode);
+        unset_mode(mode: Mode);
+        report_mode(mode: Mode);
+        set_keypad_application_mode();
+        unset_keypad_application_mode();
+        set_active_charset(index: CharsetIndex);
+        configure_charset(index: CharsetIndex, charset: StandardCharset);
+        set_color(index: usize, color: Rgb);
+        dynamic_color_sequence(prefix: String, index: usize, terminator: &str);
+        reset_color(index: usize);
+        clipboard_store(clipboard: u8, base64: &[u8]);
+        clipboard_load(clipboard: u8, terminator: &str);
+        push_title();
+        pop_title();
+        text_area_size_pixels();
+        text_area_size_chars();
+        report_keyboard_mode();
+        push_keyboard_mode(mode: KeyboardModes);
+        pop_keyboard_modes(to_pop: u16);
+        set_keyboard_mode(mode: KeyboardModes, apply: KeyboardModesApplyBehavior);
+        set_hyperlink(hyperlink: Option<Hyperlink>);
+    }
+}
+
+/// Run of placeholder cells on one screen line showing neighbouring columns of one image row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PlaceholderRun {
+    line: usize,
+    column: usize,
+    client_id: u32,
+    placement_id: u32,
+    // 이미지 칸 좌표
+    row: u32,
+    first: u32,
+    length: u32,
+}
+
+/// Placeholder cells (kitty `U=1`) found on the screen, gathered left to right, top to bottom
+#[derive(Debug, Default)]
+pub struct PlaceholderLayout {
+    runs: Vec<PlaceholderRun>,
+}
+
+impl PlaceholderLayout {
+    /// Add the placeholder cell at screen `line`, `column`.
+    /// Marks left out continue the cell to the left when it shows the same image.
+    pub fn push(&mut self, line: usize, column: usize, cell: PlaceholderCell) {
+        let previous = self.runs.last_mut().filter(|run| {
+            run.line == line
+                && run.column + run.length as usize == column
+                && run.client_id & 0xFF_FFFF == cell.image_id
+                && run.placement_id == cell.placement_id
+        });
+        let (row, first, id_high) = match &previous {
+            Some(run) => (
+                cell.row.unwrap_or(run.row),
+                cell.column.unwrap_or(run.first + run.length),
+                cell.id_high.unwrap_or(run.client_id >> 24),
+            ),
+            None => (
+                cell.row.unwrap_or(0),
+                cell.column.unwrap_or(0),
+                cell.id_high.unwrap_or(0),
+            ),
+        };
+        let client_id = cell.image_id | id_high << 24;
+        if let Some(run) = previous {
+            if run.client_id == client_id && run.row == row && run.first + run.length == first {
+                run.length += 1;
+                return;
+            }
+        }
+        self.runs.push(PlaceholderRun {
+            line,
+            column,
+            client_id,
+            placement_id: cell.placement_id,
+            row,
+            first,
+            length: 1,
+        });
+    }
+
+    /// Slices of the placeholder placements, for a screen whose first cell is at `x`, `y`.
+    /// Each image keeps its aspect ratio, centered in the cells of its placement.
+    pub fn slices(
+        &self,
+        store: &ImageStore,
+        x: i32,
+        y: i32,
+        cell_width: i32,
+        cell_height: i32,
+    ) -> Vec<ImageSlice> {
+        let mut slices = Vec::new();
+        for run in &self.runs {
+            let Some(placement) = store.virtual_placement(run.client_id, run.placement_id) else {
+                continue;
+            };
+            let [source_x, source_y, source_width, source_height] = placement.source;
+            // 배치 칸 전체(현재 셀 크기)에 이미지를 비율 유지로 맞춘 사각형
+            let box_width = (placement.columns() as i32 * cell_width) as f32;
+            let box_height = (placement.rows() as i32 * cell_height) as f32;
+            let scale = (box_width / source_width as f32).min(box_height / source_height as f32);
+            let (width, height) = (source_width as f32 * scale, source_height as f32 * scale);
+            let (left, top) = ((box_width - width) / 2.0, (box_height - height) / 2.0);
+
+            // 이 칸들이 덮는 부분과 겹치는 곳만
+            let cells_left = (run.first as i32 * cell_width) as f32;
+            let cells_top = (run.row as i32 * cell_height) as f32;
+            let x0 = cells_left.max(left);
+            let x1 = (cells_left + (run.length as i32 * cell_width) as f32).min(left + width);
+            let y0 = cells_top.max(top);
+            let y1 = (cells_top + cell_height as f32).min(top + height);
+            if x0 >= x1 || y0 >= y1 {
+                continue;
+            }
+            let to_source_x = |px: f32| source_x + ((px - left) / scale) as u32;
+            let to_source_y = |py: f32| source_y + ((py - top) / scale) as u32;
+            let screen_x = x + run.column as i32 * cell_width;
+            let screen_y = y + run.line as i32 * cell_height;
+            slices.push(ImageSlice {
+                image: placement.image.clone(),
+                x: screen_x + (x0 - cells_left).round() as i32,
+                y: screen_y + (y0 - cells_top).round() as i32,
+                width: (x1 - x0).round() as i32,
+                height: (y1 - y0).round() as i32,
+                source_x: to_source_x(x0),
+                source_y: to_source_y(y0),
+                source_width: (to_source_x(x1) - to_source_x(x0)).max(1),
+                source_height: (to_source_y(y1) - to_source_y(y0)).max(1),
+                z_index: placement.z_index,
+            });
+        }
+        slices
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    Sixel,
+    Kitty,
+    /// XTGETTCAP 요청 (`DCS + q`): 이미지는 아니지만 같은 방식으로 끝까지 읽음
+    Termcap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterState {
+    Ground,
+    Escape,
+    /// `ESC [` seen: only CSIs starting with `>` or `<` are read whole
+    CsiStart,
+    /// `ESC [>` or `ESC [<` seen, reading up to the final byte
+    Csi,
+    /// `ESC P` seen, reading parameters and intermediates up to the final byte
+    DcsHeader,
+    /// `ESC _` seen: `G` starts a kitty graphics command
+    ApcStart,
+    Body(Protocol),
+    /// `ESC` inside an image body: `\` ends it
+    BodyEscape(Protocol),
+}
+
+/// How the cursor moves once an image is anchored
+enum CursorMove {
+    /// To the line below the image, scrolling like text would (Sixel)
+    Below,
+    /// To the cell after the image on its last line (kitty)
+    Right(u32),
+    /// Back to the anchor cell (kitty `C=1`)
+    Stay,
+}
+
+/// What the [`GraphicsFilter`] leaves for [`ImageAnchors`] to do when the parser reaches
+/// the [`DEFERRED_MARKER`] put in its place
+enum Deferred {
+    /// Answer to the program, written after those to everything before it
+    Reply(String),
+    /// Anchor each row of a placement, going down a line between rows, then move the cursor
+    Place {
+        placement: u64,
+        rows: u32,
+        cursor: CursorMove,
+    },
+}
+
+/// kitty command sent in chunks (`m=1`), waiting for the rest of its payload
+struct Transmission {
+    command: KittyCommand,
+    payload: Vec<u8>,
+}
+
+/// Sits between the PTY and the terminal parser, which knows nothing about images.
+/// Sixel and kitty graphics sequences are decoded into the [`ImageStore`], and the
+/// placement left for [`ImageAnchors`] to tie one cell per image row to and move the cursor
+/// past. That and the answers to kitty commands and to the queries the parser doesn't pass
+/// on (see [`queries`]) are marked in the output, so they happen where the sequence was
+/// and replies are written in the order the queries came in.
+pub struct GraphicsFilter {
+    store: ImageStore,
+    sixel: bool,
+    kitty: bool,
+    cell_size: (u32, u32),
+    state: FilterState,
+    /// CSI, DCS 헤더 또는 이미지 본문
+    sequence: Vec<u8>,
+    params: Vec<u8>,
+    /// 너무 긴 이미지는 끝(ST)까지 버림
+    discard: bool,
+    transmission: Option<Transmission>,
+}
+
+impl GraphicsFilter {
+    pub fn new(store: ImageStore, config: &GraphicsConfig, size: WindowSize) -> Self {
+        Self {
+            store,
+            sixel: config.sixel,
+            kitty: config.kitty,
+            cell_size: cell_size(size),
+            state: FilterState::Ground,
+            sequence: Vec::new(),
+            params: Vec::new(),
+            discard: false,
+            transmission: None,
+        }
+    }
+
+    /// New images are laid out on cells of this size
+    pub fn set_cell_size(&mut self, size: WindowSize) {
+        self.cell_size = cell_size(size);
+    }
+
+    /// Pass `input` on to `output`, holding back image sequences until they are complete
+    pub fn filter(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        for &byte in input {
+            match self.state {
+                FilterState::Ground if byte == 0x1B => self.state = FilterState::Escape,
+                FilterState::Ground => output.push(byte),
+                FilterState::Escape => match byte {
+                    b'P' => self.start(b"\x1bP", FilterState::DcsHeader),
+                    b'_' if self.kitty => self.start(b"\x1b_", FilterState::ApcStart),
+                    // 파서가 모르는 질의와 표시를 흉내 낸 CSI를 보려고 CSI도 읽음
+                    b'[' => self.start(b"\x1b[", FilterState::CsiStart),
+                    // ESC ESC: 앞의 ESC만 내보내고 다시 시퀀스 시작을 기다림
+                    0x1B => output.push(0x1B),
+                    _ => {
+                        output.extend_from_slice(&[0x1B, byte]);
+                        self.state = FilterState::Ground;
+                    }
+                },
+                FilterState::CsiStart => {
+                    if byte == b'>' || byte == b'<' {
+                        self.sequence.push(byte);
+                        self.state = FilterState::Csi;
+                    } else {
+                        // 나머지 CSI는 그대로 터미널로
+                        output.append(&mut self.sequence);
+                        self.state = FilterState::Ground;
+                        self.filter(&[byte], output);
+                    }
+                }
+                FilterState::Csi => match byte {
+                    0x1B => {
+                        output.append(&mut self.sequence);
+                        self.state = FilterState::Escape;
+                    }
+                    0x40..=0x7E => {
+                        self.sequence.push(byte);
+                        self.finish_csi(output);
+                        self.state = FilterState::Ground;
+                    }
+                    _ if self.sequence.len() >= MAX_HEADER => {
+                        output.append(&mut self.sequence);
+                        output.push(byte);
+                        self.state = FilterState::Ground;
+                    }
+                    _ => self.sequence.push(byte),
+                },
+                FilterState::DcsHeader => {
+                    self.sequence.push(byte);
+                    match byte {
+                        b'0'..=b'9' | b';' | 0x20..=0x2F if self.sequence.len() < MAX_HEADER => {}
+                        b'q' if self.sequence == b"\x1bP+q" => {
+                            self.start(b"", FilterState::Body(Protocol::Termcap));
+                        }
+                        b'q' if self.sixel
+                            && !self.sequence.iter().any(|b| (0x20..=0x2F).contains(b)) =>
+                        {
+                            self.params = self.sequence[2..self.sequence.len() - 1].to_vec();
+                            self.start(b"", FilterState::Body(Protocol::Sixel));
+                        }
+                        // 그 밖의 DCS는 그대로 터미널로
+                        _ => {
+                            output.append(&mut self.sequence);
+                            self.state = FilterState::Ground;
+                        }
+                    }
+                }
+                FilterState::ApcStart => {
+                    if byte == b'G' {
+                        self.start(b"", FilterState::Body(Protocol::Kitty));
+                    } else {
+                        // 그래픽이 아닌 APC는 그대로 터미널로
+                        output.append(&mut self.sequence);
+                        self.state = FilterState::Ground;
+                        self.filter(&[byte], output);
+                    }
+                }
+                FilterState::Body(protocol) => {
+                    let limit = match protocol {
+                        Protocol::Sixel => MAX_SIXEL_BYTES,
+                        Protocol::Kitty => MAX_KITTY_BYTES,
+                        Protocol::Termcap => MAX_TERMCAP_BYTES,
+                    };
+                    match byte {
+                        0x1B => self.state = FilterState::BodyEscape(protocol),
+                        // CAN, SUB: 시퀀스 취소
+                        0x18 | 0x1A => {
+                            self.sequence.clear();
+                            self.state = FilterState::Ground;
+                        }
+                        _ if self.discard => {}
+                        _ if self.sequence.len() >= limit => {
+                            log::warn!("{:?} sequence over {} bytes, ignoring it", protocol, limit);
+                            self.sequence = Vec::new();
+                            self.discard = true;
+                        }
+                        _ => self.sequence.push(byte),
+                    }
+                }
+                FilterState::BodyEscape(protocol) => {
+                    match protocol {
+                        Protocol::Sixel => self.finish_sixel(output),
+                        Protocol::Kitty => self.finish_kitty(output),
+                        Protocol::Termcap => self.finish_termcap(output),
+                    }
+                    // ST가 아닌 ESC는 새 시퀀스의 시작
+                    if byte == b'\\' {
+                        self.state = FilterState::Ground;
+                    } else {
+                        self.state = FilterState::Escape;
+                        self.filter(&[byte], output);
+                    }
+                }
+            }
+        }
+    }
+
+    fn start(&mut self, prefix: &[u8], state: FilterState) {
+        self.sequence.clear();
+        self.sequence.extend_from_slice(prefix);
+        self.discard = false;
+        self.state = state;
+    }
+
+    fn finish_csi(&mut self, output: &mut Vec<u8>) {
+        match self.sequence.as_slice() {
+            // 프로그램이 보낸 표시는 버림
+            [0x1B, b'[', b'<', .., b'c'] => self.sequence.clear(),
+            b"\x1b[>q" | b"\x1b[>0q" => {
+                self.sequence.clear();
+                self.defer(output, Deferred::Reply(queries::answer_version()));
+            }
+            _ => output.append(&mut self.sequence),
+        }
+    }
+
+    /// Leave `deferred` for the parser to run once it gets to this point in the output
+    fn defer(&mut self, output: &mut Vec<u8>, deferred: Deferred) {
+        self.store.defer(deferred);
+        output.extend_from_slice(DEFERRED_MARKER);
+    }
+
+    fn finish_termcap(&mut self, output: &mut Vec<u8>) {
+        let request = std::mem::take(&mut self.sequence);
+        if !self.discard {
+            self.defer(output, Deferred::Reply(queries::answer_termcap(&request)));
+        }
+    }
+
+    fn finish_sixel(&mut self, output: &mut Vec<u8>) {
+        let data = std::mem::take(&mut self.sequence);
+        if self.discard {
+            return;
+        }
+        let image = match sixel::decode(&self.params, &data)
+            .and_then(|image| self.store.insert(image.width, image.height, image.rgba, 0))
+        {
+            Ok(image) => image,
+            Err(e) => {
+                log::debug!("Ignoring Sixel image: {}", e);
+                return;
+            }
+        };
+        let placement = Placement {
+            id: next_id(),
+            source: [0, 0, image.width, image.height],
+            offset: (0, 0),
+            size: (image.width, image.height),
+            cell_size: self.cell_size,
+            z_index: 0,
+            client_id: 0,
+            client_placement: 0,
+            image,
+        };
+        log::debug!(
+            "Sixel image {} ({}x{} px, {} rows)",
+            placement.image.id,
+            placement.image.width,
+            placement.image.height,
+            placement.rows()
+        );
+        let (id, rows) = (placement.id, placement.rows());
+        self.store.place(placement);
+        let cursor = CursorMove::Below;
+        self.defer(
+            output,
+            Deferred::Place {
+                placement: id,
+                rows,
+                cursor,
+            },
+        );
+    }
+
+    fn finish_kitty(&mut self, output: &mut Vec<u8>) {
+        let data = std::mem::take(&mut self.sequence);
+        if self.discard {
+            self.transmission = None;
+            return;
+        }
+        let (control, payload) = match data.iter().position(|&b| b == b';') {
+            Some(split) => (&data[..split], &data[split + 1..]),
+            None => (&data[..], &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        // 나눠 보낸 데이터의 이어지는 조각은 첫 조각의 명령에 붙임
+        let (command, payload) = match self.transmission.take() {
+            Some(mut transmission) => {
+                transmission.payload.extend_from_slice(payload);
+                if transmission.payload.len() > MAX_KITTY_BYTES {
+                    log::warn!("kitty image over {} bytes, ignoring it", MAX_KITTY_BYTES);
+                    return;
+                }
+                if command.more {
+                    self.transmission = Some(transmission);
+                    return;
+                }
+                (transmission.command, transmission.payload)
+            }
+            None if command.more => {
+                self.transmission = Some(Transmission {
+                    command,
+                    payload: payload.to_vec(),
+                });
+                return;
+            }
+            None => (command, payload.to_vec()),
+        };
+        self.run_kitty(&command, &payload, output);
+    }
+
+    fn run_kitty(&mut self, command: &KittyCommand, payload: &[u8], output: &mut Vec<u8>) {
+        let mut image_id = command.image_id;
+        let result = match command.action {
+            Action::Query => kitty::load(command, payload).map(|_| ()),
+            Action::Transmit | Action::TransmitAndPut => {
+                if image_id == 0 && command.image_number != 0 {
+                    image_id = self.store.assign_client_id(command.image_number);
+                }
+                self.transmit(command, image_id, payload, output)
+            }
+            Action::Put => match self.store.client_image(image_id, command.image_number) {
+                Some((client_id, image)) => {
+                    image_id = client_id;
+                    self.put(command, client_id, image, output)
+                }
+                None => Err(kitty::error("ENOENT", "no such image")),
+            },
+            // 삭제에는 답하지 않음
+            Action::Delete => {
+                self.delete(command);
+                return;
+            }
+            Action::Unsupported => Err(kitty::error("EINVAL", "unsupported action")),
+        };
+        if let Err(e) = &result {
+            log::debug!("kitty graphics command failed: {}", e);
+        }
+        if let Some(reply) = kitty::reply(command, image_id, &result) {
+            self.defer(output, Deferred::Reply(reply));
+        }
+    }
+
+    fn transmit(
+        &mut self,
+        command: &KittyCommand,
+        image_id: u32,
+        payload: &[u8],
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let image = kitty::load(command, payload)?;
+        // id 없이 보내기만 한 이미지는 나중에 가리킬 방법이 없음
+        if command.action == Action::Transmit && image_id == 0 {
+            return Ok(());
+        }
+        let image = self
+            .store
+            .insert(image.width, image.height, image.rgba, image_id)
+            .map_err(|e| kitty::error("EFBIG", e))?;
+        log::debug!(
+            "kitty image {} ({}x{} px)",
+            image_id,
+            image.width,
+            image.height
+        );
+        if command.action == Action::TransmitAndPut {
+            self.put(command, image_id, image, output)?;
+        }
+        Ok(())
+    }
+
+    fn put(
+        &mut self,
+        command: &KittyCommand,
+        client_id: u32,
+        image: Arc<GraphicsImage>,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let (cell_width, cell_height) = self.cell_size;
+        // 보여줄 부분 (w, h가 0이면 이미지 끝까지)
+        let source_x = command.source_x.min(image.width);
+        let source_y = command.source_y.min(image.height);
+        let source_width = match command.source_width {
+            0 => image.width - source_x,
+            width => width.min(image.width - source_x),
+        };
+        let source_height = match command.source_height {
+            0 => image.height - source_y,
+            height => height.min(image.height - source_y),
+        };
+        if source_width == 0 || source_height == 0 {
+            return Err(kitty::error("EINVAL", "empty source rectangle"));
+        }
+
+        // c, r을 주면 그 칸들에 맞춤 (하나만 주면 비율 유지)
+        let columns = command.columns.min(MAX_PLACEMENT_CELLS);
+        let rows = command.rows.min(MAX_PLACEMENT_CELLS);
+        let scaled = |size: u32, from: u32, to: u32| (size as u64 * to as u64 / from as u64) as u32;
+        let (width, height) = match (columns, rows) {
+            (0, 0) => (source_width, source_height),
+            (columns, 0) => {
+                let width = columns * cell_width;
+                (width, scaled(source_height, source_width, width))
+            }
+            (0, rows) => {
+                let height = rows * cell_height;
+                (scaled(source_width, source_height, height), height)
+            }
+            (columns, rows) => (columns * cell_width, rows * cell_height),
+        };
+
+        // 자리 표시 문자로 그릴 배치는 칸 수만 정해 두고 커서는 움직이지 않음
+        if command.unicode_placeholder {
+            let columns = width.div_ceil(cell_width).clamp(1, MAX_PLACEMENT_CELLS);
+            let rows = height.div_ceil(cell_height).clamp(1, MAX_PLACEMENT_CELLS);
+            self.store.place_virtual(Placement {
+                id: next_id(),
+                image,
+                source: [source_x, source_y, source_width, source_height],
+                offset: (0, 0),
+                size: (columns * cell_width, rows * cell_height),
+                cell_size: self.cell_size,
+                z_index: command.z_index,
+                client_id,
+                client_placement: command.placement_id,
+            });
+            return Ok(());
+        }
+
+        let placement = Placement {
+            id: next_id(),
+            image,
+            source: [source_x, source_y, source_width, source_height],
+            offset: (
+                command.cell_x_offset.min(cell_width - 1),
+                command.cell_y_offset.min(cell_height - 1),
+            ),
+            size: (width.max(1), height.max(1)),
+            cell_size: self.cell_size,
+            z_index: command.z_index,
+            client_id,
+            client_placement: command.placement_id,
+        };
+        let (id, rows, columns) = (placement.id, placement.rows(), placement.columns());
+        self.store.place(placement);
+        let cursor = if command.stay {
+            CursorMove::Stay
+        } else {
+            CursorMove::Right(columns)
+        };
+        self.defer(
+            output,
+            Deferred::Place {
+                placement: id,
+                rows,
+                cursor,
+            },
+        );
+        Ok(())
+    }
+
+    fn delete(&mut self, command: &KittyCommand) {
+        let free = command.delete.is_ascii_uppercase();
+        match command.delete.to_ascii_lowercase() {
+            b'a' => self.store.delete(|_| true, free),
+            b'i' | b'n' => {
+                let client_id = match command.delete.to_ascii_lowercase() {
+                    b'i' => command.image_id,
+                    _ => match self.store.client_image(0, command.image_number) {
+                        Some((client_id, _)) => client_id,
+                        None => return,
+                    },
+                };
+                let placement_id = command.placement_id;
+                self.store.delete(
+                    |placement| {
+                        placement.client_id == client_id
+                            && (placement_id == 0 || placement.client_placement == placement_id)
+                    },
+                    free,
+                );
+                if free && placement_id == 0 {
+                    self.store.free_client_image(client_id);
+                }
+            }
+            b'r' => {
+                let range = command.source_x..=command.source_y;
+                self.store
+                    .delete(|placement| range.contains(&placement.client_id), free);
+            }
+            b'z' => {
+                let z_index = command.z_index;
+                self.store
+                    .delete(|placement| placement.z_index == z_index, free);
+            }
+            b'c' | b'p' | b'q' | b'x' | b'y' => self.store.defer_delete(command.clone()),
+            _ => log::debug!("Unsupported kitty deletion d={}", command.delete as char),
+        }
+    }
+}
+
+/// Lines [`Term`] pushes into the scrollback when clearing the main screen: down to the
+/// last one with anything on it, or one if there's nothing at all and no scrollback yet
+fn written_lines<T>(term: &Term<T>, history: usize) -> usize {
+    let grid = term.grid();
+    (0..grid.screen_lines())
+        .rev()
+        .find(|&line| {
+            grid[Line(line as i32)][..]
+                .iter()
+                .any(|cell| !cell.is_empty())
+        })
+        .map_or(usize::from(history == 0), |line| line + 1)
+}
+
+fn cell_size(size: WindowSize) -> (u32, u32) {
+    (
+        size.cell_width.max(1) as u32,
+        size.cell_height.max(1) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::EventProxy;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+    use std::sync::mpsc;
+
+    fn filter() -> (GraphicsFilter, ImageStore) {
+        let store = ImageStore::new(1024 * 1024);
+        let size = WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 8,
+            cell_height: 4,
+        };
+        let filter = GraphicsFilter::new(store.clone(), &GraphicsConfig::default(), size);
+        (filter, store)
+    }
+
+    #[test]
+    fn test_sixel_replaced_by_marker() {
+        let (mut filter, store) = filter();
+        let mut output = Vec::new();
+        // 시퀀스가 여러 번에 나뉘어 읽혀도 됨
+        filter.filter(b"a\x1bP0;1;0q#1!3~", &mut output);
+        assert_eq!(output, b"a");
+        filter.filter(b"\x1b\\b\x1b[0m\x1bP1$qm\x1b\\", &mut output);
+        assert_eq!(output, b"a\x1b[<cb\x1b[0m\x1bP1$qm\x1b\\");
+
+        // 높이 6px, 셀 높이 4px: 두 줄
+        let deferred = store.inner.lock().unwrap().deferred.pop_front();
+        let Some(Deferred::Place {
+            placement,
+            rows,
+            cursor: CursorMove::Below,
+        }) = deferred
+        else {
+            panic!("Sixel image not left for the parser");
+        };
+        assert_eq!(rows, 2);
+        let placement = store.placement(placement).unwrap();
+        assert_eq!((placement.image.width, placement.image.height), (3, 6));
+        // 셀이 두 배로 커지면 이미지도 두 배로
+        let slice = ImageSlice::new(&placement, 1, 0, 0, 16, 8).unwrap();
+        assert_eq!((slice.source_y, slice.source_height), (4, 2));
+        assert_eq!((slice.width, slice.height), (6, 4));
+    }
+
+    /// Filter, terminal and parser put together like a session's PTY thread
+    struct Pty {
+        filter: GraphicsFilter,
+        store: ImageStore,
+        term: Term<EventProxy>,
+        proxy: EventProxy,
+        events: mpsc::Receiver<Event>,
+        parser: Processor,
+    }
+
+    impl Pty {
+        /// Terminal of `lines` x 20 cells keeping `history` lines of scrollback
+        fn new(lines: usize, history: usize) -> Self {
+            let (filter, store) = filter();
+            let (proxy, events) = EventProxy::new(true);
+            let config = Config {
+                scrolling_history: history,
+                ..Config::default()
+            };
+            let term = Term::new(config, &TermSize::new(20, lines), proxy.clone());
+            Self {
+                filter,
+                store,
+                term,
+                proxy,
+                events,
+                parser: Processor::new(),
+            }
+        }
+
+        /// Filter `input` and parse it, returning the replies written back to the program
+        fn read(&mut self, input: &[u8]) -> Vec<String> {
+            let mut output = Vec::new();
+            self.filter.filter(input, &mut output);
+            self.parser.advance(
+                &mut ImageAnchors::new(&mut self.term, &self.store, &self.proxy),
+                &output,
+            );
+            self.replies()
+        }
+
+        /// End a synchronized update the way its timeout does
+        fn stop_sync(&mut self) -> Vec<String> {
+            self.parser.stop_sync(&mut ImageAnchors::new(
+                &mut self.term,
+                &self.store,
+                &self.proxy,
+            ));
+            self.replies()
+        }
+
+        fn replies(&self) -> Vec<String> {
+            self.events
+                .try_iter()
+                .filter_map(|event| match event {
+                    Event::PtyWrite(text) => Some(text),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn cursor(&self) -> (i32, usize) {
+            let point = self.term.grid().cursor.point;
+            (point.line.0, point.column.0)
+        }
+
+        fn anchor_at(&self, line: i32, column: usize) -> Option<(u64, u32)> {
+            self.store
+                .anchors()
+                .into_iter()
+                .find(|anchor| (anchor.line, anchor.column) == (line, column))
+                .map(|anchor| (anchor.placement, anchor.row))
+        }
+
+        /// Rows anchored and the text on their cells
+        fn anchored(&self) -> Vec<(u32, char)> {
+            let mut anchors = self.store.anchors();
+            anchors.sort_by_key(|anchor| anchor.row);
+            anchors
+                .iter()
+                .map(|anchor| {
+                    let point = Point::new(Line(anchor.line), Column(anchor.column));
+                    (anchor.row, self.term.grid()[point].c)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_sixel_keeps_saved_cursor_and_cell() {
+        let mut pty = Pty::new(10, 100);
+        pty.read(b"\x1b[3;5Hxyz\x1b[3;5H\x1b7\x1bPq~\x1b\\");
+        // 두 줄 아래로 내려간 뒤 ESC 8은 원래 칸으로
+        assert_eq!(pty.cursor(), (4, 4));
+        pty.read(b"\x1b8");
+        assert_eq!(pty.cursor(), (2, 4));
+
+        // 기준 칸은 그대로
+        assert_eq!(pty.term.grid()[Line(2)][Column(4)].c, 'x');
+        assert!(pty.term.grid()[Line(2)][Column(4)].hyperlink().is_none());
+        let (id, row) = pty.anchor_at(2, 4).unwrap();
+        assert_eq!(row, 0);
+        assert_eq!(pty.anchor_at(3, 4), Some((id, 1)));
+        assert!(pty.store.placement(id).is_some());
+        assert!(pty.term.grid().cursor.template.hyperlink().is_none());
+    }
+
+    #[test]
+    fn test_kitty_stay_keeps_cursor_and_saved_cursor() {
+        let mut pty = Pty::new(10, 100);
+        pty.read(
+            b"\x1b[10;10H\x1b7\x1b[3;5H\x1b_Ga=t,f=32,s=2,v=2,i=5;AAAAAAAAAAAAAAAAAAAAAA==\x1b\\",
+        );
+        // 2x2 px를 3행 높이로: 세 줄에 고정하고 커서는 그대로
+        pty.read(b"\x1b_Ga=p,i=5,r=3,C=1\x1b\\");
+        assert_eq!(pty.cursor(), (2, 4));
+        let (id, _) = pty.anchor_at(4, 4).unwrap();
+        assert_eq!(pty.anchor_at(2, 4), Some((id, 0)));
+        assert_eq!(pty.store.placement(id).unwrap().client_id, 5);
+
+        pty.read(b"\x1b8");
+        assert_eq!(pty.cursor(), (9, 9));
+    }
+
+    #[test]
+    fn test_program_links_stay_on_anchor_cells() {
+        let mut pty = Pty::new(6, 100);
+        let link = b"\x1b]8;;https://example.com\x1b\\";
+        let mut output = Vec::new();
+        pty.filter.filter(link, &mut output);
+        assert_eq!(output, link);
+
+        // 링크 안에서 이미지를 그리고 글자를 씀
+        pty.read(b"\x1b]8;;https://example.com\x1b\\\x1bPq~\x1b\\\x1b[1;1Hab\x1b]8;;\x1b\\");
+        let hyperlink = |pty: &Pty| {
+            let link = pty.term.grid()[Line(0)][Column(0)].hyperlink()?;
+            Some(link.uri().to_string())
+        };
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, ' ')]);
+        assert_eq!(hyperlink(&pty).as_deref(), Some("https://example.com"));
+
+        // 셀을 표시해 두었다가 되돌려도 그대로
+        pty.read(b"\x1b[?1049h\x1b[?1049l");
+        pty.store.resize(&mut pty.term, TermSize::new(10, 6));
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, ' ')]);
+        assert_eq!(hyperlink(&pty).as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn test_anchors_move_with_their_cells() {
+        // 6줄 20열, 3행 5열부터 두 줄짜리 이미지 아래에 a, b를 씀
+        type Case = (&'static [u8], &'static [(u32, char)]);
+        let cases: [Case; 14] = [
+            (b"", &[(0, 'a'), (1, 'b')]),
+            // 스크롤 영역 안에서 위로 밀려난 줄은 사라짐
+            (b"\x1b[3;5r\x1b[5;1H\n", &[(1, 'b')]),
+            (b"\x1b[2;5r\x1b[5;1H\n", &[(0, 'a'), (1, 'b')]),
+            (b"\x1b[2;5r\x1b[5;1H\n\n\n", &[]),
+            // 줄 삽입, 삭제
+            (b"\x1b[2;1H\x1b[2L", &[(0, 'a'), (1, 'b')]),
+            (b"\x1b[2;1H\x1b[3L", &[(0, 'a')]),
+            (b"\x1b[3;1H\x1b[M", &[(1, 'b')]),
+            // 글자 삽입, 삭제, 지우기
+            (b"\x1b[3;1H\x1b[2@\x1b[3P", &[(0, 'a'), (1, 'b')]),
+            (b"\x1b[3;1H\x1b[16@", &[(1, 'b')]),
+            (b"\x1b[3;5H\x1b[X\x1b[4;5H\x1b[1K", &[]),
+            (b"\x1b[4h\x1b[3;1Hzz\x1b[4l", &[(0, 'a'), (1, 'b')]),
+            // 화면 위로 스크롤, 아래로 스크롤
+            (b"\x1b[1;1H\x1bM\x1b[T\x1b[3S", &[(0, 'a'), (1, 'b')]),
+            // 맨 아래 줄 끝에서 넘어가면 스크롤
+            (b"\x1b[6;20Hxy", &[(0, 'a'), (1, 'b')]),
+            (b"\x1b[4;1H\x1b[1J", &[(1, 'b')]),
+        ];
+        for (input, expected) in cases {
+            let mut pty = Pty::new(6, 100);
+            pty.read(b"\x1b[3;5H\x1bPq~\x1b\\\x1b[3;5Ha\x1b[4;5Hb");
+            pty.read(input);
+            let text = String::from_utf8_lossy(input);
+            assert_eq!(pty.anchored(), expected, "{:?}", text);
+            // 앵커가 하나도 없으면 배치도 지움
+            let placements = pty.store.inner.lock().unwrap().placements.len();
+            assert_eq!(placements, usize::from(!expected.is_empty()), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_anchors_after_scroll_and_clear() {
+        // 스크롤백 없이 세 줄: 화면 밖으로 밀려난 줄은 사라짐
+        let mut pty = Pty::new(3, 0);
+        pty.read(b"\x1bPq~\x1b\\");
+        let (id, _) = pty.anchor_at(0, 0).unwrap();
+        pty.read(b"\n");
+        assert_eq!(pty.anchor_at(0, 0), Some((id, 1)));
+        pty.read(b"\n\n");
+        assert!(pty.store.placement(id).is_none());
+
+        // 스크롤백으로 올라간 이미지는 ED 3으로 지워짐
+        let mut pty = Pty::new(3, 100);
+        pty.read(b"\x1bPq~\x1b\\\n\n\n");
+        assert_eq!(pty.anchor_at(-2, 0).map(|(_, row)| row), Some(1));
+        pty.read(b"\x1b[3J");
+        assert!(pty.store.anchors().is_empty());
+
+        // ED 2는 내용이 있는 줄까지 스크롤백으로 올림
+        let mut pty = Pty::new(6, 100);
+        pty.read(b"\x1b[3;5H\x1bPq~\x1b\\\x1b[3;5Ha\x1b[4;5Hb\x1b[2J");
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, 'b')]);
+        assert_eq!(pty.anchor_at(-2, 4).map(|(_, row)| row), Some(0));
+        // 스크롤백이 넘치면 사라짐
+        pty.read(&b"\n".repeat(120));
+        assert!(pty.store.anchors().is_empty());
+    }
+
+    #[test]
+    fn test_alt_screen_hides_main_anchors() {
+        let mut pty = Pty::new(6, 100);
+        pty.read(b"\x1bPq~\x1b\\\x1b[1;1Ha");
+        let (main, _) = pty.anchor_at(0, 0).unwrap();
+        let main_image = pty.store.placement(main).unwrap().image.id;
+
+        // 보조 화면에서는 주 화면의 앵커를 숨겨 두고 배치는 남김
+        pty.read(b"\x1b[?1049h\x1b[H\x1bPq~\x1b\\");
+        let (alt, _) = pty.anchor_at(0, 0).unwrap();
+        assert_eq!(pty.store.anchors().len(), 2);
+        assert!(pty.store.placement(main).is_some());
+
+        // 돌아오면 보조 화면의 배치는 지우고 주 화면의 앵커는 제자리로
+        pty.read(b"\x1b[?1049l");
+        assert!(pty.store.placement(alt).is_none());
+        assert_eq!(pty.anchor_at(0, 0), Some((main, 0)));
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, ' ')]);
+
+        // 초기화하면 모두 지움
+        pty.read(b"\x1bc");
+        assert!(pty.store.placement(main).is_none());
+        assert!(!pty
+            .store
+            .inner
+            .lock()
+            .unwrap()
+            .images
+            .contains_key(&main_image));
+    }
+
+    #[test]
+    fn test_resize_rewraps_anchors() {
+        let mut pty = Pty::new(6, 100);
+        // 15글자 뒤에 이미지: 10열로 줄이면 나뉜 줄의 5열로 (커서 위로 늘어난 줄은 스크롤백으로)
+        pty.read(b"xxxxxxxxxxxxxxx\x1bPq~\x1b\\\x1b[1;16Ha\x1b[2;16Hb\x1b[3;1H");
+        assert_eq!(pty.anchor_at(0, 15).map(|(_, row)| row), Some(0));
+        pty.store.resize(&mut pty.term, TermSize::new(10, 6));
+        assert_eq!(pty.anchor_at(-1, 5).map(|(_, row)| row), Some(0));
+        assert_eq!(pty.anchor_at(1, 5).map(|(_, row)| row), Some(1));
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, 'b')]);
+
+        // 다시 늘리면 원래 자리로
+        pty.store.resize(&mut pty.term, TermSize::new(20, 6));
+        assert_eq!(pty.anchor_at(0, 15).map(|(_, row)| row), Some(0));
+        assert_eq!(pty.anchored(), [(0, 'a'), (1, 'b')]);
+    }
+
+    #[test]
+    fn test_kitty_chunks_placement_and_replies() {
+        let mut pty = Pty::new(10, 100);
+        // RGBA 2x2를 두 조각으로, 3열 x 2행에 맞춰 배치
+        let replies = pty.read(b"\x1b_Ga=T,f=32,s=2,v=2,i=5,c=3,r=2,m=1;AAAAAAAAAAAA\x1b\\");
+        assert!(replies.is_empty());
+        // 마지막 줄에서 이미지 오른쪽으로 간 뒤에 응답, 그다음 커서 위치 보고
+        assert_eq!(
+            pty.read(b"\x1b_Gm=0;AAAAAAAAAA==\x1b\\\x1b[6n"),
+            ["\x1b_Gi=5;OK\x1b\\", "\x1b[2;4R"]
+        );
+        let (id, row) = pty.anchor_at(1, 0).unwrap();
+        assert_eq!((pty.anchor_at(0, 0), row), (Some((id, 0)), 1));
+        let placement = pty.store.placement(id).unwrap();
+        assert_eq!((placement.size, placement.columns()), ((24, 8), 3));
+
+        // 프로그램의 질의와 kitty 응답은 온 순서대로
+        assert_eq!(
+            pty.read(b"\x1b[5n\x1b_Ga=p,i=9\x1b\\\x1b[c"),
+            [
+                "\x1b[0n",
+                "\x1b_Gi=9;ENOENT:no such image\x1b\\",
+                "\x1b[?62;4;22c"
+            ]
+        );
+
+        // 지우면 배치가 없어지고, 대문자면 이미지도
+        pty.read(b"\x1b_Ga=d,d=I,i=5\x1b\\");
+        assert!(pty.store.placement(id).is_none());
+        assert!(pty.store.client_image(5, 0).is_none());
+    }
+
+    #[test]
+    fn test_program_markers_are_dropped() {
+        let mut pty = Pty::new(10, 100);
+        // 프로그램이 보낸 표시가 미뤄 둔 응답을 앞당기지 않음
+        let replies = pty.read(b"\x1b[<c\x1b[6n\x1bP+q544e\x1b\\\x1b[<1;2c\x1b[>q");
+        assert_eq!(
+            replies,
+            [
+                "\x1b[1;1R".to_string(),
+                "\x1bP1+r544E=787465726D2D323536636F6C6F72\x1b\\".to_string(),
+                queries::answer_version(),
+            ]
+        );
+        assert!(pty.store.inner.lock().unwrap().deferred.is_empty());
+    }
+
+    #[test]
+    fn test_placeholder_placement() {
+        let mut pty = Pty::new(10, 100);
+        // RGBA 2x2를 자리 표시 문자용으로 2열 x 1행에: 응답만 하고 커서는 그대로
+        let replies =
+            pty.read(b"\x1b_Ga=T,f=32,s=2,v=2,i=5,U=1,c=2,r=1;AAAAAAAAAAAAAAAAAAAAAA==\x1b\\");
+        assert_eq!(replies, ["\x1b_Gi=5;OK\x1b\\"]);
+        assert_eq!(pty.cursor(), (0, 0));
+        let store = pty.store;
+
+        // 3행 10열에 0행 0열, 오른쪽 칸은 표시 없이 이어짐
+        let mut layout = PlaceholderLayout::default();
+        let cell = |row, column| PlaceholderCell {
+            image_id: 5,
+            placement_id: 0,
+            row,
+            column,
+            id_high: None,
+        };
+        layout.push(3, 10, cell(Some(0), Some(0)));
+        layout.push(3, 11, cell(None, None));
+        let slices = layout.slices(&store, 0, 0, 8, 4);
+        assert_eq!(slices.len(), 1);
+        // 16x4 칸에 4x4로 맞춰 가운데
+        let slice = &slices[0];
+        assert_eq!(
+            (slice.x, slice.y, slice.width, slice.height),
+            (86, 12, 4, 4)
+        );
+        assert_eq!((slice.source_width, slice.source_height), (2, 2));
+
+        // 없는 이미지를 가리키면 그리지 않음
+        layout.push(
+            5,
+            0,
+            PlaceholderCell {
+                image_id: 6,
+                ..cell(None, None)
+            },
+        );
+        assert_eq!(layout.slices(&store, 0, 0, 8, 4).len(), 1);
+    }
+
+    #[test]
+    fn test_synchronized_output_replies_in_order() {
+        let mut pty = Pty::new(10, 100);
+        // 동기화 중의 조회는 끝날 때 다른 응답과 차례대로, 켜져 있다고 답함
+        assert!(pty.read(b"\x1b[?2026h\x1b[?2026$p\x1b[6n").is_empty());
+        assert!(pty.read(b"\x1b[>q").is_empty());
+        assert_eq!(
+            pty.read(b"\x1b[?2026l\x1b[?2026$p"),
+            [
+                "\x1b[?2026;1$y".to_string(),
+                "\x1b[1;1R".to_string(),
+                queries::answer_version(),
+                "\x1b[?2026;2$y".to_string(),
+            ]
+        );
+
+        // 시간이 지나 끝나도 같음
+        assert!(pty.read(b"\x1b[?2026h\x1b[?2026$p").is_empty());
+        assert_eq!(pty.stop_sync(), ["\x1b[?2026;1$y"]);
+        assert_eq!(pty.read(b"\x1b[?2026$p"), ["\x1b[?2026;2$y"]);
+    }
+
+    #[test]
+    fn test_delete_by_position() {
+        let placement = Placement {
+            id: 1,
+            image: Arc::new(GraphicsImage {
+                id: 1,
+                width: 16,
+                height: 8,
+                rgba: Vec::new(),
+            }),
+            source: [0, 0, 16, 8],
+            offset: (0, 0),
+            size: (16, 8),
+            cell_size: (8, 4),
+            z_index: 2,
+            client_id: 1,
+            client_placement: 0,
+        };
+        // 3행 4열에서 시작해 2x2 칸
+        let at =
+            |control: &[u8]| deleted_at(&KittyCommand::parse(control), &placement, 3, 4, (4, 5));
+        assert!(at(b"d=c"));
+        assert!(at(b"d=p,x=6,y=5"));
+        assert!(!at(b"d=p,x=7,y=5"));
+        assert!(!at(b"d=q,x=5,y=4,z=1"));
+        assert!(at(b"d=Y,y=4"));
+    }
+}
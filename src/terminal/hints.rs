@@ -0,0 +1,181 @@
+use alacritty_terminal::{grid::Grid, term::cell::Cell};
+use regex::Regex;
+
+use crate::config::{HintAction, HintConfig};
+
+use super::links::{self, LineText};
+
+/// A regex match in the viewport together with the label the user types to select it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HintMatch {
+    pub label: String,
+    /// Viewport row (0 = top of the screen)
+    pub row: usize,
+    pub start_col: usize,
+    /// Exclusive end column
+    pub end_col: usize,
+    pub text: String,
+}
+
+/// Result of typing one character while hints are shown
+#[derive(Debug, Clone, PartialEq)]
+pub enum HintInput {
+    /// Still ambiguous, keep showing the remaining labels
+    Pending,
+    /// The typed label picked exactly one match
+    Selected(HintMatch),
+    /// No label starts with the typed text; the character is ignored
+    NoMatch,
+}
+
+/// Active hint mode for one session
+#[derive(Debug, Clone)]
+pub struct HintState {
+    pub action: HintAction,
+    pub matches: Vec<HintMatch>,
+    pub typed: String,
+}
+
+impl HintState {
+    /// Find every match of `hint` in the viewport and label them
+    ///
+    /// Returns `None` when the regex is invalid or nothing on screen matches.
+    pub fn new(grid: &Grid<Cell>, hint: &HintConfig, alphabet: &str) -> Option<Self> {
+        let regex = match Regex::new(&hint.regex) {
+            Ok(regex) => regex,
+            Err(e) => {
+                log::warn!("Invalid regex for hint {:?}: {}", hint.name, e);
+                return None;
+            }
+        };
+
+        let found: Vec<(usize, usize, usize, String)> = links::visible_lines(grid)
+            .enumerate()
+            .flat_map(|(row, line)| {
+                LineText::from_grid(grid, line)
+                    .find_all(&regex)
+                    .into_iter()
+                    .map(move |(start_col, end_col, text)| (row, start_col, end_col, text))
+            })
+            .collect();
+
+        if found.is_empty() {
+            return None;
+        }
+
+        let labels = generate_labels(alphabet, found.len());
+        let matches = found
+            .into_iter()
+            .zip(labels)
+            .map(|((row, start_col, end_col, text), label)| HintMatch {
+                label,
+                row,
+                start_col,
+                end_col,
+                text,
+            })
+            .collect();
+
+        Some(Self {
+            action: hint.action,
+            matches,
+            typed: String::new(),
+        })
+    }
+
+    /// Matches whose label still starts with what has been typed
+    pub fn visible(&self) -> impl Iterator<Item = &HintMatch> {
+        self.matches
+            .iter()
+            .filter(move |m| m.label.starts_with(&self.typed))
+    }
+
+    pub fn push(&mut self, ch: char) -> HintInput {
+        let mut typed = self.typed.clone();
+        typed.push(ch.to_ascii_lowercase());
+
+        let mut candidates = self.matches.iter().filter(|m| m.label.starts_with(&typed));
+        let Some(first) = candidates.next() else {
+            return HintInput::NoMatch;
+        };
+        if first.label == typed {
+            return HintInput::Selected(first.clone());
+        }
+
+        self.typed = typed;
+        HintInput::Pending
+    }
+
+    pub fn pop(&mut self) {
+        self.typed.pop();
+    }
+}
+
+/// Generate `count` labels from `alphabet`, all of the same length so none is a prefix of another
+pub fn generate_labels(alphabet: &str, count: usize) -> Vec<String> {
+    let mut chars: Vec<char> = Vec::new();
+    for ch in alphabet.chars().map(|c| c.to_ascii_lowercase()) {
+        if !chars.contains(&ch) {
+            chars.push(ch);
+        }
+    }
+    if chars.len() < 2 {
+        log::warn!(
+            "Hint alphabet {:?} is too short, using the default",
+            alphabet
+        );
+        chars = "jfkdls;ahgurieowpq".chars().collect();
+    }
+
+    let mut length = 1;
+    while chars.len().pow(length) < count {
+        length += 1;
+    }
+
+    (0..count)
+        .map(|mut index| {
+            let mut label = vec![chars[0]; length as usize];
+            for slot in label.iter_mut().rev() {
+                *slot = chars[index % chars.len()];
+                index /= chars.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_labels() {
+        assert_eq!(generate_labels("abc", 3), vec!["a", "b", "c"]);
+        assert_eq!(generate_labels("abc", 4), vec!["aa", "ab", "ac", "ba"]);
+        assert!(generate_labels("abc", 0).is_empty());
+    }
+
+    #[test]
+    fn test_hint_state_selection() {
+        let hint = |label: &str| HintMatch {
+            label: label.to_string(),
+            row: 0,
+            start_col: 0,
+            end_col: 1,
+            text: label.to_uppercase(),
+        };
+        let mut state = HintState {
+            action: HintAction::Copy,
+            matches: vec![hint("aa"), hint("ab"), hint("ba")],
+            typed: String::new(),
+        };
+
+        assert_eq!(state.push('x'), HintInput::NoMatch);
+        assert_eq!(state.push('a'), HintInput::Pending);
+        assert_eq!(state.visible().count(), 2);
+        assert_eq!(state.push('B'), HintInput::Selected(hint("ab")));
+
+        state.pop();
+        assert_eq!(state.visible().count(), 3);
+    }
+}
@@ -0,0 +1,509 @@
+use alacritty_terminal::vte::ansi::Color as AnsiColor;
+use anyhow::Result;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use flate2::read::ZlibDecoder;
+use resvg::tiny_skia;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Temporary files (`t=t`) are only read and deleted if their name contains this, as in kitty
+const TEMP_FILE_MARKER: &str = "tty-graphics-protocol";
+
+/// Largest image data read from one transmission, after decompression
+const MAX_DATA_BYTES: usize = 256 * 1024 * 1024;
+
+/// Payloads may leave out the base64 padding
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Character the cells of a Unicode placeholder placement (`U=1`) are written with
+pub const PLACEHOLDER: char = '\u{10EEEE}';
+
+/// Combining marks numbering the rows and columns of placeholder cells: the n-th mark is n
+const DIACRITICS: [u32; 297] = [
+    0x0305, 0x030D, 0x030E, 0x0310, 0x0312, 0x033D, 0x033E, 0x033F, 0x0346, 0x034A, 0x034B, 0x034C,
+    0x0350, 0x0351, 0x0352, 0x0357, 0x035B, 0x0363, 0x0364, 0x0365, 0x0366, 0x0367, 0x0368, 0x0369,
+    0x036A, 0x036B, 0x036C, 0x036D, 0x036E, 0x036F, 0x0483, 0x0484, 0x0485, 0x0486, 0x0487, 0x0592,
+    0x0593, 0x0594, 0x0595, 0x0597, 0x0598, 0x0599, 0x059C, 0x059D, 0x059E, 0x059F, 0x05A0, 0x05A1,
+    0x05A8, 0x05A9, 0x05AB, 0x05AC, 0x05AF, 0x05C4, 0x0610, 0x0611, 0x0612, 0x0613, 0x0614, 0x0615,
+    0x0616, 0x0617, 0x0657, 0x0658, 0x0659, 0x065A, 0x065B, 0x065D, 0x065E, 0x06D6, 0x06D7, 0x06D8,
+    0x06D9, 0x06DA, 0x06DB, 0x06DC, 0x06DF, 0x06E0, 0x06E1, 0x06E2, 0x06E4, 0x06E7, 0x06E8, 0x06EB,
+    0x06EC, 0x0730, 0x0732, 0x0733, 0x0735, 0x0736, 0x073A, 0x073D, 0x073F, 0x0740, 0x0741, 0x0743,
+    0x0745, 0x0747, 0x0749, 0x074A, 0x07EB, 0x07EC, 0x07ED, 0x07EE, 0x07EF, 0x07F0, 0x07F1, 0x07F3,
+    0x0816, 0x0817, 0x0818, 0x0819, 0x081B, 0x081C, 0x081D, 0x081E, 0x081F, 0x0820, 0x0821, 0x0822,
+    0x0823, 0x0825, 0x0826, 0x0827, 0x0829, 0x082A, 0x082B, 0x082C, 0x082D, 0x0951, 0x0953, 0x0954,
+    0x0F82, 0x0F83, 0x0F86, 0x0F87, 0x135D, 0x135E, 0x135F, 0x17DD, 0x193A, 0x1A17, 0x1A75, 0x1A76,
+    0x1A77, 0x1A78, 0x1A79, 0x1A7A, 0x1A7B, 0x1A7C, 0x1B6B, 0x1B6D, 0x1B6E, 0x1B6F, 0x1B70, 0x1B71,
+    0x1B72, 0x1B73, 0x1CD0, 0x1CD1, 0x1CD2, 0x1CDA, 0x1CDB, 0x1CE0, 0x1DC0, 0x1DC1, 0x1DC3, 0x1DC4,
+    0x1DC5, 0x1DC6, 0x1DC7, 0x1DC8, 0x1DC9, 0x1DCB, 0x1DCC, 0x1DD1, 0x1DD2, 0x1DD3, 0x1DD4, 0x1DD5,
+    0x1DD6, 0x1DD7, 0x1DD8, 0x1DD9, 0x1DDA, 0x1DDB, 0x1DDC, 0x1DDD, 0x1DDE, 0x1DDF, 0x1DE0, 0x1DE1,
+    0x1DE2, 0x1DE3, 0x1DE4, 0x1DE5, 0x1DE6, 0x1DFE, 0x20D0, 0x20D1, 0x20D4, 0x20D5, 0x20D6, 0x20D7,
+    0x20DB, 0x20DC, 0x20E1, 0x20E7, 0x20E9, 0x20F0, 0x2CEF, 0x2CF0, 0x2CF1, 0x2DE0, 0x2DE1, 0x2DE2,
+    0x2DE3, 0x2DE4, 0x2DE5, 0x2DE6, 0x2DE7, 0x2DE8, 0x2DE9, 0x2DEA, 0x2DEB, 0x2DEC, 0x2DED, 0x2DEE,
+    0x2DEF, 0x2DF0, 0x2DF1, 0x2DF2, 0x2DF3, 0x2DF4, 0x2DF5, 0x2DF6, 0x2DF7, 0x2DF8, 0x2DF9, 0x2DFA,
+    0x2DFB, 0x2DFC, 0x2DFD, 0x2DFE, 0x2DFF, 0xA66F, 0xA67C, 0xA67D, 0xA6F0, 0xA6F1, 0xA8E0, 0xA8E1,
+    0xA8E2, 0xA8E3, 0xA8E4, 0xA8E5, 0xA8E6, 0xA8E7, 0xA8E8, 0xA8E9, 0xA8EA, 0xA8EB, 0xA8EC, 0xA8ED,
+    0xA8EE, 0xA8EF, 0xA8F0, 0xA8F1, 0xAAB0, 0xAAB2, 0xAAB3, 0xAAB7, 0xAAB8, 0xAABE, 0xAABF, 0xAAC1,
+    0xFE20, 0xFE21, 0xFE22, 0xFE23, 0xFE24, 0xFE25, 0xFE26, 0x10A0F, 0x10A38, 0x1D185, 0x1D186,
+    0x1D187, 0x1D188, 0x1D189, 0x1D1AA, 0x1D1AB, 0x1D1AC, 0x1D1AD, 0x1D242, 0x1D243, 0x1D244,
+];
+
+/// What a graphics command asks for (`a=`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Action {
+    /// `t`: keep the image for later placements
+    #[default]
+    Transmit,
+    /// `T`: keep the image and place it at the cursor
+    TransmitAndPut,
+    /// `q`: check that the image would load, without keeping it
+    Query,
+    /// `p`: place an image sent before
+    Put,
+    /// `d`: delete placements and images
+    Delete,
+    /// Animation frames and anything else
+    Unsupported,
+}
+
+/// Control data of an APC `_G` command (`a=T,f=100,i=1;<payload>`).
+/// Keys that are not given keep the protocol's defaults.
+#[derive(Debug, Clone)]
+pub struct KittyCommand {
+    pub action: Action,
+    /// `q`: 1 suppresses OK replies, 2 also errors
+    pub quiet: u32,
+    /// `f`: 24 (RGB), 32 (RGBA) or 100 (PNG)
+    pub format: u32,
+    /// `t`: d (direct), f (file), t (temporary file), s (shared memory)
+    pub medium: u8,
+    /// `o=z`: payload is zlib compressed
+    pub compressed: bool,
+    /// `s`, `v`: pixel size of RGB(A) data
+    pub width: u32,
+    pub height: u32,
+    /// `S`, `O`: bytes to read from a file and where to start
+    pub size: usize,
+    pub offset: usize,
+    /// `i`, `I`, `p`: image id, image number and placement id
+    pub image_id: u32,
+    pub image_number: u32,
+    pub placement_id: u32,
+    /// `m=1`: more chunks follow
+    pub more: bool,
+    /// `x`, `y`, `w`, `h`: part of the image to show (pixels).
+    /// Deletions by position reuse `x` and `y` as 1-based cell coordinates.
+    pub source_x: u32,
+    pub source_y: u32,
+    pub source_width: u32,
+    pub source_height: u32,
+    /// `X`, `Y`: offset inside the first cell (pixels)
+    pub cell_x_offset: u32,
+    pub cell_y_offset: u32,
+    /// `c`, `r`: cells to scale the image into
+    pub columns: u32,
+    pub rows: u32,
+    /// `z`: negative values draw under the text
+    pub z_index: i32,
+    /// `C=1`: leave the cursor where it is
+    pub stay: bool,
+    /// `U=1`: virtual placement, drawn where the program writes placeholder characters
+    pub unicode_placeholder: bool,
+    /// `d`: what to delete; upper case also frees the image data
+    pub delete: u8,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: Action::Transmit,
+            quiet: 0,
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            size: 0,
+            offset: 0,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            more: false,
+            source_x: 0,
+            source_y: 0,
+            source_width: 0,
+            source_height: 0,
+            cell_x_offset: 0,
+            cell_y_offset: 0,
+            columns: 0,
+            rows: 0,
+            z_index: 0,
+            stay: false,
+            unicode_placeholder: false,
+            delete: b'a',
+        }
+    }
+}
+
+impl KittyCommand {
+    /// Parse comma separated `key=value` pairs; unknown keys are ignored
+    pub fn parse(control: &[u8]) -> Self {
+        let mut command = Self::default();
+        for pair in control.split(|&b| b == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+                .unwrap_or(0);
+            let unsigned = number.clamp(0, u32::MAX as i64) as u32;
+            let first = value.first().copied().unwrap_or(0);
+            match key {
+                b'a' => {
+                    command.action = match first {
+                        b't' => Action::Transmit,
+                        b'T' => Action::TransmitAndPut,
+                        b'q' => Action::Query,
+                        b'p' => Action::Put,
+                        b'd' => Action::Delete,
+                        _ => Action::Unsupported,
+                    }
+                }
+                b'q' => command.quiet = unsigned,
+                b'f' => command.format = unsigned,
+                b't' => command.medium = first,
+                b'o' => command.compressed = first == b'z',
+                b's' => command.width = unsigned,
+                b'v' => command.height = unsigned,
+                b'S' => command.size = unsigned as usize,
+                b'O' => command.offset = unsigned as usize,
+                b'i' => command.image_id = unsigned,
+                b'I' => command.image_number = unsigned,
+                b'p' => command.placement_id = unsigned,
+                b'm' => command.more = unsigned == 1,
+                b'x' => command.source_x = unsigned,
+                b'y' => command.source_y = unsigned,
+                b'w' => command.source_width = unsigned,
+                b'h' => command.source_height = unsigned,
+                b'X' => command.cell_x_offset = unsigned,
+                b'Y' => command.cell_y_offset = unsigned,
+                b'c' => command.columns = unsigned,
+                b'r' => command.rows = unsigned,
+                b'z' => command.z_index = number.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+                b'C' => command.stay = unsigned == 1,
+                b'U' => command.unicode_placeholder = unsigned == 1,
+                b'd' => command.delete = first,
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+/// Image cell a placeholder character stands for. Row, column and the high byte of the
+/// image id come from up to three combining marks; missing ones follow from the cell to the left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaceholderCell {
+    /// Low 24 bits of the image id, from the foreground color
+    pub image_id: u32,
+    /// From the underline color, 0 for any placement of the image
+    pub placement_id: u32,
+    pub row: Option<u32>,
+    pub column: Option<u32>,
+    pub id_high: Option<u32>,
+}
+
+impl PlaceholderCell {
+    pub fn parse(fg: AnsiColor, underline: Option<AnsiColor>, marks: &[char]) -> Self {
+        let mut numbers = marks.iter().map(|&mark| {
+            DIACRITICS
+                .binary_search(&(mark as u32))
+                .ok()
+                .map(|n| n as u32)
+        });
+        Self {
+            image_id: color_id(fg),
+            placement_id: underline.map_or(0, color_id),
+            row: numbers.next().flatten(),
+            column: numbers.next().flatten(),
+            id_high: numbers.next().flatten(),
+        }
+    }
+}
+
+/// Id carried in a color: 24-bit colors hold three bytes, indexed colors one
+fn color_id(color: AnsiColor) -> u32 {
+    match color {
+        AnsiColor::Spec(rgb) => (rgb.r as u32) << 16 | (rgb.g as u32) << 8 | rgb.b as u32,
+        AnsiColor::Indexed(index) => index as u32,
+        AnsiColor::Named(_) => 0,
+    }
+}
+
+/// Pixels of a finished transmission
+pub struct KittyImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+/// Error carrying one of the protocol's error codes, sent back as `CODE:message`
+pub fn error(code: &str, message: impl fmt::Display) -> anyhow::Error {
+    anyhow::anyhow!("{}:{}", code, message)
+}
+
+/// Decode the base64 payload of a transmission into RGBA pixels.
+/// For `t=f` and `t=t` the payload is a file name and the data is read from that file.
+pub fn load(command: &KittyCommand, payload: &[u8]) -> Result<KittyImage> {
+    let payload = BASE64
+        .decode(payload)
+        .map_err(|e| error("EINVAL", format!("bad base64 payload: {}", e)))?;
+    let data = match command.medium {
+        b'd' => payload,
+        b'f' | b't' => read_file(command, &payload)?,
+        _ => return Err(error("EINVAL", "unsupported transmission medium")),
+    };
+    let data = if command.compressed {
+        inflate(&data)?
+    } else {
+        data
+    };
+    decode_pixels(command, data)
+}
+
+fn read_file(command: &KittyCommand, name: &[u8]) -> Result<Vec<u8>> {
+    let name = std::str::from_utf8(name).map_err(|_| error("EINVAL", "file name is not UTF-8"))?;
+    let path = Path::new(name);
+    let temporary = command.medium == b't';
+    // 프로그램이 지우라고 한 파일이 아무 파일이나 되지 않도록
+    if temporary && !is_temp_file(path) {
+        return Err(error("EPERM", format!("{} is not a temporary file", name)));
+    }
+
+    let result = (|| {
+        let mut file = File::open(path).map_err(|e| error("EBADF", e))?;
+        // 장치 파일은 끝없이 읽힐 수 있으므로 일반 파일만
+        if !file.metadata().is_ok_and(|metadata| metadata.is_file()) {
+            return Err(error("EINVAL", format!("{} is not a regular file", name)));
+        }
+        file.seek(SeekFrom::Start(command.offset as u64))
+            .map_err(|e| error("EBADF", e))?;
+        let limit = match command.size {
+            0 => MAX_DATA_BYTES,
+            size => size.min(MAX_DATA_BYTES),
+        };
+        let mut data = Vec::new();
+        file.take(limit as u64)
+            .read_to_end(&mut data)
+            .map_err(|e| error("EBADF", e))?;
+        Ok(data)
+    })();
+    if temporary {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+fn is_temp_file(path: &Path) -> bool {
+    let Ok(path) = fs::canonicalize(path) else {
+        return false;
+    };
+    let in_temp_dir = [std::env::temp_dir(), "/tmp".into(), "/dev/shm".into()]
+        .iter()
+        .filter_map(|dir| fs::canonicalize(dir).ok())
+        .any(|dir| path.starts_with(dir));
+    in_temp_dir
+        && path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().contains(TEMP_FILE_MARKER))
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut inflated = Vec::new();
+    ZlibDecoder::new(data)
+        .take(MAX_DATA_BYTES as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|e| error("EINVAL", format!("bad zlib data: {}", e)))?;
+    if inflated.len() > MAX_DATA_BYTES {
+        return Err(error("EFBIG", "image data is too large"));
+    }
+    Ok(inflated)
+}
+
+fn decode_pixels(command: &KittyCommand, data: Vec<u8>) -> Result<KittyImage> {
+    match command.format {
+        100 => {
+            let pixmap = tiny_skia::Pixmap::decode_png(&data).map_err(|e| error("EBADPNG", e))?;
+            // tiny-skia는 알파를 곱한 값으로 들고 있음
+            let rgba = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| {
+                    let color = pixel.demultiply();
+                    [color.red(), color.green(), color.blue(), color.alpha()]
+                })
+                .collect();
+            Ok(KittyImage {
+                width: pixmap.width(),
+                height: pixmap.height(),
+                rgba,
+            })
+        }
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            if width == 0 || height == 0 {
+                return Err(error("EINVAL", "image size (s, v) is missing"));
+            }
+            let channels = command.format as usize / 8;
+            let expected = (width as usize)
+                .checked_mul(height as usize)
+                .and_then(|pixels| pixels.checked_mul(channels));
+            if expected != Some(data.len()) {
+                return Err(error(
+                    "ENODATA",
+                    format!(
+                        "{}x{} image needs {} bytes, got {}",
+                        width,
+                        height,
+                        width as usize * height as usize * channels,
+                        data.len()
+                    ),
+                ));
+            }
+            let rgba = if channels == 4 {
+                data
+            } else {
+                data.chunks_exact(3)
+                    .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                    .collect()
+            };
+            Ok(KittyImage {
+                width,
+                height,
+                rgba,
+            })
+        }
+        format => Err(error("EINVAL", format!("unsupported format {}", format))),
+    }
+}
+
+/// Answer to `command` about image `image_id` (`ESC _ G i=1;OK ESC \`).
+/// Nothing is sent for commands without an id or number, or when `q` asks for quiet.
+pub fn reply(command: &KittyCommand, image_id: u32, result: &Result<()>) -> Option<String> {
+    if image_id == 0 && command.image_number == 0 {
+        return None;
+    }
+    let message = match result {
+        Ok(()) if command.quiet >= 1 => return None,
+        Ok(()) => "OK".to_string(),
+        Err(_) if command.quiet >= 2 => return None,
+        Err(e) => e.to_string(),
+    };
+    let mut keys = format!("i={}", image_id);
+    if command.image_number != 0 {
+        keys.push_str(&format!(",I={}", command.image_number));
+    }
+    if command.placement_id != 0 {
+        keys.push_str(&format!(",p={}", command.placement_id));
+    }
+    Some(format!("\x1b_G{};{}\x1b\\", keys, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn encode(data: &[u8]) -> Vec<u8> {
+        BASE64.encode(data).into_bytes()
+    }
+
+    #[test]
+    fn test_parse_and_reply() {
+        let command = KittyCommand::parse(b"a=T,f=24,s=2,v=1,i=7,p=3,z=-5,q=1,bogus,m=1");
+        assert_eq!(command.action, Action::TransmitAndPut);
+        assert_eq!((command.format, command.width, command.height), (24, 2, 1));
+        assert_eq!((command.image_id, command.placement_id), (7, 3));
+        assert_eq!(command.z_index, -5);
+        assert!(command.more);
+
+        // q=1: 성공은 조용히, 오류는 알림
+        assert_eq!(reply(&command, 7, &Ok(())), None);
+        let failed = Err(error("ENOENT", "no such image"));
+        assert_eq!(
+            reply(&command, 7, &failed).unwrap(),
+            "\x1b_Gi=7,p=3;ENOENT:no such image\x1b\\"
+        );
+        assert_eq!(reply(&KittyCommand::parse(b"a=q"), 0, &Ok(())), None);
+    }
+
+    #[test]
+    fn test_placeholder_cell() {
+        use alacritty_terminal::vte::ansi::Rgb;
+        // 24비트 색 (0, 1, 2) = 이미지 258, 256색 밑줄 7 = 배치 7, 행 2, 열 0
+        let fg = AnsiColor::Spec(Rgb { r: 0, g: 1, b: 2 });
+        let marks = ['\u{030E}', '\u{0305}'];
+        let cell = PlaceholderCell::parse(fg, Some(AnsiColor::Indexed(7)), &marks);
+        assert_eq!((cell.image_id, cell.placement_id), (258, 7));
+        assert_eq!(
+            (cell.row, cell.column, cell.id_high),
+            (Some(2), Some(0), None)
+        );
+        assert_eq!(DIACRITICS[296], 0x1D244);
+    }
+
+    #[test]
+    fn test_load_direct_data() {
+        // RGB 2x1, zlib 압축
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[255, 0, 0, 0, 0, 255]).unwrap();
+        let payload = encode(&encoder.finish().unwrap());
+        let command = KittyCommand::parse(b"f=24,s=2,v=1,o=z");
+        let image = load(&command, &payload).unwrap();
+        assert_eq!(image.rgba, [255, 0, 0, 255, 0, 0, 255, 255]);
+
+        let short = KittyCommand::parse(b"f=32,s=2,v=2");
+        let message = load(&short, &encode(&[0; 4])).err().unwrap().to_string();
+        assert!(message.starts_with("ENODATA:"));
+
+        let mut pixmap = tiny_skia::Pixmap::new(1, 1).unwrap();
+        pixmap.fill(tiny_skia::Color::from_rgba8(0, 255, 0, 255));
+        let png = KittyCommand::parse(b"f=100");
+        let image = load(&png, &encode(&pixmap.encode_png().unwrap())).unwrap();
+        assert_eq!((image.width, image.height), (1, 1));
+        assert_eq!(image.rgba, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_temporary_file_is_deleted() {
+        let path = std::env::temp_dir().join(format!(
+            "{}-sterm-test-{}.rgba",
+            TEMP_FILE_MARKER,
+            std::process::id()
+        ));
+        fs::write(&path, [1, 2, 3, 4]).unwrap();
+        let name = encode(path.to_str().unwrap().as_bytes());
+
+        let command = KittyCommand::parse(b"f=32,s=1,v=1,t=t");
+        assert_eq!(load(&command, &name).unwrap().rgba, [1, 2, 3, 4]);
+        assert!(!path.exists());
+
+        // 표시가 없는 이름은 임시 파일로 보지 않음
+        let other = std::env::temp_dir().join(format!("sterm-test-{}.rgba", std::process::id()));
+        fs::write(&other, [1, 2, 3, 4]).unwrap();
+        let name = encode(other.to_str().unwrap().as_bytes());
+        assert!(load(&command, &name).is_err());
+        assert!(other.exists());
+        fs::remove_file(other).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+use alacritty_terminal::{
+    grid::{Dimensions, Grid},
+    index::{Column, Line, Point},
+    term::cell::{Cell, Flags},
+};
+use anyhow::Result;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::{LinkConfig, URL_PATTERN};
+use crate::utils::platform::Platform;
+
+/// `path:line[:col]` references as printed by compilers, grep and test runners
+pub const FILE_PATH_PATTERN: &str =
+    r"(?:~|\.{1,2})?/?(?:[\w.\-]+/)*[\w\-]+\.[A-Za-z0-9]+:\d+(?::\d+)?";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Explicit OSC 8 hyperlink stored on the cells
+    Hyperlink,
+    /// URL detected in the visible text
+    Url,
+    /// `file:line:col` reference detected in the visible text
+    FilePath,
+}
+
+/// A link found on a single visible line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkMatch {
+    pub kind: LinkKind,
+    pub line: Line,
+    pub start_col: usize,
+    /// Exclusive end column
+    pub end_col: usize,
+    /// Text shown on screen
+    pub text: String,
+    /// What gets opened: the OSC 8 URI, or the matched text
+    pub target: String,
+}
+
+impl LinkMatch {
+    pub fn contains(&self, point: Point) -> bool {
+        point.line == self.line && point.column.0 >= self.start_col && point.column.0 < self.end_col
+    }
+}
+
+/// Text of one grid line together with the column each character starts at
+pub struct LineText {
+    pub text: String,
+    /// (byte offset in `text`, column, cell width) per character cell
+    cells: Vec<(usize, usize, usize)>,
+}
+
+impl LineText {
+    pub fn from_grid(grid: &Grid<Cell>, line: Line) -> Self {
+        let mut text = String::new();
+        let mut cells = Vec::new();
+
+        for col in 0..grid.columns() {
+            let cell = &grid[line][Column(col)];
+            if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                continue;
+            }
+            let width = if cell.flags.contains(Flags::WIDE_CHAR) {
+                2
+            } else {
+                1
+            };
+            cells.push((text.len(), col, width));
+            text.push(cell.c);
+            text.extend(cell.zerowidth().unwrap_or(&[]).iter());
+        }
+
+        Self { text, cells }
+    }
+
+    /// Convert a byte range of `text` to a column range (end exclusive)
+    pub fn column_range(&self, start: usize, end: usize) -> (usize, usize) {
+        let start_col = self
+            .cells
+            .iter()
+            .rev()
+            .find(|(offset, _, _)| *offset <= start)
+            .map(|(_, col, _)| *col)
+            .unwrap_or(0);
+        let end_col = self
+            .cells
+            .iter()
+            .rev()
+            .find(|(offset, _, _)| *offset < end)
+            .map(|(_, col, width)| col + width)
+            .unwrap_or(start_col);
+        (start_col, end_col)
+    }
+
+    /// All non-overlapping matches of `regex` as (start_col, end_col, text)
+    pub fn find_all(&self, regex: &Regex) -> Vec<(usize, usize, String)> {
+        regex
+            .find_iter(&self.text)
+            .filter_map(|m| {
+                let matched = trim_trailing_punctuation(m.as_str());
+                if matched.is_empty() {
+                    return None;
+                }
+                let (start_col, end_col) = self.column_range(m.start(), m.start() + matched.len());
+                Some((start_col, end_col, matched.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Sentence punctuation right after a URL or path is almost never part of it.
+/// Closing brackets stay when they close one opened inside the match (`wiki/Foo_(bar)`).
+fn trim_trailing_punctuation(text: &str) -> &str {
+    let unbalanced = |text: &str, open: char, close: char| {
+        text.matches(open).count() < text.matches(close).count()
+    };
+    let mut text = text;
+    while let Some(last) = text.chars().next_back() {
+        let trim = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' => true,
+            ')' => unbalanced(text, '(', ')'),
+            ']' => unbalanced(text, '[', ']'),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        text = &text[..text.len() - last.len_utf8()];
+    }
+    text
+}
+
+fn url_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(URL_PATTERN).expect("valid URL pattern"))
+}
+
+fn file_path_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(FILE_PATH_PATTERN).expect("valid file path pattern"))
+}
+
+/// Whether `text` starts with a URL scheme recognized by link detection
+pub fn is_url(text: &str) -> bool {
+    url_regex().find(text).is_some_and(|m| m.start() == 0)
+}
+
+/// Lines currently shown in the viewport, top to bottom
+pub fn visible_lines(grid: &Grid<Cell>) -> impl Iterator<Item = Line> {
+    let top = -(grid.display_offset() as i32);
+    (0..grid.screen_lines() as i32).map(move |i| Line(top + i))
+}
+
+/// OSC 8 hyperlink under `point`, extended over neighbouring cells with the same link
+pub fn hyperlink_at(grid: &Grid<Cell>, point: Point) -> Option<LinkMatch> {
+    let row = &grid[point.line];
+    let hyperlink = row[point.column].hyperlink()?;
+    let same_link = |col: usize| row[Column(col)].hyperlink().as_ref() == Some(&hyperlink);
+
+    let mut start_col = point.column.0;
+    while start_col > 0 && same_link(start_col - 1) {
+        start_col -= 1;
+    }
+    let mut end_col = point.column.0 + 1;
+    while end_col < grid.columns() && same_link(end_col) {
+        end_col += 1;
+    }
+
+    let text = (start_col..end_col)
+        .map(|col| &row[Column(col)])
+        .filter(|cell| !cell.flags.contains(Flags::WIDE_CHAR_SPACER))
+        .map(|cell| cell.c)
+        .collect();
+
+    Some(LinkMatch {
+        kind: LinkKind::Hyperlink,
+        line: point.line,
+        start_col,
+        end_col,
+        text,
+        target: hyperlink.uri().to_string(),
+    })
+}
+
+/// URLs and `file:line:col` paths detected in the text of `line`
+///
+/// URLs win over paths when both match the same text (e.g. `http://host:8080/a.rs:1`).
+pub fn detect_links(grid: &Grid<Cell>, line: Line) -> Vec<LinkMatch> {
+    let line_text = LineText::from_grid(grid, line);
+    let mut links: Vec<LinkMatch> = line_text
+        .find_all(url_regex())
+        .into_iter()
+        .map(|(start_col, end_col, text)| LinkMatch {
+            kind: LinkKind::Url,
+            line,
+            start_col,
+            end_col,
+            target: text.clone(),
+            text,
+        })
+        .collect();
+
+    for (start_col, end_col, text) in line_text.find_all(file_path_regex()) {
+        let overlaps = links
+            .iter()
+            .any(|link| start_col < link.end_col && link.start_col < end_col);
+        if !overlaps {
+            links.push(LinkMatch {
+                kind: LinkKind::FilePath,
+                line,
+                start_col,
+                end_col,
+                target: text.clone(),
+                text,
+            });
+        }
+    }
+
+    links
+}
+
+/// Link under `point`: an OSC 8 hyperlink first, then detected URLs and paths
+pub fn link_at(grid: &Grid<Cell>, point: Point) -> Option<LinkMatch> {
+    if point.column.0 >= grid.columns()
+        || point.line < grid.topmost_line()
+        || point.line > grid.bottommost_line()
+    {
+        return None;
+    }
+    hyperlink_at(grid, point).or_else(|| {
+        detect_links(grid, point.line)
+            .into_iter()
+            .find(|link| link.contains(point))
+    })
+}
+
+/// Split a `path:line[:col]` reference into its parts
+pub fn parse_file_reference(text: &str) -> (&str, Option<u32>, Option<u32>) {
+    let mut parts = text.rsplitn(3, ':').collect::<Vec<_>>();
+    parts.reverse();
+    match parts.as_slice() {
+        [path, line, col] if line.parse::<u32>().is_ok() => {
+            (*path, line.parse().ok(), col.parse().ok())
+        }
+        [path, line] => (*path, line.parse().ok(), None),
+        // "a:b:10" 처럼 경로에 ':'가 있는 경우 마지막 숫자만 줄 번호로 취급
+        [first, second, line] => {
+            let path_len = first.len() + 1 + second.len();
+            (&text[..path_len], line.parse().ok(), None)
+        }
+        _ => (text, None, None),
+    }
+}
+
+/// Relative paths are relative to the shell that printed them, not to sterm
+fn resolve_path(path: &str, cwd: Option<&Path>) -> PathBuf {
+    match cwd {
+        Some(cwd) if Path::new(path).is_relative() && !path.starts_with('~') => cwd.join(path),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Open a link with the configured opener
+///
+/// Paths go through `file_opener` when set so editors can jump to the line and column.
+/// `cwd` is the working directory of the session the link was found in.
+pub fn open_link(link: &LinkMatch, cwd: Option<&Path>, config: &LinkConfig) -> Result<()> {
+    log::info!("Opening {:?} link: {}", link.kind, link.target);
+    if link.kind != LinkKind::FilePath {
+        return Platform::open_with(&config.opener, &link.target);
+    }
+
+    let (path, line, column) = parse_file_reference(&link.target);
+    let path = resolve_path(path, cwd).to_string_lossy().into_owned();
+    match &config.file_opener {
+        Some(template) => {
+            let line = line.unwrap_or(1).to_string();
+            let column = column.unwrap_or(1).to_string();
+            let args: Vec<String> = template
+                .split_whitespace()
+                .map(|arg| {
+                    arg.replace("{path}", &path)
+                        .replace("{line}", &line)
+                        .replace("{column}", &column)
+                })
+                .collect();
+            Platform::spawn_command(&args)
+        }
+        None => Platform::open_with(&config.opener, &path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_regex_trims_punctuation() {
+        let m = url_regex().find("see https://example.com/a?b=1).").unwrap();
+        assert_eq!(
+            trim_trailing_punctuation(m.as_str()),
+            "https://example.com/a?b=1"
+        );
+    }
+
+    #[test]
+    fn test_balanced_parentheses_are_kept() {
+        let wiki = "https://en.wikipedia.org/wiki/Foo_(bar)";
+        assert_eq!(trim_trailing_punctuation(wiki), wiki);
+        assert_eq!(
+            trim_trailing_punctuation("https://en.wikipedia.org/wiki/Foo_(bar))."),
+            wiki
+        );
+        assert_eq!(
+            trim_trailing_punctuation("https://example.com/a]"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn test_relative_paths_use_session_cwd() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            resolve_path("src/main.rs", Some(cwd)),
+            PathBuf::from("/home/user/project/src/main.rs")
+        );
+        assert_eq!(
+            resolve_path("../lib.rs", Some(cwd)),
+            PathBuf::from("/home/user/project/../lib.rs")
+        );
+        assert_eq!(
+            resolve_path("/etc/hosts", Some(cwd)),
+            PathBuf::from("/etc/hosts")
+        );
+        assert_eq!(
+            resolve_path("~/notes.md", Some(cwd)),
+            PathBuf::from("~/notes.md")
+        );
+        assert_eq!(
+            resolve_path("src/main.rs", None),
+            PathBuf::from("src/main.rs")
+        );
+    }
+
+    #[test]
+    fn test_file_path_regex() {
+        let m = file_path_regex()
+            .find("error at src/main.rs:10:5 here")
+            .unwrap();
+        assert_eq!(m.as_str(), "src/main.rs:10:5");
+        assert!(file_path_regex().find("no path here").is_none());
+    }
+
+    #[test]
+    fn test_parse_file_reference() {
+        assert_eq!(
+            parse_file_reference("src/main.rs:10:5"),
+            ("src/main.rs", Some(10), Some(5))
+        );
+        assert_eq!(
+            parse_file_reference("main.rs:7"),
+            ("main.rs", Some(7), None)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::output::LineAssembler;
+use super::SessionId;
+use crate::config::{LogFormat, LoggingConfig};
+use crate::utils::platform::Platform;
+
+/// What the file name template can refer to
+pub struct LogFileFields<'a> {
+    pub id: SessionId,
+    pub title: &'a str,
+    pub profile: Option<&'a str>,
+}
+
+/// Output of one session being written to a file
+pub struct SessionLog {
+    path: PathBuf,
+    file: BufWriter<File>,
+    format: LogFormat,
+    lines: LineAssembler,
+}
+
+impl SessionLog {
+    /// Open (or append to) the log file named by the configured template
+    pub fn open(config: &LoggingConfig, fields: &LogFileFields) -> Result<Self> {
+        let directory = match &config.directory {
+            Some(directory) => directory.clone(),
+            None => Platform::data_dir()?.join("logs"),
+        };
+        fs::create_dir_all(&directory)?;
+        let path = directory.join(file_name(&config.file_name, fields, Local::now()));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        log::info!("Logging session {} to {}", fields.id, path.display());
+        Ok(Self {
+            path,
+            file: BufWriter::new(file),
+            format: config.format,
+            lines: LineAssembler::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append output read from the PTY
+    pub fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        match self.format {
+            LogFormat::Raw => self.file.write_all(bytes)?,
+            LogFormat::Plain | LogFormat::Timestamped => {
+                for line in self.lines.push(bytes) {
+                    self.write_line(&line)?;
+                }
+            }
+        }
+        // 비정상 종료해도 기록이 남도록 읽을 때마다 내보냄
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        if self.format == LogFormat::Timestamped {
+            write!(self.file, "[{}] ", Local::now().format("%Y-%m-%d %H:%M:%S"))?;
+        }
+        writeln!(self.file, "{}", line)?;
+        Ok(())
+    }
+}
+
+impl Drop for SessionLog {
+    fn drop(&mut self) {
+        // 줄바꿈 없이 끝난 마지막 줄 (프롬프트 등)도 남김
+        let partial = self.lines.partial().to_string();
+        if self.format != LogFormat::Raw && !partial.is_empty() {
+            let _ = self.write_line(&partial);
+        }
+        let _ = self.file.flush();
+    }
+}
+
+/// Fill in the template, keeping the result a single file name
+fn file_name(template: &str, fields: &LogFileFields, now: DateTime<Local>) -> String {
+    let name = template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H-%M-%S").to_string())
+        .replace("{profile}", fields.profile.unwrap_or("default"))
+        .replace("{title}", fields.title)
+        .replace("{id}", &fields.id.to_string());
+    let name = sanitize_file_name(&name);
+    if name.is_empty() || name.starts_with('.') {
+        format!("session-{}{}", fields.id, name)
+    } else {
+        name
+    }
+}
+
+/// Replace path separators and control characters so `name` stays one file name
+pub fn sanitize_file_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_file_name_template() {
+        let now = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap();
+        let fields = LogFileFields {
+            id: 2,
+            title: "vim src/main.rs",
+            profile: None,
+        };
+        assert_eq!(
+            file_name("{date}_{time}_{profile}_{title}.log", &fields, now),
+            "2024-03-09_14-05-00_default_vim src_main.rs.log"
+        );
+        assert_eq!(file_name("../{id}", &fields, now), "session-2.._2");
+    }
+
+    #[test]
+    fn test_timestamped_log() {
+        let directory = std::env::temp_dir().join(format!("sterm-log-test-{}", std::process::id()));
+        let config = LoggingConfig {
+            format: LogFormat::Timestamped,
+            file_name: "{id}.log".to_string(),
+            directory: Some(directory.clone()),
+            ..LoggingConfig::default()
+        };
+        let fields = LogFileFields {
+            id: 0,
+            title: "",
+            profile: None,
+        };
+        let mut log = SessionLog::open(&config, &fields).unwrap();
+        log.write(b"\x1b[32mok\x1b[0m\r\n$ ").unwrap();
+        let path = log.path().to_path_buf();
+        drop(log);
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with('[') && lines[0].ends_with("] ok"));
+        assert!(lines[1].ends_with("] $ "));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,29 +1,111 @@
+pub mod broadcast;
+pub mod export;
+pub mod graphics;
+pub mod hints;
+pub mod kitty;
+pub mod links;
+pub mod logging;
+pub mod output;
+pub mod persistence;
+pub mod pty_loop;
+pub mod queries;
+pub mod screenshot;
+pub mod sixel;
+pub mod triggers;
+pub mod vi_mode;
+
 use alacritty_terminal::{
     event::{Event, EventListener, Notify, WindowSize},
-    event_loop::{EventLoop, Msg, Notifier},
-    grid::{Dimensions, Grid},
-    index::{Column, Line, Point},
-    selection::SelectionRange,
+    event_loop::Msg,
+    grid::{Dimensions, Grid, Scroll},
+    index::{Boundary, Column, Direction, Line, Point, Side},
+    selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
-    term::{cell::Cell, test::TermSize, Config as TermConfig, Term, TermMode},
+    term::{
+        cell::{Cell, Flags},
+        color::Colors,
+        search::RegexSearch,
+        test::TermSize,
+        Config as TermConfig, Osc52, Term, TermMode,
+    },
     tty::{self, Options as TtyOptions, Shell},
+    vte::ansi::{CursorShape, CursorStyle as TermCursorStyle, NamedColor, Processor, Rgb},
 };
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     mpsc, Arc,
 };
 use tokio::sync::Mutex;
 
-use crate::config::Config;
+use crate::config::{
+    ClipboardAccess, ClipboardConfig, Config, CursorStyle, ExitBehavior, ExportFormat, HintAction,
+    HintConfig, LoggingConfig, ProfileConfig, ScreenshotFormat, ZoomScope,
+};
 use crate::utils::color::{Color, ColorTheme};
-use crate::utils::font::FontMetrics;
+use crate::utils::font::{
+    FontManager, FontMetrics, FontStyle, FontWeight, TextAttributes, UnderlineStyle,
+};
+use crate::utils::keybinding::KeyBinding;
+use crate::utils::platform::Platform;
+use broadcast::Broadcast;
+use export::{ExportRange, ExportStyle};
+use graphics::{GraphicsFilter, ImageSlice, ImageStore, PlaceholderLayout};
+use hints::{HintMatch, HintState};
+use links::{LineText, LinkKind, LinkMatch};
+use logging::{LogFileFields, SessionLog};
+use output::{LineAssembler, OutputTap, ReplyWriter, TappedPty};
+use persistence::{SavedSessions, SavedTab};
+use pty_loop::{Notifier, PtyLoop};
+use screenshot::ScreenRegion;
+use triggers::{TriggerHit, Triggers};
+use vi_mode::{ViCommand, ViModeState};
 
 static SESSION_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub type SessionId = usize;
-pub type UIUpdateCallback = Box<dyn Fn(SessionId, String) + Send + Sync>;
+type PtyThread = std::thread::JoinHandle<()>;
+
+/// How to start a session; anything left unset falls back to the config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionOptions {
+    /// Program and arguments to run instead of the configured shell
+    pub command: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    pub title: Option<String>,
+    pub env: HashMap<String, String>,
+    /// Keep the tab open after the program exits, whatever the exit code
+    pub hold: bool,
+    /// Output of a previous run, drawn above the new shell
+    pub restored_output: Option<String>,
+    /// `[profiles]` entry the session was opened with, for its per-session settings
+    pub profile: Option<String>,
+}
+
+/// Font zoom step requested by a key binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoomAction {
+    In,
+    Out,
+    Reset,
+}
+
+/// Shell started on a session's PTY, used to find out what runs in the foreground
+struct ShellProcess {
+    pid: u32,
+    /// Duplicate of the PTY master that stays open while the reader thread owns the original
+    pty: std::fs::File,
+}
+
+/// What to do with a session whose shell has exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionExitAction {
+    Close,
+    /// Keep the tab open showing the exit banner until Enter restarts the shell
+    Hold,
+}
 
 /// Renderable terminal content (from tterm/mterm)
 #[derive(Clone)]
@@ -35,6 +117,14 @@ pub struct RenderableContent {
     pub terminal_size: TerminalSize,
     pub cursor_line: usize,
     pub cursor_col: usize,
+    pub cursor_shape: CursorShape,
+    pub cursor_blinking: bool,
+    pub cursor_wide: bool,
+    pub colors: Colors,
+    pub display_offset: usize,
+    pub vi_mode: bool,
+    /// 이미지 배치 줄이 고정된 셀
+    pub image_anchors: Vec<graphics::Anchor>,
 }
 
 impl Default for RenderableContent {
@@ -47,18 +137,17 @@ impl Default for RenderableContent {
             terminal_size: TerminalSize::default(),
             cursor_line: 0,
             cursor_col: 0,
+            cursor_shape: CursorShape::Block,
+            cursor_blinking: false,
+            cursor_wide: false,
+            colors: Colors::default(),
+            display_offset: 0,
+            vi_mode: false,
+            image_anchors: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum TerminalEvent {
-    Output(String),
-    TitleChanged(String),
-    Bell,
-    Exit,
-}
-
 /// Colored text segment for rendering
 #[derive(Debug, Clone)]
 pub struct ColoredTextSegment {
@@ -73,6 +162,8 @@ pub struct ColoredTextSegment {
     pub y: i32,      // 절대 Y 위치 (픽셀)
     pub width: i32,  // 텍스트 폭 (픽셀)
     pub height: i32, // 텍스트 높이 (픽셀)
+    pub attributes: TextAttributes,
+    pub underline_color: Color,
 }
 
 /// Extracted terminal content with color information
@@ -81,26 +172,65 @@ pub struct ColoredTerminalContent {
     pub segments: Vec<ColoredTextSegment>,
     pub cursor_line: usize,
     pub cursor_col: usize,
+    pub cursor_shape: CursorShape,
+    pub cursor_blinking: bool,
+    pub cursor_wide: bool,
+    pub cursor_color: Color,
+    pub vi_mode: bool,
+    pub background: Color,
+    pub foreground: Color,
     pub total_lines: usize,
     pub total_cols: usize,
+    /// Image rows anchored to visible cells, ordered by z-index (negative ones go under the text)
+    pub images: Vec<ImageSlice>,
 }
 
+/// alacritty_terminal's primary device attributes reply (VT102)
+const TERM_PRIMARY_ATTRIBUTES: &str = "\x1b[?6c";
+/// VT220 with Sixel graphics (4) and ANSI colors (22)
+const SIXEL_PRIMARY_ATTRIBUTES: &str = "\x1b[?62;4;22c";
+
 // EventProxy - PTY 이벤트를 수신하여 UI로 전달
 #[derive(Clone)]
 pub struct EventProxy {
     sender: mpsc::Sender<Event>,
+    /// Sixel을 그리므로 DA1 응답에 알림
+    sixel: bool,
+    /// PTY로 돌려보낼 응답 (그래픽 필터가 직접 만든 응답 포함)
+    replies: ReplyWriter,
 }
 
 impl EventProxy {
-    pub fn new() -> (Self, mpsc::Receiver<Event>) {
+    pub fn new(sixel: bool) -> (Self, mpsc::Receiver<Event>) {
         let (sender, receiver) = mpsc::channel();
-        (Self { sender }, receiver)
+        let proxy = Self {
+            sender,
+            sixel,
+            replies: ReplyWriter::default(),
+        };
+        (proxy, receiver)
     }
 }
 
 impl EventListener for EventProxy {
     fn send_event(&self, event: Event) {
-        if let Err(_) = self.sender.send(event) {
+        let event = match event {
+            Event::PtyWrite(text) => {
+                // Term은 DA1에 고정된 값으로 답하므로 지원하는 기능을 넣은 응답으로 바꿈
+                let text = if self.sixel && text == TERM_PRIMARY_ATTRIBUTES {
+                    SIXEL_PRIMARY_ATTRIBUTES.to_string()
+                } else {
+                    text
+                };
+                // 응답은 UI 스레드를 거치지 않고 PTY 스레드에서 바로 씀
+                match self.replies.write(text) {
+                    Some(text) => Event::PtyWrite(text),
+                    None => return,
+                }
+            }
+            event => event,
+        };
+        if self.sender.send(event).is_err() {
             log::warn!("Failed to send PTY event: receiver may have been dropped");
         }
     }
@@ -148,40 +278,60 @@ pub struct TerminalSession {
     pub term: Arc<FairMutex<Term<EventProxy>>>,
     pub notifier: Notifier,
     pub size: TerminalSize,
-    pub content: Arc<Mutex<String>>, // UI 렌더링용 콘텐츠
-    pub ui_callback: Option<Arc<UIUpdateCallback>>,
     pub is_running: Arc<Mutex<bool>>,
     pub last_content: RenderableContent,
+    pub theme: ColorTheme,
+    pub enable_bold: bool,
+    pub enable_italic: bool,
+    pub vi_mode: ViModeState,
+    /// 마지막으로 종료된 셸의 종료 코드 (시그널로 종료되면 None)
+    pub exit_code: Option<i32>,
+    /// 셸이 종료된 뒤 재시작을 기다리는 중
+    pub held: bool,
+    /// 탭별 확대/축소 크기 (ZoomScope::Tab일 때만 사용)
+    pub font_size: Option<i32>,
+    event_proxy: EventProxy,
+    pty_thread: Option<PtyThread>,
+    shell: ShellProcess,
+    /// 셸을 시작한 옵션 (재시작할 때도 사용)
+    options: SessionOptions,
+    /// 트리거와 기록이 읽는 PTY 출력 사본
+    output_tap: OutputTap,
+    lines: LineAssembler,
+    triggers: Triggers,
+    session_log: Option<SessionLog>,
+    /// 트리거가 탭에 붙인 배지
+    pub badge: Option<String>,
+    /// Sixel 등으로 받은 이미지 (격자 셀에 고정되어 함께 스크롤됨)
+    images: ImageStore,
+    /// 프로필을 반영한 OSC 52 정책
+    clipboard: ClipboardConfig,
 }
 
 impl TerminalSession {
     pub fn new(
         id: SessionId,
-        shell: &str,
+        config: &Config,
+        mut options: SessionOptions,
         pty_event_proxy_sender: mpsc::Sender<(SessionId, Event)>,
     ) -> Result<Self> {
-        log::info!("Creating new terminal session {} with shell: {}", id, shell);
+        log::info!(
+            "Creating new terminal session {} with shell: {}",
+            id,
+            options.command.first().unwrap_or(&config.terminal.shell)
+        );
 
-        // PTY 설정 - tterm 방식
-        let pty_config = TtyOptions {
-            shell: Some(Shell::new(
-                shell.to_string(),
-                vec!["-i".to_string(), "-l".to_string()],
-            )),
-            working_directory: None,
-            env: std::collections::HashMap::new(),
-            ..TtyOptions::default()
+        // Terminal 설정 - 기본 커서 스타일은 설정에서, DECSCUSR로 앱이 변경 가능
+        let clipboard = Self::session_clipboard(config, &options);
+        let term_config = TermConfig {
+            default_cursor_style: Self::term_cursor_style(config),
+            osc52: Self::term_osc52(&clipboard),
+            ..TermConfig::default()
         };
-
-        // Terminal 설정
-        let term_config = TermConfig::default();
         let terminal_size = TerminalSize::default();
 
         // EventProxy 생성
-        let (event_proxy, event_receiver) = EventProxy::new();
-
-        // PTY 생성 (tterm 방식)
-        let pty = tty::new(&pty_config, terminal_size.into(), id as u64)?;
+        let (event_proxy, event_receiver) = EventProxy::new(config.graphics.sixel);
 
         // Terminal 생성
         let term_size = TermSize::new(
@@ -199,52 +349,102 @@ impl TerminalSession {
             cursor: term.grid_mut().cursor_cell().clone(),
             cursor_line: 0,
             cursor_col: 0,
+            cursor_shape: term.cursor_style().shape,
+            cursor_blinking: term.cursor_style().blinking,
+            cursor_wide: false,
+            colors: *term.colors(),
+            display_offset: 0,
+            vi_mode: false,
+            image_anchors: Vec::new(),
         };
 
-        let term = Arc::new(FairMutex::new(term));
+        // 복원된 탭은 새 셸보다 먼저 이전 출력과 구분 표시를 그림
+        let restored_output = options.restored_output.take();
+        if let Some(banner) = persistence::restore_banner(restored_output.as_deref()) {
+            let mut parser: Processor = Processor::new();
+            parser.advance(&mut term, banner.as_bytes());
+        }
+        if let Some(dir) = options
+            .working_directory
+            .as_ref()
+            .filter(|dir| !dir.is_dir())
+        {
+            log::warn!(
+                "Working directory {} does not exist, using the default",
+                dir.display()
+            );
+            options.working_directory = None;
+        }
 
-        // EventLoop 생성 및 시작
-        let pty_event_loop = EventLoop::new(
-            term.clone(),
-            event_proxy,
-            pty,
-            false, // hold
-            false, // ref_test
+        let term = Arc::new(FairMutex::new(term));
+        let triggers = Self::session_triggers(config, &options);
+        let output_tap = OutputTap::default();
+        let images = ImageStore::new(config.graphics.memory_limit_mb * 1024 * 1024);
+        let (notifier, pty_thread, shell) = Self::spawn_pty(
+            id,
+            config,
+            terminal_size,
+            &options,
+            &term,
+            &event_proxy,
+            &output_tap,
+            &images,
         )?;
 
-        let notifier = Notifier(pty_event_loop.channel());
-
-        // EventLoop를 백그라운드에서 실행
-        let _pty_event_loop_handle = pty_event_loop.spawn();
-
-        let content = Arc::new(Mutex::new(String::new()));
         let is_running = Arc::new(Mutex::new(true));
 
-        let session = Self {
+        let mut session = Self {
             id,
-            title: format!("Terminal {}", id + 1),
+            title: options
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("Terminal {}", id + 1)),
             term,
             notifier,
             size: terminal_size,
-            content: content.clone(),
-            ui_callback: None,
             is_running: is_running.clone(),
             last_content: initial_content,
+            theme: ColorTheme::from_config(&config.ui),
+            enable_bold: config.terminal.enable_bold,
+            enable_italic: config.terminal.enable_italic,
+            vi_mode: ViModeState::default(),
+            exit_code: None,
+            held: false,
+            font_size: None,
+            event_proxy,
+            pty_thread: Some(pty_thread),
+            shell,
+            options,
+            output_tap,
+            lines: LineAssembler::default(),
+            triggers,
+            session_log: None,
+            badge: None,
+            images,
+            clipboard,
         };
 
-        // PTY 이벤트 구독 스레드 시작 (tterm 방식) - 이벤트 로깅만
+        let profile_logging = session
+            .profile_config(config)
+            .is_some_and(|profile| profile.logging);
+        if config.logging.enabled || profile_logging {
+            if let Err(e) = session.start_logging(&config.logging) {
+                log::error!("Failed to start logging session {}: {}", id, e);
+            }
+        }
+        session.update_output_tap();
+
+        // PTY 이벤트 구독 스레드 시작 (tterm 방식)
+        // 셸이 종료돼도 재시작할 수 있도록 Exit에서 멈추지 않고, Term이 사라져 채널이 닫히면 종료
         let _pty_event_subscription = std::thread::Builder::new()
             .name(format!("pty_event_subscription_{}", id))
-            .spawn(move || loop {
-                if let Ok(event) = event_receiver.recv() {
+            .spawn(move || {
+                while let Ok(event) = event_receiver.recv() {
                     log::debug!("PTY event received for session {}: {:?}", id, event);
-                    if let Err(e) = pty_event_proxy_sender.send((id, event.clone())) {
+                    if let Err(e) = pty_event_proxy_sender.send((id, event)) {
                         log::warn!("pty_event_subscription_{}: Failed to send PtyEvent: {}. Receiver may have been dropped.", id, e);
                         break; // Exit the loop if receiver is gone
                     }
-                    if let Event::Exit = event {
-                        break;
-                    }
                 }
             })?;
 
@@ -254,16 +454,684 @@ impl TerminalSession {
             id
         );
 
-        // 초기 프롬프트 출력을 위해 newline 전송
-        session.notifier.notify(b"\n");
+        // 초기 프롬프트 출력을 위해 newline 전송 (셸 대신 실행한 프로그램에는 보내지 않음)
+        if session.options.command.is_empty() {
+            session.notifier.notify(b"\n");
+        }
 
         log::info!("Terminal session {} created successfully", id);
         Ok(session)
     }
 
-    // UI 콜백 설정
-    pub fn set_ui_callback(&mut self, callback: Arc<UIUpdateCallback>) {
-        self.ui_callback = Some(callback);
+    /// Start the shell on a new PTY driving `term`, returning its input channel, reader thread and process
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_pty(
+        id: SessionId,
+        config: &Config,
+        size: TerminalSize,
+        options: &SessionOptions,
+        term: &Arc<FairMutex<Term<EventProxy>>>,
+        event_proxy: &EventProxy,
+        output_tap: &OutputTap,
+        images: &ImageStore,
+    ) -> Result<(Notifier, PtyThread, ShellProcess)> {
+        let shell = match options.command.split_first() {
+            Some((program, args)) => Shell::new(program.clone(), args.to_vec()),
+            None => Shell::new(
+                config.terminal.shell.to_string(),
+                vec!["-i".to_string(), "-l".to_string()],
+            ),
+        };
+
+        // 셸 안에서 실행한 `sterm msg`가 기본으로 이 탭을 가리키도록
+        let mut env = options.env.clone();
+        env.insert(crate::ipc::SESSION_ENV.to_string(), id.to_string());
+
+        // PTY 설정 - tterm 방식
+        let pty_config = TtyOptions {
+            shell: Some(shell),
+            working_directory: options.working_directory.clone(),
+            env,
+            ..TtyOptions::default()
+        };
+        let graphics = GraphicsFilter::new(images.clone(), &config.graphics, size.into());
+        let pty = TappedPty::new(
+            tty::new(&pty_config, size.into(), id as u64)?,
+            output_tap.clone(),
+            graphics,
+        )?;
+        let shell = ShellProcess {
+            pid: pty.pty().child().id(),
+            pty: pty.pty().file().try_clone()?,
+        };
+
+        let pty_loop = PtyLoop::new(term.clone(), event_proxy.clone(), pty, images.clone())?;
+        let sender = pty_loop.channel();
+        event_proxy
+            .replies
+            .connect(move |bytes| Notifier(sender.clone()).notify(bytes));
+        let notifier = Notifier(pty_loop.channel());
+
+        // PTY 읽기와 쓰기를 백그라운드에서 실행
+        let pty_thread = pty_loop.spawn(format!("pty_reader_{}", id))?;
+        Ok((notifier, pty_thread, shell))
+    }
+
+    /// Current directory of the shell
+    pub fn working_directory(&self) -> Option<PathBuf> {
+        if self.held {
+            return self.options.working_directory.clone();
+        }
+        Platform::process_cwd(self.shell.pid)
+    }
+
+    /// Plain text of the last `max_lines` lines of scrollback and screen, trailing blanks removed
+    pub fn history_text(&self, max_lines: usize) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let text_of = |line: i32| {
+            LineText::from_grid(grid, Line(line))
+                .text
+                .trim_end()
+                .to_string()
+        };
+
+        // 화면 아래쪽 빈 줄은 건너뛰고 마지막으로 내용이 있는 줄부터 max_lines만큼
+        let top = grid.topmost_line().0;
+        let mut last = grid.bottommost_line().0;
+        while last >= top && text_of(last).is_empty() {
+            last -= 1;
+        }
+        let first = (last + 1 - max_lines.min(grid.total_lines()) as i32).max(top);
+        (first..=last).map(text_of).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Export the selection, or the whole scrollback and screen, keeping whitespace and colors
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        selection: bool,
+        font_family: &str,
+    ) -> Result<String> {
+        let term = self.term.lock();
+        let range = if selection {
+            let range = term
+                .selection
+                .as_ref()
+                .and_then(|selection| selection.to_range(&*term))
+                .ok_or_else(|| anyhow::anyhow!("Nothing is selected in session {}", self.id))?;
+            ExportRange::selection(range)
+        } else {
+            ExportRange::all(term.grid())
+        };
+        let style = ExportStyle {
+            theme: &self.theme,
+            colors: term.colors(),
+            title: &self.title,
+            font_family,
+        };
+        Ok(export::export(term.grid(), range, format, &style))
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.term
+            .lock()
+            .selection
+            .as_ref()
+            .is_some_and(|selection| !selection.is_empty())
+    }
+
+    /// Plain text of the visible screen, trailing blank lines removed
+    pub fn screen_text(&self) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let lines: Vec<String> = (0..grid.screen_lines() as i32)
+            .map(|line| {
+                LineText::from_grid(grid, Line(line))
+                    .text
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        lines.join("\n").trim_end().to_string()
+    }
+
+    /// Name of the foreground process on the PTY, unless it is the shell itself
+    pub fn foreground_process(&self) -> Option<String> {
+        use std::os::unix::io::AsRawFd;
+
+        if self.held {
+            return None;
+        }
+        // SAFETY: self.shell.pty는 세션이 소유한 열린 PTY master fd
+        let pgrp = unsafe { libc::tcgetpgrp(self.shell.pty.as_raw_fd()) };
+        if pgrp <= 0 || pgrp as u32 == self.shell.pid {
+            return None;
+        }
+        Platform::process_name(pgrp as u32)
+    }
+
+    /// Record the exit code reported by the PTY reader (`Event::ChildExit`)
+    pub fn record_child_exit(&mut self, code: i32) {
+        log::info!("Shell of session {} exited with code {}", self.id, code);
+        self.exit_code = Some(code);
+    }
+
+    /// Handle the shell exiting: close the tab or hold it open with a restart banner
+    pub fn handle_exit(&mut self, behavior: ExitBehavior) -> SessionExitAction {
+        if let Ok(mut running) = self.is_running.try_lock() {
+            *running = false;
+        }
+        let failed = self.exit_code != Some(0);
+        // --hold로 시작한 세션은 정책과 관계없이 열어 둠
+        let hold = self.options.hold
+            || match behavior {
+                ExitBehavior::Close => false,
+                ExitBehavior::HoldOnError => failed,
+                ExitBehavior::Hold => true,
+            };
+        if !hold {
+            return SessionExitAction::Close;
+        }
+
+        self.held = true;
+        let status = match self.exit_code {
+            Some(code) => format!("Process exited with code {}", code),
+            None => "Process exited".to_string(),
+        };
+        let banner = format!(
+            "\r\n\x1b[0;7m[{} — press Enter to restart]\x1b[0m\r\n",
+            status
+        );
+        let mut term = self.term.lock();
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut *term, banner.as_bytes());
+        drop(term);
+        self.request_redraw();
+
+        SessionExitAction::Hold
+    }
+
+    /// Start a fresh shell in the same tab, keeping the old output as history.
+    /// Returns the old PTY thread to be joined once the manager lock is released.
+    pub fn restart(&mut self, config: &Config) -> Result<Option<StoppedPty>> {
+        log::info!("Restarting shell in session {}", self.id);
+        let _ = self.notifier.0.send(Msg::Shutdown);
+        let stopped = self.pty_thread.take().map(|thread| StoppedPty {
+            id: self.id,
+            thread,
+        });
+
+        let (notifier, pty_thread, shell) = Self::spawn_pty(
+            self.id,
+            config,
+            self.size,
+            &self.options,
+            &self.term,
+            &self.event_proxy,
+            &self.output_tap,
+            &self.images,
+        )?;
+        self.notifier = notifier;
+        self.pty_thread = Some(pty_thread);
+        self.shell = shell;
+        self.exit_code = None;
+        self.held = false;
+        if let Ok(mut running) = self.is_running.try_lock() {
+            *running = true;
+        }
+        Ok(stopped)
+    }
+
+    /// Global triggers followed by the ones of the session's profile
+    fn session_triggers(config: &Config, options: &SessionOptions) -> Triggers {
+        let profile = options
+            .profile
+            .as_ref()
+            .and_then(|name| config.profiles.get(name));
+        Triggers::new(
+            config
+                .triggers
+                .iter()
+                .chain(profile.into_iter().flat_map(|profile| &profile.triggers)),
+        )
+    }
+
+    /// Global OSC 52 policy with the overrides of the session's profile
+    fn session_clipboard(config: &Config, options: &SessionOptions) -> ClipboardConfig {
+        match options
+            .profile
+            .as_ref()
+            .and_then(|name| config.profiles.get(name))
+        {
+            Some(profile) => config.clipboard.with_overrides(&profile.clipboard),
+            None => config.clipboard.clone(),
+        }
+    }
+
+    /// OSC 52 policy the session was opened with
+    pub fn clipboard(&self) -> &ClipboardConfig {
+        &self.clipboard
+    }
+
+    fn profile_config<'a>(&self, config: &'a Config) -> Option<&'a ProfileConfig> {
+        self.options
+            .profile
+            .as_ref()
+            .and_then(|name| config.profiles.get(name))
+    }
+
+    /// Copy PTY output only while triggers or a log need it
+    fn update_output_tap(&self) {
+        self.output_tap
+            .set_enabled(!self.triggers.is_empty() || self.session_log.is_some());
+    }
+
+    pub fn is_logging(&self) -> bool {
+        self.session_log.is_some()
+    }
+
+    /// Start writing the session's output to a new log file, returning its path
+    pub fn start_logging(&mut self, config: &LoggingConfig) -> Result<PathBuf> {
+        let session_log = SessionLog::open(
+            config,
+            &LogFileFields {
+                id: self.id,
+                title: &self.title,
+                profile: self.options.profile.as_deref(),
+            },
+        )?;
+        let path = session_log.path().to_path_buf();
+        self.session_log = Some(session_log);
+        self.update_output_tap();
+        Ok(path)
+    }
+
+    /// Stop logging, returning the path of the closed log file
+    pub fn stop_logging(&mut self) -> Option<PathBuf> {
+        let session_log = self.session_log.take()?;
+        log::info!("Stopped logging session {}", self.id);
+        self.update_output_tap();
+        Some(session_log.path().to_path_buf())
+    }
+
+    /// Run kitty deletions that pick placements by their position on the screen.
+    /// `d=c` uses the cursor as it is now, which may have moved on since the command.
+    fn apply_image_deletes(&self) {
+        let deletes = self.images.take_deletes();
+        if deletes.is_empty() {
+            return;
+        }
+        let term = self.term.lock();
+        let cursor = term.grid().cursor.point;
+        let screen_lines = 0..term.screen_lines() as i32;
+        // 화면에 있는 배치마다 맨 위 왼쪽 칸 (화면 줄, 열)
+        let mut visible: HashMap<u64, (Arc<graphics::Placement>, i32, usize)> = HashMap::new();
+        for anchor in self.images.anchors() {
+            if !screen_lines.contains(&anchor.line) {
+                continue;
+            }
+            if let Some(placement) = self.images.placement(anchor.placement) {
+                let line = anchor.line - anchor.row as i32;
+                visible.insert(anchor.placement, (placement, line, anchor.column));
+            }
+        }
+        for command in deletes {
+            let deleted: HashSet<u64> = visible
+                .iter()
+                .filter(|(_, (placement, line, col))| {
+                    graphics::deleted_at(
+                        &command,
+                        placement,
+                        *line,
+                        *col,
+                        (cursor.line.0, cursor.column.0),
+                    )
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            self.images.delete(
+                |placement| deleted.contains(&placement.id),
+                command.delete.is_ascii_uppercase(),
+            );
+        }
+    }
+
+    /// Log and run the triggers over the output read since the last call.
+    /// Sends and highlights are done here; the other hits are returned for the manager and UI.
+    pub fn process_output(&mut self) -> Vec<TriggerHit> {
+        self.apply_image_deletes();
+        let bytes = self.output_tap.take();
+        if bytes.is_empty() {
+            return Vec::new();
+        }
+        if let Some(session_log) = self.session_log.as_mut() {
+            if let Err(e) = session_log.write(&bytes) {
+                log::error!("Failed to write log of session {}: {}", self.id, e);
+                self.stop_logging();
+            }
+        }
+        if self.triggers.is_empty() {
+            return Vec::new();
+        }
+
+        let completed = self.lines.push(&bytes);
+        // 완료된 줄의 번호 (None: 아직 끝나지 않은 커서 줄)
+        let mut hits = Vec::new();
+        for (index, line) in completed.iter().enumerate() {
+            hits.extend(
+                self.triggers
+                    .line_completed(line)
+                    .into_iter()
+                    .map(|hit| (Some(index), hit)),
+            );
+        }
+        let partial = self.lines.partial().to_string();
+        hits.extend(
+            self.triggers
+                .partial_line(&partial)
+                .into_iter()
+                .map(|hit| (None, hit)),
+        );
+
+        // 줄바꿈, 화면 지우기, 커서 이동이 있었을 수 있으므로 줄이 실제로 있는 위치를 찾음
+        let highlighted: Vec<usize> = (0..completed.len())
+            .filter(|index| {
+                hits.iter().any(|(line, hit)| {
+                    *line == Some(*index) && matches!(hit, TriggerHit::Highlight { .. })
+                })
+            })
+            .collect();
+        let rows = if highlighted.is_empty() {
+            Vec::new()
+        } else {
+            let lines: Vec<&str> = highlighted
+                .iter()
+                .map(|&index| completed[index].as_str())
+                .collect();
+            triggers::locate_lines(self.term.lock().grid(), &lines)
+        };
+
+        let mut remaining = Vec::new();
+        for (line, hit) in hits {
+            match hit {
+                TriggerHit::Send(text) => {
+                    log::info!("Trigger sends {:?} to session {}", text, self.id);
+                    let _ = self.write(&text);
+                }
+                TriggerHit::Highlight {
+                    text,
+                    foreground,
+                    background,
+                } => {
+                    let mut term = self.term.lock();
+                    let row = match line {
+                        Some(index) => highlighted
+                            .iter()
+                            .position(|&highlighted| highlighted == index)
+                            .and_then(|position| rows[position]),
+                        None => Some(term.grid().cursor.point.line.0),
+                    };
+                    let done = row.is_some_and(|row| {
+                        triggers::highlight(term.grid_mut(), row, &text, foreground, background)
+                    });
+                    if !done {
+                        log::debug!("Trigger highlight {:?} is no longer on screen", text);
+                    }
+                }
+                hit => remaining.push(hit),
+            }
+        }
+        remaining
+    }
+
+    /// Map the configured cursor style onto alacritty's default cursor style
+    fn term_cursor_style(config: &Config) -> TermCursorStyle {
+        let shape = match config.ui.cursor_style {
+            CursorStyle::Block => CursorShape::Block,
+            CursorStyle::Underline => CursorShape::Underline,
+            CursorStyle::Beam => CursorShape::Beam,
+        };
+        TermCursorStyle {
+            shape,
+            blinking: config.ui.cursor_blinking,
+        }
+    }
+
+    /// Let alacritty forward only the OSC 52 directions the clipboard policy doesn't deny
+    fn term_osc52(clipboard: &ClipboardConfig) -> Osc52 {
+        match (
+            clipboard.osc52_write != ClipboardAccess::Deny,
+            clipboard.osc52_read != ClipboardAccess::Deny,
+        ) {
+            (true, true) => Osc52::CopyPaste,
+            (true, false) => Osc52::OnlyCopy,
+            (false, true) => Osc52::OnlyPaste,
+            (false, false) => Osc52::Disabled,
+        }
+    }
+
+    /// Update terminal focus, reporting it to applications that enabled focus events (DECSET 1004)
+    pub fn set_focused(&self, focused: bool) {
+        let mut term = self.term.lock();
+        if term.is_focused == focused {
+            return;
+        }
+        term.is_focused = focused;
+        if term.mode().contains(TermMode::FOCUS_IN_OUT) {
+            let report: &'static [u8] = if focused { b"\x1b[I" } else { b"\x1b[O" };
+            self.notifier.notify(report);
+        }
+    }
+
+    /// Current value of a palette entry: the OSC-set override if any, otherwise the theme color
+    pub fn palette_color(&self, index: usize) -> Color {
+        let term = self.term.lock();
+        self.theme.resolve_index(index, term.colors())
+    }
+
+    /// Answer an OSC 4/10/11/12 color query (`Event::ColorRequest`)
+    pub fn respond_color_request(
+        &self,
+        index: usize,
+        format: &(dyn Fn(Rgb) -> String + Send + Sync),
+    ) {
+        let color = self.palette_color(index);
+        let reply = format(Rgb {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        });
+        log::debug!(
+            "Color query reply for session {} index {}: {:?}",
+            self.id,
+            index,
+            reply
+        );
+        self.notifier.notify(reply.into_bytes());
+    }
+
+    /// Link (OSC 8 hyperlink, URL or file path) at a viewport cell
+    pub fn link_at(&self, row: usize, col: usize) -> Option<LinkMatch> {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let line = Line(row as i32 - grid.display_offset() as i32);
+        links::link_at(grid, Point::new(line, Column(col)))
+    }
+
+    /// 화면에 보이는 줄에서 힌트 정규식에 맞는 항목을 찾아 라벨을 붙인다
+    pub fn start_hints(&self, hint: &HintConfig, alphabet: &str) -> Option<HintState> {
+        let term = self.term.lock();
+        HintState::new(term.grid(), hint, alphabet)
+    }
+
+    /// PTY 출력이 없어도 UI가 다시 그리도록 Wakeup 이벤트를 보냄
+    fn request_redraw(&self) {
+        self.event_proxy.send_event(Event::Wakeup);
+    }
+
+    pub fn is_vi_mode(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::VI)
+    }
+
+    /// 복사 모드(vi 모드) 켜기/끄기. 끌 때는 선택을 지우고 맨 아래로 돌아감
+    pub fn toggle_vi_mode(&mut self) {
+        let mut term = self.term.lock();
+        term.toggle_vi_mode();
+        self.vi_mode = ViModeState::default();
+        if !term.mode().contains(TermMode::VI) {
+            term.selection = None;
+            term.scroll_display(Scroll::Bottom);
+        }
+        drop(term);
+        self.request_redraw();
+    }
+
+    /// 복사 모드 키 처리. `y`로 복사한 텍스트를 반환
+    pub fn vi_key(&mut self, text: &str, ctrl: bool) -> Option<String> {
+        let command = self.vi_mode.handle_key(text, ctrl);
+        let mut yanked = None;
+        let mut term = self.term.lock();
+
+        match command {
+            ViCommand::None => {}
+            ViCommand::Motion(motion) => term.vi_motion(motion),
+            ViCommand::Top => {
+                let point = Point::new(term.topmost_line(), Column(0));
+                term.vi_goto_point(point);
+            }
+            ViCommand::Bottom => {
+                let point = Point::new(term.bottommost_line(), Column(0));
+                term.vi_goto_point(point);
+            }
+            ViCommand::Scroll(half_pages) => {
+                let lines = half_pages * (term.screen_lines() as i32 / 2).max(1);
+                let point = term.vi_mode_cursor.scroll(&*term, lines).point;
+                term.scroll_display(Scroll::Delta(lines));
+                term.vi_goto_point(point);
+            }
+            ViCommand::InlineSearch {
+                needle,
+                direction,
+                till,
+            } => {
+                if let Some(point) = Self::inline_search(&term, needle, direction, till) {
+                    term.vi_goto_point(point);
+                }
+            }
+            ViCommand::Search { pattern, direction } => match RegexSearch::new(&pattern) {
+                Ok(mut regex) => {
+                    // 현재 위치의 매치는 건너뛰도록 한 칸 옮겨서 검색
+                    let point = term.vi_mode_cursor.point;
+                    let origin = match direction {
+                        Direction::Right => point.add(&*term, Boundary::None, 1),
+                        Direction::Left => point.sub(&*term, Boundary::None, 1),
+                    };
+                    match term.search_next(&mut regex, origin, direction, Side::Left, None) {
+                        Some(found) => term.vi_goto_point(*found.start()),
+                        None => log::info!("Pattern not found: {}", pattern),
+                    }
+                }
+                Err(e) => log::warn!("Invalid search pattern {:?}: {}", pattern, e),
+            },
+            ViCommand::ToggleSelection(ty) => Self::toggle_selection(&mut term, ty),
+            ViCommand::Yank => {
+                yanked = term.selection_to_string();
+                term.selection = None;
+            }
+            ViCommand::Cancel if term.selection.is_some() => term.selection = None,
+            ViCommand::Cancel | ViCommand::Exit => {
+                drop(term);
+                self.toggle_vi_mode();
+                return None;
+            }
+        }
+
+        drop(term);
+        self.request_redraw();
+        yanked
+    }
+
+    /// `f`/`t` 검색: 현재 줄에서 `needle`의 위치 (`till`이면 그 바로 앞 칸)
+    fn inline_search(
+        term: &Term<EventProxy>,
+        needle: char,
+        direction: Direction,
+        till: bool,
+    ) -> Option<Point> {
+        let origin = term.vi_mode_cursor.point;
+        // till은 바로 옆 칸의 매치를 건너뛰어야 `;` 반복이 앞으로 나아감
+        let skip = if till { 2 } else { 1 };
+        let start = match direction {
+            Direction::Right => origin.add(term, Boundary::None, skip),
+            Direction::Left => origin.sub(term, Boundary::None, skip),
+        };
+        if start.line != origin.line {
+            return None;
+        }
+
+        let mut buf = [0; 4];
+        let needles = needle.encode_utf8(&mut buf);
+        let found = match direction {
+            Direction::Right => term.inline_search_right(start, needles),
+            Direction::Left => term.inline_search_left(start, needles),
+        }
+        .ok()?;
+
+        Some(match (till, direction) {
+            (false, _) => found,
+            (true, Direction::Right) => found.sub(term, Boundary::None, 1),
+            (true, Direction::Left) => found.add(term, Boundary::None, 1),
+        })
+    }
+
+    /// 같은 종류면 선택 해제, 다른 종류면 종류만 바꾸고, 없으면 vi 커서에서 새로 시작
+    fn toggle_selection(term: &mut Term<EventProxy>, ty: SelectionType) {
+        match term.selection.as_ref().map(|selection| selection.ty) {
+            Some(current) if current == ty => term.selection = None,
+            Some(_) => {
+                if let Some(selection) = term.selection.as_mut() {
+                    selection.ty = ty;
+                }
+            }
+            None => {
+                let mut selection = Selection::new(ty, term.vi_mode_cursor.point, Side::Left);
+                selection.include_all();
+                term.selection = Some(selection);
+            }
+        }
+    }
+
+    /// 복사 모드 상태 표시 문자열 (복사 모드가 아니면 None)
+    pub fn vi_status(&self) -> Option<String> {
+        let term = self.term.lock();
+        if !term.mode().contains(TermMode::VI) {
+            return None;
+        }
+        if let Some(prompt) = self.vi_mode.prompt() {
+            return Some(prompt);
+        }
+        let status = match term.selection.as_ref().map(|selection| selection.ty) {
+            Some(SelectionType::Simple) | Some(SelectionType::Semantic) => "-- VISUAL --",
+            Some(SelectionType::Lines) => "-- VISUAL LINE --",
+            Some(SelectionType::Block) => "-- VISUAL BLOCK --",
+            None => "-- COPY --",
+        };
+        Some(status.to_string())
+    }
+
+    /// Shape to draw the cursor with, `in_viewport` telling whether it is scrolled into view
+    pub fn cursor_shape<T>(terminal: &Term<T>, in_viewport: bool) -> CursorShape {
+        // vi 커서 > 숨김(스크롤로 화면 밖 포함) > 포커스 없음(hollow) > 앱이 지정한 모양 순으로 결정
+        if terminal.mode().contains(TermMode::VI) && in_viewport {
+            CursorShape::Block
+        } else if !terminal.mode().contains(TermMode::SHOW_CURSOR) || !in_viewport {
+            CursorShape::Hidden
+        } else if !terminal.is_focused {
+            CursorShape::HollowBlock
+        } else {
+            terminal.cursor_style().shape
+        }
     }
 
     /// Sync terminal state and return renderable content (from tterm/mterm)
@@ -276,97 +1144,143 @@ impl TerminalSession {
         };
 
         let cursor = terminal.grid_mut().cursor_cell().clone();
+        let cursor_style = terminal.cursor_style();
+        let vi_mode = terminal.mode().contains(TermMode::VI);
+        let display_offset = terminal.grid().display_offset();
+        // 복사 모드에서는 vi 커서를 항상 블록으로 표시
+        let mut point: Point = if vi_mode {
+            terminal.vi_mode_cursor.point
+        } else {
+            terminal.grid().cursor.point
+        };
+        let viewport_row = point.line.0 + display_offset as i32;
+        let in_viewport = viewport_row >= 0 && viewport_row < terminal.screen_lines() as i32;
+        let cursor_shape = Self::cursor_shape(&terminal, in_viewport);
         let grid_ref = terminal.grid();
-        let point: Point = grid_ref.cursor.point;
+        // 와이드 문자 위의 커서는 첫 번째 셀에서 두 칸 폭으로 표시
+        if grid_ref[point].flags.contains(Flags::WIDE_CHAR_SPACER) && point.column.0 > 0 {
+            point.column -= 1;
+        }
+        let cursor_wide = grid_ref[point].flags.contains(Flags::WIDE_CHAR);
         self.last_content.grid = grid_ref.clone();
         self.last_content.selectable_range = selectable_range;
         self.last_content.cursor = cursor.clone();
         self.last_content.terminal_mode = *terminal.mode();
         self.last_content.terminal_size = self.size;
-        self.last_content.cursor_line = point.line.0 as usize;
-        self.last_content.cursor_col = point.column.0 as usize;
+        self.last_content.cursor_line = viewport_row.max(0) as usize;
+        self.last_content.cursor_col = point.column.0;
+        self.last_content.cursor_shape = cursor_shape;
+        self.last_content.cursor_blinking = cursor_style.blinking;
+        self.last_content.cursor_wide = cursor_wide;
+        self.last_content.colors = *terminal.colors();
+        self.last_content.display_offset = display_offset;
+        self.last_content.vi_mode = vi_mode;
+        self.last_content.image_anchors = self.images.anchors();
         &self.last_content
     }
 
-    /// Extract text from terminal grid
-    pub fn extract_terminal_text(&mut self) -> String {
-        let content = self.sync();
-        let grid = &content.grid;
-        let mut result = String::new();
-
-        // Grid를 순회해서 텍스트 추출 (alacritty 방식)
-        for indexed in grid.display_iter() {
-            let cell = indexed.cell;
-            let ch = cell.c;
-
-            // 줄바꿈 처리
-            if indexed.point.column.0 == 0 && indexed.point.line.0 > 0 {
-                result.push('\n');
-            }
+    /// Build the text attributes for a cell, honoring the bold/italic config switches
+    fn cell_attributes(flags: Flags, enable_bold: bool, enable_italic: bool) -> TextAttributes {
+        let underline = if flags.contains(Flags::DOUBLE_UNDERLINE) {
+            UnderlineStyle::Double
+        } else if flags.contains(Flags::UNDERCURL) {
+            UnderlineStyle::Curly
+        } else if flags.contains(Flags::DOTTED_UNDERLINE) {
+            UnderlineStyle::Dotted
+        } else if flags.contains(Flags::DASHED_UNDERLINE) {
+            UnderlineStyle::Dashed
+        } else if flags.contains(Flags::UNDERLINE) {
+            UnderlineStyle::Single
+        } else {
+            UnderlineStyle::None
+        };
 
-            // 문자 추가 (공백이 아닌 경우만)
-            if ch != ' ' || result.chars().last() != Some(' ') {
-                result.push(ch);
-            }
-        }
+        let weight = if enable_bold && flags.contains(Flags::BOLD) {
+            FontWeight::Bold
+        } else {
+            FontWeight::Normal
+        };
+        let style = if enable_italic && flags.contains(Flags::ITALIC) {
+            FontStyle::Italic
+        } else {
+            FontStyle::Normal
+        };
 
-        // 끝의 빈 줄들 제거
-        result.trim_end().to_string()
+        TextAttributes::new()
+            .with_weight(weight)
+            .with_style(style)
+            .with_underline(underline)
+            .with_strikethrough(flags.contains(Flags::STRIKEOUT))
+            .with_hidden(flags.contains(Flags::HIDDEN))
     }
 
-    /// Extract text with color information from terminal grid
+    /// Extract text with color and style information from terminal grid
+    ///
+    /// A new segment starts whenever the line, colors, text attributes or underline color change.
+    /// Positions come from grid columns: wide characters span two cells, and cells carrying
+    /// zero-width characters (combining marks, ZWJ sequences) keep them attached to the base cell.
+    /// Both are emitted as standalone segments so font advance differences never shift the line.
     pub fn extract_colored_terminal_content(
         &mut self,
         font_metrics: &FontMetrics,
     ) -> ColoredTerminalContent {
         let session_id = self.id; // Copy id first to avoid borrow issues
+        let theme = self.theme.clone();
+        let (enable_bold, enable_italic) = (self.enable_bold, self.enable_italic);
+        let images = self.images.clone();
         let content = self.sync();
         let grid = &content.grid;
-        let theme = ColorTheme::default();
-        let mut segments = Vec::new();
+        let mut segments: Vec<ColoredTextSegment> = Vec::new();
+        let mut image_slices: Vec<ImageSlice> = Vec::new();
+        let mut placeholders = PlaceholderLayout::default();
+        let mut current: Option<ColoredTextSegment> = None;
+        // 현재 세그먼트가 단독 셀(와이드/결합 문자)인지 여부
+        let mut current_isolated = false;
 
         log::debug!("Starting color extraction for session {}", session_id);
 
-        // display_iter()를 사용해서 실제 색상 정보 추출하되 줄별로 정리
-        let mut current_line = 0;
-        let mut line_text = String::new();
-        let mut line_segments = Vec::new();
-        let mut current_segment_text = String::new();
-        let mut current_fg = theme.foreground;
-        let mut current_bg = theme.background;
-        let mut segment_start_col = 0;
-        let mut accumulated_x_by_line: std::collections::HashMap<usize, i32> =
-            std::collections::HashMap::new();
-
         for indexed in grid.display_iter() {
             let cell = indexed.cell;
-            let ch = cell.c;
-            let line_num = indexed.point.line.0 as usize;
-            let _col_num = indexed.point.column.0 as usize;
-
-            // Skip wide char spacers
-            if cell
-                .flags
-                .contains(alacritty_terminal::term::cell::Flags::WIDE_CHAR_SPACER)
-            {
+            // 스크롤백을 보고 있으면 화면 맨 위 줄이 음수 Line이므로 뷰포트 기준 행으로 변환
+            let line_num = (indexed.point.line.0 + content.display_offset as i32) as usize;
+            let col = indexed.point.column.0;
+
+            // 와이드 문자의 두 번째 셀은 앞 셀이 두 칸을 차지하므로 건너뜀
+            if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
                 continue;
             }
 
+            let cell_cols = if cell.flags.contains(Flags::WIDE_CHAR) {
+                2
+            } else {
+                1
+            };
+            let mut character = cell.c;
+            let mut zerowidth = cell.zerowidth().unwrap_or(&[]);
+            // kitty 자리 표시 문자: 색과 결합 문자가 가리키는 이미지 칸을 그리고 글자는 공백으로
+            if character == kitty::PLACEHOLDER {
+                placeholders.push(
+                    line_num,
+                    col,
+                    kitty::PlaceholderCell::parse(cell.fg, cell.underline_color(), zerowidth),
+                );
+                character = ' ';
+                zerowidth = &[];
+            }
+            let isolated = cell_cols > 1 || !zerowidth.is_empty();
+
             // Get actual colors from indexed cell
-            let mut fg_color = theme.convert_ansi_color(&indexed.fg);
-            let mut bg_color = theme.convert_ansi_color(&indexed.bg);
-
-            // Apply cell flags
-            if cell
-                .flags
-                .contains(alacritty_terminal::term::cell::Flags::INVERSE)
-            {
+            let mut fg_color = theme.convert_ansi_color(&indexed.fg, &content.colors);
+            let mut bg_color = theme.convert_ansi_color(&indexed.bg, &content.colors);
+
+            // Apply cell flags (선택 영역은 반전으로 표시)
+            let selected = content
+                .selectable_range
+                .is_some_and(|range| range.contains(indexed.point));
+            if cell.flags.contains(Flags::INVERSE) != selected {
                 std::mem::swap(&mut fg_color, &mut bg_color);
             }
-            if cell.flags.intersects(
-                alacritty_terminal::term::cell::Flags::DIM
-                    | alacritty_terminal::term::cell::Flags::DIM_BOLD,
-            ) {
+            if cell.flags.intersects(Flags::DIM | Flags::DIM_BOLD) {
                 fg_color = Color {
                     r: ((fg_color.r as f32) * 0.7) as u8,
                     g: ((fg_color.g as f32) * 0.7) as u8,
@@ -375,114 +1289,82 @@ impl TerminalSession {
                 };
             }
 
-            // 새 줄이 시작되면 이전 줄 처리
-            if line_num != current_line {
-                // 이전 줄의 마지막 세그먼트 추가
-                if !current_segment_text.is_empty() {
-                    let current_line_x = accumulated_x_by_line.get(&current_line).unwrap_or(&0);
-                    let text_width =
-                        (current_segment_text.chars().count() as i32) * font_metrics.char_width;
-                    let abs_x = font_metrics.padding_x + current_line_x;
-                    let abs_y =
-                        font_metrics.padding_y + (current_line as i32) * font_metrics.line_height;
-
-                    line_segments.push(ColoredTextSegment {
-                        text: current_segment_text.clone(),
-                        fg_color: current_fg,
-                        bg_color: current_bg,
-                        line: current_line,
-                        start_col: segment_start_col,
-                        end_col: segment_start_col + current_segment_text.chars().count(),
-                        x: abs_x,
-                        y: abs_y,
-                        width: text_width,
-                        height: font_metrics.line_height,
-                    });
-
-                    // 누적 X 위치 업데이트
-                    accumulated_x_by_line.insert(current_line, current_line_x + text_width);
-                }
+            let attributes = Self::cell_attributes(cell.flags, enable_bold, enable_italic);
+            // SGR 58 밑줄 색상, 지정되지 않으면 전경색
+            let underline_color = cell
+                .underline_color()
+                .map(|color| theme.convert_ansi_color(&color, &content.colors))
+                .unwrap_or(fg_color);
+
+            let same_style = current.as_ref().is_some_and(|seg| {
+                !isolated
+                    && !current_isolated
+                    && seg.line == line_num
+                    && seg.end_col == col
+                    && seg.fg_color == fg_color
+                    && seg.bg_color == bg_color
+                    && seg.attributes == attributes
+                    && seg.underline_color == underline_color
+            });
 
-                // 줄별 세그먼트들을 메인 리스트에 추가
-                segments.extend(line_segments.clone());
-
-                // 새 줄 초기화
-                current_line = line_num;
-                line_text.clear();
-                line_segments.clear();
-                current_segment_text.clear();
-                segment_start_col = 0;
-                current_fg = fg_color;
-                current_bg = bg_color;
-            }
-
-            // 색상이 변경되면 새 세그먼트 시작
-            let colors_changed = fg_color.r != current_fg.r
-                || fg_color.g != current_fg.g
-                || fg_color.b != current_fg.b
-                || bg_color.r != current_bg.r
-                || bg_color.g != current_bg.g
-                || bg_color.b != current_bg.b;
-
-            if colors_changed && !current_segment_text.is_empty() {
-                // 현재 세그먼트 저장 (절대 위치 계산 포함)
-                let current_line_x = accumulated_x_by_line.get(&current_line).unwrap_or(&0);
-                let text_width =
-                    (current_segment_text.chars().count() as i32) * font_metrics.char_width;
-                let abs_x = font_metrics.padding_x + current_line_x;
-                let abs_y =
-                    font_metrics.padding_y + (current_line as i32) * font_metrics.line_height;
-
-                line_segments.push(ColoredTextSegment {
-                    text: current_segment_text.clone(),
-                    fg_color: current_fg,
-                    bg_color: current_bg,
-                    line: current_line,
-                    start_col: segment_start_col,
-                    end_col: segment_start_col + current_segment_text.chars().count(),
-                    x: abs_x,
-                    y: abs_y,
-                    width: text_width,
+            if !same_style {
+                segments.extend(current.take());
+                current_isolated = isolated;
+
+                current = Some(ColoredTextSegment {
+                    text: String::new(),
+                    fg_color,
+                    bg_color,
+                    line: line_num,
+                    start_col: col,
+                    end_col: col,
+                    x: font_metrics.padding_x + (col as i32) * font_metrics.char_width,
+                    y: font_metrics.padding_y + (line_num as i32) * font_metrics.line_height,
+                    width: 0,
                     height: font_metrics.line_height,
+                    attributes,
+                    underline_color,
                 });
+            }
+
+            // 문자 추가 (결합 문자는 기준 문자 뒤에 붙임)
+            if let Some(seg) = current.as_mut() {
+                seg.text.push(character);
+                seg.text.extend(zerowidth.iter());
+                seg.end_col += cell_cols;
+                seg.width += (cell_cols as i32) * font_metrics.char_width;
+            }
+        }
 
-                // 누적 X 위치 업데이트
-                accumulated_x_by_line.insert(current_line, current_line_x + text_width);
-
-                // 새 세그먼트 시작
-                segment_start_col += current_segment_text.chars().count();
-                current_segment_text.clear();
-                current_fg = fg_color;
-                current_bg = bg_color;
-            }
-
-            // 문자 추가
-            current_segment_text.push(ch);
-            line_text.push(ch);
-        }
-
-        // 마지막 세그먼트 처리 (절대 위치 계산 포함)
-        if !current_segment_text.is_empty() {
-            let text_len = current_segment_text.chars().count();
-            let current_line_x = accumulated_x_by_line.get(&current_line).unwrap_or(&0);
-            let text_width = (text_len as i32) * font_metrics.char_width;
-            let abs_x = font_metrics.padding_x + current_line_x;
-            let abs_y = font_metrics.padding_y + (current_line as i32) * font_metrics.line_height;
-
-            line_segments.push(ColoredTextSegment {
-                text: current_segment_text,
-                fg_color: current_fg,
-                bg_color: current_bg,
-                line: current_line,
-                start_col: segment_start_col,
-                end_col: segment_start_col + text_len,
-                x: abs_x,
-                y: abs_y,
-                width: text_width,
-                height: font_metrics.line_height,
+        // 마지막 세그먼트 처리
+        segments.extend(current);
+        // 보이는 줄에 고정된 이미지 줄
+        for anchor in &content.image_anchors {
+            let line_num = anchor.line + content.display_offset as i32;
+            if !(0..grid.screen_lines() as i32).contains(&line_num) {
+                continue;
+            }
+            let slice = images.placement(anchor.placement).and_then(|placement| {
+                ImageSlice::new(
+                    &placement,
+                    anchor.row,
+                    font_metrics.padding_x + (anchor.column as i32) * font_metrics.char_width,
+                    font_metrics.padding_y + line_num * font_metrics.line_height,
+                    font_metrics.char_width,
+                    font_metrics.line_height,
+                )
             });
+            image_slices.extend(slice);
         }
-        segments.extend(line_segments);
+        image_slices.extend(placeholders.slices(
+            &images,
+            font_metrics.padding_x,
+            font_metrics.padding_y,
+            font_metrics.char_width,
+            font_metrics.line_height,
+        ));
+        // z-index가 큰 이미지가 위에 그려지도록
+        image_slices.sort_by_key(|slice| slice.z_index);
 
         log::debug!(
             "Color extraction completed for session {}. Total segments: {}",
@@ -490,37 +1372,20 @@ impl TerminalSession {
             segments.len()
         );
 
-        // 생성된 세그먼트들을 자세히 디버그 출력
-        for (i, seg) in segments.iter().enumerate() {
-            let text_preview = if seg.text.chars().count() > 30 {
-                let truncated: String = seg.text.chars().take(30).collect();
-                format!("{}...", truncated)
-            } else {
-                seg.text.clone()
-            };
-            let text_escaped = text_preview
-                .replace('\n', "\\n")
-                .replace('\r', "\\r")
-                .replace('\t', "\\t");
-           /* log::debug!(
-                "🎨 Generated Segment[{}]: text='{}' len={} line={} x={} y={} w={} h={}",
-                i,
-                text_escaped,
-                seg.text.len(),
-                seg.line,
-                seg.x,
-                seg.y,
-                seg.width,
-                seg.height
-            );*/
-        }
-
         ColoredTerminalContent {
             segments,
             cursor_line: content.cursor_line,
             cursor_col: content.cursor_col,
+            cursor_shape: content.cursor_shape,
+            cursor_blinking: content.cursor_blinking,
+            cursor_wide: content.cursor_wide,
+            cursor_color: theme.resolve_index(NamedColor::Cursor as usize, &content.colors),
+            vi_mode: content.vi_mode,
+            background: theme.resolve_index(NamedColor::Background as usize, &content.colors),
+            foreground: theme.resolve_index(NamedColor::Foreground as usize, &content.colors),
             total_lines: grid.screen_lines(),
             total_cols: grid.columns(),
+            images: image_slices,
         }
     }
 
@@ -541,33 +1406,52 @@ impl TerminalSession {
         self.size.layout_width = cols as f32 * self.size.cell_width as f32;
         self.size.layout_height = rows as f32 * self.size.cell_height as f32;
 
+        // Term 리사이즈 (줄이 다시 나뉘면 이미지 앵커도 함께 옮김)
+        self.images.resize(
+            &mut self.term.lock(),
+            TermSize::new(cols as usize, rows as usize),
+        );
+
         // PTY에 리사이즈 알림
         let window_size: WindowSize = self.size.into();
         self.notifier.0.send(Msg::Resize(window_size))?;
 
-        // Term에도 리사이즈 알림
-        let mut term = self.term.lock();
-        term.resize(TermSize::new(cols as usize, rows as usize));
-
         Ok(())
     }
 
-    pub async fn get_content(&self) -> String {
-        self.content.lock().await.clone()
-    }
-
-    pub async fn is_alive(&self) -> bool {
-        let running = self.is_running.lock().await;
-        *running
-    }
-
-    pub async fn stop(&self) {
+    /// Tell the PTY reader to shut down; dropping the PTY when it exits hangs up the shell.
+    /// The returned thread is joined once the manager lock is released.
+    pub async fn stop(&mut self) -> Option<StoppedPty> {
         log::info!("Stopping terminal session {}", self.id);
         let mut running = self.is_running.lock().await;
         *running = false;
+        drop(running);
 
         // PTY에 종료 신호 전송
         let _ = self.notifier.0.send(Msg::Shutdown);
+        self.pty_thread.take().map(|thread| StoppedPty {
+            id: self.id,
+            thread,
+        })
+    }
+}
+
+/// PTY thread of a stopped or restarted session.
+/// It may still be reading the last output, which needs the PTY event thread to get the
+/// manager lock, so join it only after releasing the lock.
+#[must_use]
+pub struct StoppedPty {
+    id: SessionId,
+    thread: PtyThread,
+}
+
+impl StoppedPty {
+    pub async fn join(self) {
+        let id = self.id;
+        let joined = tokio::task::spawn_blocking(move || self.thread.join()).await;
+        if !matches!(joined, Ok(Ok(_))) {
+            log::warn!("PTY thread of session {} did not shut down cleanly", id);
+        }
     }
 }
 
@@ -575,182 +1459,62 @@ pub struct TerminalManager {
     config: Config,
     sessions: HashMap<SessionId, TerminalSession>,
     active_session: Option<SessionId>,
-    ui_callback: Option<Arc<UIUpdateCallback>>,
     pty_event_sender: mpsc::Sender<(SessionId, Event)>,
     pty_event_receiver: Option<mpsc::Receiver<(SessionId, Event)>>,
+    /// 전체 탭에 적용되는 폰트 크기 (ZoomScope::Global)
+    font_size: i32,
+    /// 키보드 입력을 같이 받는 세션들 (None이면 활성 세션만)
+    broadcast: Option<Broadcast>,
 }
 
 impl TerminalManager {
     pub fn new(config: Config) -> Result<Self> {
         let (pty_event_sender, pty_event_receiver) = mpsc::channel();
+        let font_size = Self::configured_font_size(&config);
         Ok(Self {
             config,
             sessions: HashMap::new(),
             active_session: None,
-            ui_callback: None,
             pty_event_sender,
             pty_event_receiver: Some(pty_event_receiver),
+            font_size,
+            broadcast: None,
         })
     }
 
-    pub fn set_ui_update_callback(&mut self, callback: UIUpdateCallback) {
-        self.ui_callback = Some(Arc::new(callback));
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn take_pty_event_receiver(&mut self) -> Option<mpsc::Receiver<(SessionId, Event)>> {
         self.pty_event_receiver.take()
     }
 
-    pub async fn process_pty_event(&mut self, session_id: SessionId, event: Event) {
-        match event {
-            Event::PtyWrite(data) => {
-                let text = String::from_utf8_lossy(data.as_bytes());
-                log::debug!("PTY output for session {}: {:?}", session_id, text);
-
-                // 해당 세션의 콘텐츠 업데이트
-                if let Some(session) = self.sessions.get(&session_id) {
-                    let mut content_guard = session.content.lock().await;
-                    content_guard.push_str(&text);
-
-                    // 스크롤백 관리
-                    if content_guard.len() > 50000 {
-                        let split_pos = content_guard.len() - 40000;
-                        if let Some(newline_pos) = content_guard[split_pos..].find('\n') {
-                            content_guard.drain(0..split_pos + newline_pos + 1);
-                        }
-                    }
-
-                    // UI 업데이트 콜백 호출
-                    if let Some(callback) = &self.ui_callback {
-                        callback(session_id, content_guard.clone());
-                    }
-                }
-            }
-            Event::Title(title) => {
-                log::debug!(
-                    "Terminal title changed for session {}: {}",
-                    session_id,
-                    title
-                );
-            }
-            Event::Exit => {
-                log::info!("Terminal session {} exited", session_id);
-                if let Some(session) = self.sessions.get(&session_id) {
-                    let mut running_guard = session.is_running.lock().await;
-                    *running_guard = false;
-                }
-            }
-            _ => {
-                // 다른 이벤트들은 무시
-            }
-        }
-    }
-
-    pub fn process_pty_event_sync(&self, session_id: SessionId, event: Event) {
-        match event {
-            Event::PtyWrite(data) => {
-                let text = String::from_utf8_lossy(data.as_bytes());
-                log::debug!("PTY output for session {} (sync): {:?}", session_id, text);
-
-                // 해당 세션의 콘텐츠 업데이트
-                if let Some(session) = self.sessions.get(&session_id) {
-                    if let Ok(mut content_guard) = session.content.try_lock() {
-                        content_guard.push_str(&text);
-
-                        // 스크롤백 관리
-                        if content_guard.len() > 50000 {
-                            let split_pos = content_guard.len() - 40000;
-                            if let Some(newline_pos) = content_guard[split_pos..].find('\n') {
-                                content_guard.drain(0..split_pos + newline_pos + 1);
-                            }
-                        }
-
-                        // UI 업데이트 콜백 호출
-                        if let Some(callback) = &self.ui_callback {
-                            callback(session_id, content_guard.clone());
-                        }
-                    }
-                }
-            }
-            Event::Title(title) => {
-                log::debug!(
-                    "Terminal title changed for session {}: {}",
-                    session_id,
-                    title
-                );
-            }
-            Event::Exit => {
-                log::info!("Terminal session {} exited", session_id);
-                if let Some(session) = self.sessions.get(&session_id) {
-                    if let Ok(mut running_guard) = session.is_running.try_lock() {
-                        *running_guard = false;
-                    }
-                }
-            }
-            _ => {
-                // 다른 이벤트들은 무시
-            }
-        }
-    }
-
-    pub fn update_session_content_and_get(
-        &self,
-        session_id: SessionId,
-        text: &str,
-    ) -> Option<String> {
-        if let Some(session) = self.sessions.get(&session_id) {
-            if let Ok(mut content_guard) = session.content.try_lock() {
-                content_guard.push_str(text);
-
-                // 스크롤백 관리
-                if content_guard.len() > 50000 {
-                    let split_pos = content_guard.len() - 40000;
-                    if let Some(newline_pos) = content_guard[split_pos..].find('\n') {
-                        content_guard.drain(0..split_pos + newline_pos + 1);
-                    }
-                }
-
-                return Some(content_guard.clone());
-            }
-        }
-        None
-    }
-
-    /// Extract terminal text from session (for UI updates)
-    pub fn extract_session_terminal_text(&mut self, session_id: SessionId) -> Option<String> {
-        if let Some(session) = self.sessions.get_mut(&session_id) {
-            Some(session.extract_terminal_text())
-        } else {
-            None
-        }
-    }
-
     pub fn extract_session_colored_content(
         &mut self,
         session_id: SessionId,
         font_metrics: &FontMetrics,
     ) -> Option<ColoredTerminalContent> {
-        if let Some(session) = self.sessions.get_mut(&session_id) {
-            Some(session.extract_colored_terminal_content(font_metrics))
-        } else {
-            None
-        }
+        self.sessions
+            .get_mut(&session_id)
+            .map(|session| session.extract_colored_terminal_content(font_metrics))
     }
 
     pub fn create_new_session(&mut self) -> Result<SessionId> {
+        self.create_session(SessionOptions::default())
+    }
+
+    /// 명령, 디렉토리, 제목 등을 지정해 세션 생성
+    pub fn create_session(&mut self, options: SessionOptions) -> Result<SessionId> {
         let session_id = SESSION_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
 
-        let mut session = TerminalSession::new(
+        let session = TerminalSession::new(
             session_id,
-            &self.config.terminal.shell,
+            &self.config,
+            options,
             self.pty_event_sender.clone(),
         )?;
 
-        // UI 콜백 설정
-        if let Some(callback) = &self.ui_callback {
-            session.set_ui_callback(callback.clone());
-        }
-
         self.sessions.insert(session_id, session);
 
         if self.active_session.is_none() {
@@ -761,6 +1525,49 @@ impl TerminalManager {
         Ok(session_id)
     }
 
+    /// 열린 탭들을 저장용 상태로 변환 (탭 순서 = 세션 ID 순)
+    pub fn save_state(&self) -> SavedSessions {
+        let mut ids: Vec<SessionId> = self.sessions.keys().copied().collect();
+        ids.sort_unstable();
+
+        let session_config = &self.config.session;
+        let tabs = ids
+            .iter()
+            .map(|id| {
+                let session = &self.sessions[id];
+                SavedTab {
+                    title: session.title.clone(),
+                    working_directory: session.working_directory(),
+                    profile: session.options.profile.clone(),
+                    scrollback: session_config
+                        .save_scrollback
+                        .then(|| session.history_text(session_config.scrollback_lines)),
+                }
+            })
+            .collect();
+        let active_tab = self
+            .active_session
+            .and_then(|active| ids.iter().position(|id| *id == active))
+            .unwrap_or(0);
+
+        SavedSessions { tabs, active_tab }
+    }
+
+    /// 저장된 탭마다 새 셸을 시작. 생성된 세션 ID와 제목을 탭 순서대로 반환
+    pub fn restore_state(&mut self, saved: &SavedSessions) -> Vec<(SessionId, String)> {
+        let mut restored = Vec::new();
+        for tab in &saved.tabs {
+            match self.create_session(tab.session_options()) {
+                Ok(id) => restored.push((id, tab.title.clone())),
+                Err(e) => log::error!("Failed to restore tab {:?}: {}", tab.title, e),
+            }
+        }
+        if let Some((id, _)) = restored.get(saved.active_tab) {
+            self.active_session = Some(*id);
+        }
+        restored
+    }
+
     pub fn get_session(&self, session_id: SessionId) -> Option<&TerminalSession> {
         self.sessions.get(&session_id)
     }
@@ -783,23 +1590,523 @@ impl TerminalManager {
         }
     }
 
-    pub async fn close_session(&mut self, session_id: SessionId) -> Result<()> {
-        if let Some(session) = self.sessions.remove(&session_id) {
-            session.stop().await;
+    /// Remove a session, returning its PTY thread to join after releasing the manager lock
+    pub async fn close_session(&mut self, session_id: SessionId) -> Result<Option<StoppedPty>> {
+        let Some(mut session) = self.sessions.remove(&session_id) else {
+            return Ok(None);
+        };
+        let stopped = session.stop().await;
 
-            // 세션이 현재 활성 세션인 경우 다른 세션으로 전환
-            if self.active_session == Some(session_id) {
-                self.active_session = self.sessions.keys().next().copied();
-            }
-            log::info!("Closed terminal session: {}", session_id);
+        // 세션이 현재 활성 세션인 경우 다른 세션으로 전환
+        if self.active_session == Some(session_id) {
+            self.active_session = self.sessions.keys().next().copied();
         }
-        Ok(())
+        log::info!("Closed terminal session: {}", session_id);
+        Ok(stopped)
     }
 
     pub fn get_all_sessions(&self) -> Vec<SessionId> {
         self.sessions.keys().copied().collect()
     }
 
+    /// 지정한 세션, 없으면 활성 세션
+    pub fn resolve_session(&self, session_id: Option<SessionId>) -> Result<SessionId> {
+        match session_id.or(self.active_session) {
+            Some(id) if self.sessions.contains_key(&id) => Ok(id),
+            Some(id) => Err(anyhow::anyhow!("Session {} not found", id)),
+            None => Err(anyhow::anyhow!("No active session")),
+        }
+    }
+
+    pub fn active_session_id(&self) -> Option<SessionId> {
+        self.active_session
+    }
+
+    /// 화면 텍스트, `scrollback`이면 스크롤백 전체
+    pub fn session_text(&self, session_id: SessionId, scrollback: bool) -> Option<String> {
+        let session = self.sessions.get(&session_id)?;
+        Some(if scrollback {
+            session.history_text(usize::MAX)
+        } else {
+            session.screen_text()
+        })
+    }
+
+    /// 세션 내보내기. `selection`이면 선택 영역만
+    pub fn export_session(
+        &self,
+        session_id: SessionId,
+        format: ExportFormat,
+        selection: bool,
+    ) -> Result<String> {
+        let session = self
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+        session.export(format, selection, &self.config.ui.font_family)
+    }
+
+    /// 단축키 내보내기: 선택 영역이 있으면 선택 영역, 없으면 스크롤백 전체를 설정한 형식으로 파일에 저장
+    pub fn export_to_file(&self, session_id: SessionId) -> Result<PathBuf> {
+        let session = self
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+        let format = self.config.export.format;
+        let text = session.export(format, session.has_selection(), &self.config.ui.font_family)?;
+        let path = self.export_path(&session.title, format.extension())?;
+        std::fs::write(&path, text)?;
+        log::info!("Exported session {} to {}", session_id, path.display());
+        Ok(path)
+    }
+
+    /// 보이는 화면(또는 그 일부)을 UI와 같은 격자와 색으로 그린 SVG/PNG
+    pub fn screenshot(
+        &mut self,
+        session_id: SessionId,
+        format: ScreenshotFormat,
+        region: Option<ScreenRegion>,
+    ) -> Result<Vec<u8>> {
+        let font_size = self.font_size_for(session_id);
+        let metrics = self.font_metrics(session_id);
+        let content = self
+            .extract_session_colored_content(session_id, &metrics)
+            .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+        let svg = screenshot::render_svg(&content, &metrics, font_size, region);
+        match format {
+            ScreenshotFormat::Svg => Ok(svg.into_bytes()),
+            ScreenshotFormat::Png => screenshot::render_png(&svg),
+        }
+    }
+
+    /// 스크린샷 단축키: 설정한 형식으로 내보내기 디렉토리에 저장
+    pub fn screenshot_to_file(&mut self, session_id: SessionId) -> Result<PathBuf> {
+        let format = self.config.export.screenshot_format;
+        let image = self.screenshot(session_id, format, None)?;
+        let title = self
+            .sessions
+            .get(&session_id)
+            .map(|session| session.title.clone())
+            .unwrap_or_default();
+        let path = self.export_path(&title, format.extension())?;
+        std::fs::write(&path, image)?;
+        log::info!(
+            "Saved screenshot of session {} to {}",
+            session_id,
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// 내보내기 디렉토리 안의 `<제목>_<시각>.<확장자>`
+    fn export_path(&self, title: &str, extension: &str) -> Result<PathBuf> {
+        let directory = match &self.config.export.directory {
+            Some(directory) => directory.clone(),
+            None => Platform::data_dir()?.join("exports"),
+        };
+        std::fs::create_dir_all(&directory)?;
+        let name = logging::sanitize_file_name(&format!(
+            "{}_{}.{}",
+            title,
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+            extension
+        ));
+        Ok(directory.join(name.trim_start_matches('.')))
+    }
+
+    pub fn set_session_focus(&self, session_id: SessionId, focused: bool) {
+        if let Some(session) = self.sessions.get(&session_id) {
+            session.set_focused(focused);
+        } else {
+            log::warn!("Session {} not found for focus change", session_id);
+        }
+    }
+
+    pub fn respond_color_request(
+        &self,
+        session_id: SessionId,
+        index: usize,
+        format: &(dyn Fn(Rgb) -> String + Send + Sync),
+    ) {
+        if let Some(session) = self.sessions.get(&session_id) {
+            session.respond_color_request(index, format);
+        } else {
+            log::warn!("Session {} not found for color request", session_id);
+        }
+    }
+
+    pub fn link_at(&self, session_id: SessionId, row: usize, col: usize) -> Option<LinkMatch> {
+        self.sessions.get(&session_id)?.link_at(row, col)
+    }
+
+    pub fn open_link(&self, session_id: SessionId, link: &LinkMatch) {
+        let cwd = self
+            .sessions
+            .get(&session_id)
+            .and_then(|session| session.working_directory());
+        if let Err(e) = links::open_link(link, cwd.as_deref(), &self.config.links) {
+            log::error!("Failed to open link {}: {}", link.target, e);
+        }
+    }
+
+    /// 눌린 키가 설정된 힌트 단축키 중 하나와 맞으면 그 힌트를 반환
+    pub fn hint_for_key(
+        &self,
+        text: &str,
+        control: bool,
+        alt: bool,
+        meta: bool,
+        shift: bool,
+    ) -> Option<&HintConfig> {
+        self.config.hints.enabled.iter().find(|hint| {
+            KeyBinding::parse(&hint.binding)
+                .is_some_and(|binding| binding.matches(text, control, alt, meta, shift))
+        })
+    }
+
+    pub fn start_hints(&self, session_id: SessionId, hint: &HintConfig) -> Option<HintState> {
+        self.sessions
+            .get(&session_id)?
+            .start_hints(hint, &self.config.hints.alphabet)
+    }
+
+    pub fn record_child_exit(&mut self, session_id: SessionId, code: i32) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            session.record_child_exit(code);
+        }
+    }
+
+    /// 셸 종료 처리: 설정된 정책에 따라 탭을 닫을지 열어 둘지 결정
+    pub fn handle_session_exit(&mut self, session_id: SessionId) -> SessionExitAction {
+        let behavior = self.config.terminal.exit_behavior;
+        match self.sessions.get_mut(&session_id) {
+            Some(session) => session.handle_exit(behavior),
+            None => SessionExitAction::Close,
+        }
+    }
+
+    /// 닫기 전에 확인이 필요한 포그라운드 프로세스 이름
+    pub fn running_process(&self, session_id: SessionId) -> Option<String> {
+        let name = self.sessions.get(&session_id)?.foreground_process()?;
+        let ignored = &self.config.terminal.close_without_confirm;
+        (!ignored.contains(&name)).then_some(name)
+    }
+
+    /// 확인이 필요한 프로세스가 실행 중인 모든 세션 (ID 순)
+    pub fn running_processes(&self) -> Vec<(SessionId, String)> {
+        let mut ids: Vec<SessionId> = self.sessions.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| self.running_process(id).map(|name| (id, name)))
+            .collect()
+    }
+
+    /// 세션에 적용되는 폰트 크기
+    pub fn font_size_for(&self, session_id: SessionId) -> i32 {
+        self.sessions
+            .get(&session_id)
+            .and_then(|session| session.font_size)
+            .unwrap_or(self.font_size)
+    }
+
+    /// 설정(`ui.font_size`)의 폰트 크기. 범위를 벗어나면 기본 크기를 쓴다
+    fn configured_font_size(config: &Config) -> i32 {
+        FontManager::validate_font_size(config.ui.font_size)
+            .map(|size| size.round() as i32)
+            .unwrap_or(FontMetrics::DEFAULT_FONT_SIZE)
+    }
+
+    /// 세션을 그릴 때 쓰는 폰트 메트릭
+    pub fn font_metrics(&self, session_id: SessionId) -> FontMetrics {
+        FontMetrics::for_d2coding(self.font_size_for(session_id))
+    }
+
+    /// 폰트 확대/축소. 크기가 바뀐 세션 ID를 반환 (다시 리사이즈해야 함)
+    pub fn zoom(&mut self, session_id: SessionId, action: ZoomAction) -> Vec<SessionId> {
+        let current = self.font_size_for(session_id);
+        let size = match action {
+            ZoomAction::In => FontManager::step_font_size(current, true),
+            ZoomAction::Out => FontManager::step_font_size(current, false),
+            ZoomAction::Reset => Self::configured_font_size(&self.config),
+        };
+        log::info!("Font size {} -> {} ({:?})", current, size, action);
+
+        match self.config.ui.zoom_scope {
+            ZoomScope::Global => {
+                self.font_size = size;
+                for session in self.sessions.values_mut() {
+                    session.font_size = None;
+                }
+                self.sessions.keys().copied().collect()
+            }
+            ZoomScope::Tab => match self.sessions.get_mut(&session_id) {
+                Some(session) => {
+                    session.font_size = Some(size);
+                    vec![session_id]
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+
+    pub fn request_redraw(&self, session_id: SessionId) {
+        if let Some(session) = self.sessions.get(&session_id) {
+            session.request_redraw();
+        }
+    }
+
+    /// 종료 안내를 표시 중인 세션인지 확인
+    pub fn is_session_held(&self, session_id: SessionId) -> bool {
+        self.sessions
+            .get(&session_id)
+            .is_some_and(|session| session.held)
+    }
+
+    pub fn restart_session(&mut self, session_id: SessionId) -> Result<Option<StoppedPty>> {
+        let config = self.config.clone();
+        match self.sessions.get_mut(&session_id) {
+            Some(session) => session.restart(&config),
+            None => Err(anyhow::anyhow!("Session {} not found", session_id)),
+        }
+    }
+
+    /// 눌린 키가 복사 모드 단축키인지 확인
+    pub fn is_vi_mode_key(
+        &self,
+        text: &str,
+        control: bool,
+        alt: bool,
+        meta: bool,
+        shift: bool,
+    ) -> bool {
+        KeyBinding::parse(&self.config.keybindings.vi_mode)
+            .is_some_and(|binding| binding.matches(text, control, alt, meta, shift))
+    }
+
+    pub fn toggle_vi_mode(&mut self, session_id: SessionId) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            session.toggle_vi_mode();
+        }
+    }
+
+    pub fn is_vi_mode(&self, session_id: SessionId) -> bool {
+        self.sessions
+            .get(&session_id)
+            .is_some_and(|session| session.is_vi_mode())
+    }
+
+    /// 복사 모드 키 처리, `y`로 선택한 텍스트는 클립보드에 복사
+    pub fn vi_key(&mut self, session_id: SessionId, text: &str, ctrl: bool) {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            return;
+        };
+        if let Some(yanked) = session.vi_key(text, ctrl) {
+            match Platform::copy_to_clipboard(&yanked) {
+                Ok(_) => log::info!("Yanked {} characters to clipboard", yanked.chars().count()),
+                Err(e) => log::error!("Failed to copy to clipboard: {}", e),
+            }
+        }
+    }
+
+    pub fn vi_status(&self, session_id: SessionId) -> Option<String> {
+        self.sessions.get(&session_id)?.vi_status()
+    }
+
+    /// 선택된 힌트에 설정된 동작을 실행
+    pub fn run_hint_action(&self, session_id: SessionId, action: HintAction, hint: &HintMatch) {
+        log::info!("Hint {:?}: {}", action, hint.text);
+        match action {
+            HintAction::Copy => {
+                if let Err(e) = Platform::copy_to_clipboard(&hint.text) {
+                    log::error!("Failed to copy hint to clipboard: {}", e);
+                }
+            }
+            HintAction::Paste => {
+                if let Err(e) = self.write_to_session(session_id, &hint.text) {
+                    log::error!("Failed to paste hint: {}", e);
+                }
+            }
+            HintAction::Open => {
+                let kind = if links::is_url(&hint.text) {
+                    LinkKind::Url
+                } else {
+                    LinkKind::FilePath
+                };
+                let link = LinkMatch {
+                    kind,
+                    line: Line(hint.row as i32),
+                    start_col: hint.start_col,
+                    end_col: hint.end_col,
+                    text: hint.text.clone(),
+                    target: hint.text.clone(),
+                };
+                self.open_link(session_id, &link);
+            }
+        }
+    }
+
+    /// OSC 52 policy of a session (the global one if the session is gone)
+    pub fn clipboard_policy(&self, session_id: SessionId) -> &ClipboardConfig {
+        self.sessions
+            .get(&session_id)
+            .map_or(&self.config.clipboard, |session| session.clipboard())
+    }
+
+    /// OSC 52 쓰기 요청을 클립보드에 반영 (크기 제한을 넘으면 무시). 복사했으면 true
+    pub fn store_clipboard(&self, session_id: SessionId, text: &str) -> bool {
+        let policy = self.clipboard_policy(session_id);
+        if !policy.fits(text) {
+            log::warn!(
+                "Dropped OSC 52 copy from session {}: {} bytes exceeds limit of {}",
+                session_id,
+                text.len(),
+                policy.osc52_max_bytes
+            );
+            return false;
+        }
+        match Platform::copy_to_clipboard(text) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to copy OSC 52 text to clipboard: {}", e);
+                false
+            }
+        }
+    }
+
+    /// OSC 52 읽기 요청에 클립보드 내용으로 응답. 응답을 보냈으면 true
+    pub fn load_clipboard(
+        &self,
+        session_id: SessionId,
+        format: &(dyn Fn(&str) -> String + Send + Sync),
+    ) -> bool {
+        let text = match Platform::paste_from_clipboard() {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("Failed to read clipboard for OSC 52: {}", e);
+                return false;
+            }
+        };
+        let Some(reply) = self.clipboard_reply(session_id, &text, format) else {
+            return false;
+        };
+        match self.write_to_session(session_id, &reply) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to send clipboard to session {}: {}", session_id, e);
+                false
+            }
+        }
+    }
+
+    /// OSC 52 응답으로 보낼 클립보드 내용 (크기 제한을 넘으면 None)
+    pub fn clipboard_reply(
+        &self,
+        session_id: SessionId,
+        text: &str,
+        format: &(dyn Fn(&str) -> String + Send + Sync),
+    ) -> Option<String> {
+        let policy = self.clipboard_policy(session_id);
+        if !policy.fits(text) {
+            log::warn!(
+                "Refused OSC 52 read from session {}: clipboard has {} bytes, limit is {}",
+                session_id,
+                text.len(),
+                policy.osc52_max_bytes
+            );
+            return None;
+        }
+        Some(format(text))
+    }
+
+    /// 세션의 새 출력에 트리거 적용. 알림과 배지는 여기서 처리하고 UI가 할 일(벨, 배지)을 돌려줌
+    pub fn process_output(&mut self, session_id: SessionId) -> Vec<TriggerHit> {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            return Vec::new();
+        };
+        let mut hits = session.process_output();
+        hits.retain(|hit| match hit {
+            TriggerHit::Notify(message) => {
+                let title = format!("sterm: {}", session.title);
+                if let Err(e) = Platform::show_notification(&title, message) {
+                    log::warn!("Failed to show trigger notification: {}", e);
+                }
+                false
+            }
+            TriggerHit::Badge(badge) => {
+                session.badge = Some(badge.clone());
+                true
+            }
+            _ => true,
+        });
+        hits
+    }
+
+    /// 세션 출력 기록 시작/중지. 시작하면 기록 파일 경로를 돌려줌
+    pub fn toggle_logging(&mut self, session_id: SessionId) -> Result<Option<PathBuf>> {
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+        if session.stop_logging().is_some() {
+            return Ok(None);
+        }
+        Ok(Some(session.start_logging(&self.config.logging)?))
+    }
+
+    /// 출력을 기록 중인 세션
+    pub fn logging_sessions(&self) -> Vec<SessionId> {
+        let mut sessions: Vec<SessionId> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.is_logging())
+            .map(|(id, _)| *id)
+            .collect();
+        sessions.sort_unstable();
+        sessions
+    }
+
+    /// 모든 세션에 브로드캐스트를 켜거나 끔
+    pub fn toggle_broadcast_all(&mut self) {
+        self.broadcast = match self.broadcast {
+            Some(Broadcast::All) => None,
+            _ => Some(Broadcast::All),
+        };
+        log::info!("Broadcast input: {:?}", self.broadcast);
+    }
+
+    /// 세션을 브로드캐스트 그룹에 넣거나 뺌
+    pub fn toggle_broadcast_member(&mut self, session_id: SessionId) {
+        let sessions = self.get_all_sessions();
+        self.broadcast = Broadcast::toggle_member(self.broadcast.take(), session_id, &sessions);
+        log::info!("Broadcast input: {:?}", self.broadcast);
+    }
+
+    /// 브로드캐스트 입력을 받는 세션 (꺼져 있으면 빈 목록)
+    pub fn broadcast_members(&self) -> Vec<SessionId> {
+        match &self.broadcast {
+            Some(broadcast) => broadcast.members(&self.get_all_sessions()),
+            None => Vec::new(),
+        }
+    }
+
+    /// 키보드 입력 쓰기. 브로드캐스트 중이면 그룹의 모든 세션에 보냄
+    pub fn write_input(&self, session_id: SessionId, data: &str) -> Result<()> {
+        let Some(broadcast) = &self.broadcast else {
+            return self.write_to_session(session_id, data);
+        };
+        for target in broadcast.targets(session_id, &self.get_all_sessions()) {
+            if target == session_id {
+                self.write_to_session(target, data)?;
+            } else if !self.is_session_held(target) {
+                // 다른 세션의 실패 때문에 입력한 세션까지 막지 않음
+                if let Err(e) = self.write_to_session(target, data) {
+                    log::warn!("Failed to broadcast input to session {}: {}", target, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn write_to_session(&self, session_id: SessionId, data: &str) -> Result<()> {
         if let Some(session) = self.sessions.get(&session_id) {
             session.write(data)?;
@@ -810,33 +2117,303 @@ impl TerminalManager {
     }
 
     pub fn resize_session(&mut self, session_id: SessionId, cols: u16, rows: u16) -> Result<()> {
+        let font_metrics = self.font_metrics(session_id);
         if let Some(session) = self.sessions.get_mut(&session_id) {
+            // PTY에 알리는 픽셀 크기도 현재 폰트에 맞춤
+            session.size.cell_width = font_metrics.char_width as u16;
+            session.size.cell_height = font_metrics.line_height as u16;
             session.resize(cols, rows)?;
         } else {
             log::warn!("Session {} not found for resize", session_id);
         }
         Ok(())
     }
+}
 
-    pub async fn get_terminal_content(&self, session_id: SessionId) -> Option<String> {
-        if let Some(session) = self.sessions.get(&session_id) {
-            Some(session.get_content().await)
-        } else {
-            None
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{TriggerAction, TriggerConfig};
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::vte::ansi::Color as AnsiColor;
+
+    /// Session running a shell that prints nothing, so only `feed` writes to the screen
+    fn quiet_session() -> TerminalSession {
+        let options = SessionOptions {
+            command: vec!["sh".into(), "-c".into(), "sleep 5".into()],
+            ..Default::default()
+        };
+        let (sender, _events) = mpsc::channel();
+        TerminalSession::new(0, &Config::default(), options, sender).unwrap()
+    }
+
+    fn feed(session: &TerminalSession, bytes: &[u8]) {
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut *session.term.lock(), bytes);
+    }
+
+    /// Segments of the first screen line
+    fn first_line(session: &mut TerminalSession) -> Vec<ColoredTextSegment> {
+        let content = session.extract_colored_terminal_content(&FontMetrics::default());
+        content
+            .segments
+            .into_iter()
+            .filter(|segment| segment.line == 0 && !segment.text.trim().is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_cell_attributes() {
+        let attributes = TextAttributes::new();
+        let underline = |style| attributes.clone().with_underline(style);
+        let cases = [
+            ("0", attributes.clone()),
+            ("1", attributes.clone().with_weight(FontWeight::Bold)),
+            ("3", attributes.clone().with_style(FontStyle::Italic)),
+            ("4", underline(UnderlineStyle::Single)),
+            ("4:1", underline(UnderlineStyle::Single)),
+            ("4:2", underline(UnderlineStyle::Double)),
+            ("4:3", underline(UnderlineStyle::Curly)),
+            ("4:4", underline(UnderlineStyle::Dotted)),
+            ("4:5", underline(UnderlineStyle::Dashed)),
+            ("4:3;24", attributes.clone()),
+            ("4:0", attributes.clone()),
+            ("9", attributes.clone().with_strikethrough(true)),
+            ("8", attributes.clone().with_hidden(true)),
+            // 흐림은 글자색으로만 표시
+            ("2", attributes.clone()),
+        ];
+        for (sgr, expected) in cases {
+            let mut term = Term::new(TermConfig::default(), &TermSize::new(10, 2), VoidListener);
+            let mut parser: Processor = Processor::new();
+            parser.advance(&mut term, format!("\x1b[{}mx", sgr).as_bytes());
+            let flags = term.grid()[Line(0)][Column(0)].flags;
+            assert_eq!(
+                TerminalSession::cell_attributes(flags, true, true),
+                expected,
+                "SGR {}",
+                sgr
+            );
         }
+
+        // 굵게/기울임 설정을 끄면 무시
+        let flags = Flags::BOLD | Flags::ITALIC | Flags::UNDERLINE;
+        assert_eq!(
+            TerminalSession::cell_attributes(flags, false, false),
+            underline(UnderlineStyle::Single)
+        );
     }
 
-    pub async fn cleanup_dead_sessions(&mut self) {
-        let mut dead_sessions = Vec::new();
+    #[test]
+    fn test_underline_color_and_dim() {
+        let mut session = quiet_session();
+        let theme = session.theme.clone();
+        feed(
+            &session,
+            b"\x1b[4;58;2;255;0;0ma\x1b[58;5;2mb\x1b[59mc\x1b[0;2md\x1b[0m",
+        );
+        let segments = first_line(&mut session);
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect();
+        assert_eq!(texts, ["a", "b", "c", "d"]);
+
+        // SGR 58 (RGB, 팔레트) 밑줄 색, SGR 59로 지우면 글자색
+        assert_eq!(segments[0].underline_color, Color::rgb(255, 0, 0));
+        assert_eq!(segments[1].underline_color, theme.green);
+        assert_eq!(segments[2].underline_color, segments[2].fg_color);
+        assert_eq!(segments[2].fg_color, theme.foreground);
+
+        let dim = &segments[3];
+        assert_eq!(dim.attributes, TextAttributes::new());
+        assert_eq!(
+            (dim.fg_color.r, dim.fg_color.g, dim.fg_color.b),
+            (
+                (theme.foreground.r as f32 * 0.7) as u8,
+                (theme.foreground.g as f32 * 0.7) as u8,
+                (theme.foreground.b as f32 * 0.7) as u8,
+            )
+        );
+    }
 
-        for (id, session) in &self.sessions {
-            if !session.is_alive().await {
-                dead_sessions.push(*id);
-            }
+    #[test]
+    fn test_wide_and_zero_width_columns() {
+        let mut session = quiet_session();
+        let metrics = FontMetrics::default();
+        // 한글/한자는 두 칸, 결합 문자와 ZWJ는 앞 글자 칸에 붙음
+        // (끝의 배경색은 빈 칸과 세그먼트를 나누기 위함)
+        feed(
+            &session,
+            "가漢ab e\u{301}x 👩\u{200d}💻z\x1b[41m ".as_bytes(),
+        );
+        let segments = first_line(&mut session);
+        let layout: Vec<(&str, usize, usize)> = segments
+            .iter()
+            .map(|segment| (segment.text.as_str(), segment.start_col, segment.end_col))
+            .collect();
+        assert_eq!(
+            layout,
+            [
+                ("가", 0, 2),
+                ("漢", 2, 4),
+                ("ab ", 4, 7),
+                ("e\u{301}", 7, 8),
+                ("x ", 8, 10),
+                ("👩\u{200d}", 10, 12),
+                ("💻", 12, 14),
+                ("z", 14, 15),
+            ]
+        );
+        // 픽셀 위치와 폭도 칸 수를 따름
+        for segment in &segments {
+            assert_eq!(
+                segment.x,
+                metrics.padding_x + segment.start_col as i32 * metrics.char_width
+            );
+            assert_eq!(
+                segment.width,
+                (segment.end_col - segment.start_col) as i32 * metrics.char_width
+            );
+        }
+    }
+
+    #[test]
+    fn test_trigger_highlights_the_matched_row() {
+        let mut config = Config::default();
+        config.triggers.push(TriggerConfig {
+            regex: "ERROR".to_string(),
+            action: TriggerAction::Highlight {
+                foreground: Some("#ff0000".to_string()),
+                background: None,
+            },
+            instant: false,
+        });
+        // 120열에서 두 줄로 나뉘는 줄 뒤에 찾을 줄을 쓰고 커서를 맨 위로 옮김.
+        // 출력이 먼저 올 수 있으므로 크기는 바꾸지 않음
+        let script = format!(
+            r"printf '{}\nERROR here\nnext\n\033[H'; sleep 5",
+            "x".repeat(160)
+        );
+        let options = SessionOptions {
+            command: vec!["sh".into(), "-c".into(), script],
+            ..Default::default()
+        };
+        let (sender, _events) = mpsc::channel();
+        let mut session = TerminalSession::new(0, &config, options, sender).unwrap();
+
+        let red = AnsiColor::Spec(Rgb { r: 255, g: 0, b: 0 });
+        let highlighted = |session: &TerminalSession| {
+            let term = session.term.lock();
+            term.grid()[Line(2)][Column(0)].fg == red
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !highlighted(&session) && std::time::Instant::now() < deadline {
+            session.process_output();
+            std::thread::sleep(std::time::Duration::from_millis(20));
         }
+        assert!(highlighted(&session));
+        let term = session.term.lock();
+        assert_ne!(term.grid()[Line(2)][Column(5)].fg, red);
+        assert_ne!(term.grid()[Line(0)][Column(0)].fg, red);
+    }
 
-        for id in dead_sessions {
-            let _ = self.close_session(id).await;
+    #[test]
+    fn test_images_are_drawn_at_their_cells() {
+        // Sixel은 첫 줄 3열에, kitty 이미지는 그 아래 줄 맨 앞에
+        let script = r"printf 'ab\033Pq#1~~\033\\\r\033_Ga=T,q=2,f=32,s=2,v=2,c=2,r=1;AAAAAAAAAAAAAAAAAAAAAA==\033\\'; sleep 5";
+        let options = SessionOptions {
+            command: vec!["sh".into(), "-c".into(), script.into()],
+            ..Default::default()
+        };
+        let (sender, _events) = mpsc::channel();
+        let mut session = TerminalSession::new(0, &Config::default(), options, sender).unwrap();
+
+        let metrics = FontMetrics::default();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut images = Vec::new();
+        while images.len() < 2 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            images = session.extract_colored_terminal_content(&metrics).images;
         }
+        let mut layout: Vec<(u32, u32, i32, i32)> = images
+            .iter()
+            .map(|slice| (slice.image.width, slice.image.height, slice.x, slice.y))
+            .collect();
+        layout.sort();
+        assert_eq!(
+            layout,
+            [
+                (
+                    2,
+                    2,
+                    metrics.padding_x,
+                    metrics.padding_y + metrics.line_height
+                ),
+                (
+                    2,
+                    6,
+                    metrics.padding_x + 2 * metrics.char_width,
+                    metrics.padding_y
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_exit_is_reported() {
+        let options = SessionOptions {
+            command: vec!["sh".into(), "-c".into(), "exit 3".into()],
+            ..Default::default()
+        };
+        let (sender, events) = mpsc::channel();
+        let _session = TerminalSession::new(0, &Config::default(), options, sender).unwrap();
+
+        let timeout = std::time::Duration::from_secs(5);
+        let mut exit_code = None;
+        while let Ok((_, event)) = events.recv_timeout(timeout) {
+            match event {
+                Event::ChildExit(code) => exit_code = Some(code),
+                Event::Exit => break,
+                _ => {}
+            }
+        }
+        assert_eq!(exit_code, Some(3));
+    }
+
+    #[test]
+    fn test_font_size_follows_config() {
+        let mut config = Config::default();
+        config.ui.font_size = 16.0;
+        let mut tm = TerminalManager::new(config).unwrap();
+        assert_eq!(tm.font_size_for(0), 16);
+
+        tm.zoom(0, ZoomAction::In);
+        assert!(tm.font_size_for(0) > 16);
+        tm.zoom(0, ZoomAction::Reset);
+        assert_eq!(tm.font_size_for(0), 16);
+    }
+
+    #[tokio::test]
+    async fn test_close_session_joins_after_unlock() {
+        let manager = Arc::new(Mutex::new(TerminalManager::new(Config::default()).unwrap()));
+        let mut tm = manager.lock().await;
+        let id = tm
+            .create_session(SessionOptions {
+                command: vec!["sh".into(), "-c".into(), "sleep 5".into()],
+                ..Default::default()
+            })
+            .unwrap();
+        let stopped = tm.close_session(id).await.unwrap().unwrap();
+        assert!(tm.get_session(id).is_none());
+        drop(tm);
+
+        // PTY 스레드가 끝나는 동안 다른 쪽에서 잠금을 얻을 수 있음
+        let join = tokio::spawn(stopped.join());
+        assert!(manager.try_lock().is_ok());
+        tokio::time::timeout(std::time::Duration::from_secs(5), join)
+            .await
+            .unwrap()
+            .unwrap();
     }
 }
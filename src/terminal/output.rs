@@ -0,0 +1,354 @@
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+    vte::{Parser, Perform},
+};
+use polling::{Event as PollingEvent, PollMode, Poller};
+use signal_hook::{consts::SIGCHLD, low_level::pipe as signal_pipe, SigId};
+use std::fs::File;
+use std::io::{self, ErrorKind, Read};
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+
+use super::graphics::GraphicsFilter;
+
+/// Most bytes kept for a consumer that has fallen behind
+const MAX_BUFFERED: usize = 4 * 1024 * 1024;
+
+/// Copy of everything the program writes, taken while the event loop reads the PTY.
+/// Bytes are only kept while some consumer (triggers, logging) has enabled the tap.
+#[derive(Clone, Default)]
+pub struct OutputTap {
+    inner: Arc<Mutex<TapBuffer>>,
+}
+
+#[derive(Default)]
+struct TapBuffer {
+    enabled: bool,
+    bytes: Vec<u8>,
+}
+
+impl OutputTap {
+    pub fn set_enabled(&self, enabled: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.enabled = enabled;
+            if !enabled {
+                inner.bytes.clear();
+            }
+        }
+    }
+
+    /// Take the bytes read since the last call
+    pub fn take(&self) -> Vec<u8> {
+        self.inner
+            .lock()
+            .map(|mut inner| std::mem::take(&mut inner.bytes))
+            .unwrap_or_default()
+    }
+
+    fn push(&self, bytes: &[u8]) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if !inner.enabled {
+            return;
+        }
+        if inner.bytes.len() + bytes.len() > MAX_BUFFERED {
+            log::warn!(
+                "Output tap overflow, dropping {} buffered bytes",
+                inner.bytes.len()
+            );
+            inner.bytes.clear();
+        }
+        inner.bytes.extend_from_slice(bytes);
+    }
+}
+
+/// Writes bytes to the PTY
+type PtySink = Box<dyn Fn(Vec<u8>) + Send>;
+
+/// Replies going back to the program. Once a PTY is connected, they are written to it
+/// straight from the PTY thread, in the order the terminal parser made them.
+#[derive(Clone, Default)]
+pub struct ReplyWriter {
+    pty: Arc<Mutex<Option<PtySink>>>,
+}
+
+impl ReplyWriter {
+    /// Send replies to `sink` from now on (a new one replaces the old when the shell restarts)
+    pub fn connect(&self, sink: impl Fn(Vec<u8>) + Send + 'static) {
+        if let Ok(mut pty) = self.pty.lock() {
+            *pty = Some(Box::new(sink));
+        }
+    }
+
+    /// Write `reply` to the PTY right away; gives it back when no PTY is connected
+    pub fn write(&self, reply: String) -> Option<String> {
+        let pty = self.pty.lock().ok();
+        match pty.as_ref().and_then(|pty| pty.as_ref()) {
+            Some(sink) => {
+                sink(reply.into_bytes());
+                None
+            }
+            None => Some(reply),
+        }
+    }
+}
+
+/// Reader over the PTY master that copies every read into an [`OutputTap`]
+/// and hands the terminal what is left after the [`GraphicsFilter`] took the images out
+pub struct TapReader {
+    file: File,
+    tap: OutputTap,
+    graphics: GraphicsFilter,
+    /// 필터를 거쳤지만 아직 읽어 가지 않은 바이트
+    filtered: Vec<u8>,
+    filtered_pos: usize,
+}
+
+impl Read for TapReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // 이미지 시퀀스 중간이라 넘길 것이 없으면 더 읽음 (없으면 WouldBlock)
+        while self.filtered_pos == self.filtered.len() {
+            self.filtered.clear();
+            self.filtered_pos = 0;
+            let read = self.file.read(buf)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            self.tap.push(&buf[..read]);
+            self.graphics.filter(&buf[..read], &mut self.filtered);
+        }
+        let pending = &self.filtered[self.filtered_pos..];
+        let len = pending.len().min(buf.len());
+        buf[..len].copy_from_slice(&pending[..len]);
+        self.filtered_pos += len;
+        Ok(len)
+    }
+}
+
+/// Poll key [`TappedPty`] registers the PTY file under
+pub const PTY_READ_WRITE_KEY: usize = 0;
+/// Poll key of the SIGCHLD pipe; readable when the shell may have exited
+pub const CHILD_EVENT_KEY: usize = 1;
+
+/// SIGCHLD pipe of our own, since the one inside `tty::Pty` can't be registered with
+/// keys we know
+struct ChildSignals {
+    receiver: UnixStream,
+    id: SigId,
+}
+
+impl ChildSignals {
+    fn new() -> io::Result<Self> {
+        let (receiver, sender) = UnixStream::pair()?;
+        receiver.set_nonblocking(true)?;
+        let id = signal_pipe::register(SIGCHLD, sender)?;
+        Ok(Self { receiver, id })
+    }
+
+    /// Number of signals received since the last call
+    fn drain(&mut self) -> usize {
+        let mut buf = [0u8; 64];
+        let mut signals = 0;
+        loop {
+            match self.receiver.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => signals += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        log::error!("Error reading from signal pipe: {}", e);
+                    }
+                    break;
+                }
+            }
+        }
+        signals
+    }
+}
+
+impl Drop for ChildSignals {
+    fn drop(&mut self) {
+        signal_hook::low_level::unregister(self.id);
+    }
+}
+
+/// PTY handed to the event loop; behaves like `tty::Pty` but feeds an [`OutputTap`].
+/// Registers with [`PTY_READ_WRITE_KEY`] and [`CHILD_EVENT_KEY`].
+pub struct TappedPty {
+    pty: tty::Pty,
+    reader: TapReader,
+    signals: ChildSignals,
+}
+
+impl TappedPty {
+    pub fn new(pty: tty::Pty, tap: OutputTap, graphics: GraphicsFilter) -> io::Result<Self> {
+        let file = pty.file().try_clone()?;
+        Ok(Self {
+            pty,
+            signals: ChildSignals::new()?,
+            reader: TapReader {
+                file,
+                tap,
+                graphics,
+                filtered: Vec::new(),
+                filtered_pos: 0,
+            },
+        })
+    }
+
+    pub fn pty(&self) -> &tty::Pty {
+        &self.pty
+    }
+}
+
+impl EventedReadWrite for TappedPty {
+    type Reader = TapReader;
+    type Writer = File;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        // 읽기 대기는 원래 PTY 파일로 등록 (TapReader는 같은 파일의 복제본을 읽음)
+        interest.key = PTY_READ_WRITE_KEY;
+        unsafe {
+            poll.add_with_mode(self.pty.file(), interest, mode)?;
+            poll.add_with_mode(
+                &self.signals.receiver,
+                PollingEvent::readable(CHILD_EVENT_KEY),
+                PollMode::Level,
+            )
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        interest.key = PTY_READ_WRITE_KEY;
+        poll.modify_with_mode(self.pty.file(), interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        poll.delete(self.pty.file())?;
+        poll.delete(&self.signals.receiver)
+    }
+
+    fn reader(&mut self) -> &mut TapReader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut File {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for TappedPty {
+    /// `tty::Pty` checks the child once per signal in its own pipe. signal-hook runs the
+    /// handlers in the order they were registered, so its byte is there before ours.
+    /// Called once without a signal too, in case the shell exited before we registered.
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        let signals = self.signals.drain().max(1);
+        (0..signals).find_map(|_| self.pty.next_child_event())
+    }
+}
+
+impl OnResize for TappedPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.reader.graphics.set_cell_size(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
+
+/// Splits program output into lines of plain text, dropping escape sequences
+pub struct LineAssembler {
+    parser: Parser,
+    lines: LinePerformer,
+}
+
+#[derive(Default)]
+struct LinePerformer {
+    line: String,
+    /// `\r` seen: the next printed character starts the line over (progress bars, prompts)
+    carriage_return: bool,
+    completed: Vec<String>,
+}
+
+impl Perform for LinePerformer {
+    fn print(&mut self, c: char) {
+        if std::mem::take(&mut self.carriage_return) {
+            self.line.clear();
+        }
+        self.line.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => {
+                self.carriage_return = false;
+                self.completed.push(std::mem::take(&mut self.line));
+            }
+            b'\r' => self.carriage_return = true,
+            b'\t' => self.line.push('\t'),
+            0x08 => {
+                self.line.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for LineAssembler {
+    fn default() -> Self {
+        Self {
+            parser: Parser::new(),
+            lines: LinePerformer::default(),
+        }
+    }
+}
+
+impl LineAssembler {
+    /// Feed output bytes, returning the lines they completed
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.parser.advance(&mut self.lines, bytes);
+        std::mem::take(&mut self.lines.completed)
+    }
+
+    /// Text of the line that has not ended yet (e.g. a prompt waiting for input)
+    pub fn partial(&self) -> &str {
+        if self.lines.carriage_return {
+            ""
+        } else {
+            &self.lines.line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_assembler() {
+        let mut lines = LineAssembler::default();
+        assert_eq!(
+            lines.push(b"\x1b[1;31merror\x1b[0m: failed\r\nnext"),
+            vec!["error: failed"]
+        );
+        assert_eq!(lines.partial(), "next");
+
+        // 같은 줄을 \r로 덮어쓰는 진행률 표시는 마지막 내용만 남음
+        assert_eq!(
+            lines.push(b" line\n10%\r50%\r100%\n"),
+            vec!["next line", "100%"]
+        );
+        lines.push(b"Password: ");
+        assert_eq!(lines.partial(), "Password: ");
+    }
+}
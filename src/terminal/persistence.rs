@@ -0,0 +1,158 @@
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+
+use crate::utils::platform::Platform;
+
+use super::SessionOptions;
+
+/// Window size and position on the last quit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    /// Logical size of the window when it was neither maximized nor fullscreen
+    pub width: f32,
+    pub height: f32,
+    /// Physical position of the top-left corner
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+impl WindowState {
+    const FILE_NAME: &'static str = "window.json";
+
+    /// Read the saved window state, `None` when nothing was saved yet
+    pub async fn load() -> Result<Option<Self>> {
+        read_json(Self::FILE_NAME).await
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        write_json(Self::FILE_NAME, self).await
+    }
+}
+
+/// One tab and the shell running in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedTab {
+    pub title: String,
+    pub working_directory: Option<PathBuf>,
+    /// Plain text of the scrollback and screen, when `session.save_scrollback` is on
+    #[serde(default)]
+    pub scrollback: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+impl SavedTab {
+    /// Options for the new shell that replaces this tab
+    pub fn session_options(&self) -> SessionOptions {
+        SessionOptions {
+            title: Some(self.title.clone()),
+            working_directory: self.working_directory.clone(),
+            restored_output: self.scrollback.clone(),
+            profile: self.profile.clone(),
+            ..SessionOptions::default()
+        }
+    }
+}
+
+/// Everything needed to bring the tabs back on the next launch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedSessions {
+    pub tabs: Vec<SavedTab>,
+    /// Index into `tabs`
+    pub active_tab: usize,
+}
+
+impl SavedSessions {
+    const FILE_NAME: &'static str = "sessions.json";
+
+    /// Read the saved sessions, `None` when nothing was saved yet
+    pub async fn load() -> Result<Option<Self>> {
+        read_json(Self::FILE_NAME).await
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        write_json(Self::FILE_NAME, self).await?;
+        log::info!("Saved {} tab(s)", self.tabs.len());
+        Ok(())
+    }
+
+    /// Remove the saved file so the next launch starts fresh
+    pub async fn clear() -> Result<()> {
+        let path = Platform::data_dir()?.join(Self::FILE_NAME);
+        if path.exists() {
+            fs::remove_file(&path).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn read_json<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>> {
+    let path = Platform::data_dir()?.join(file_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).await?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+async fn write_json<T: Serialize>(file_name: &str, value: &T) -> Result<()> {
+    let path = Platform::data_dir()?.join(file_name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(value)?).await?;
+    Ok(())
+}
+
+/// Bytes written into a restored tab before its new shell starts: the old output and a marker
+pub fn restore_banner(scrollback: Option<&str>) -> Option<String> {
+    let scrollback = scrollback
+        .map(str::trim_end)
+        .filter(|text| !text.is_empty())?;
+    let mut banner = scrollback.replace('\n', "\r\n");
+    banner.push_str("\r\n\x1b[0;7m[Restored session]\x1b[0m\r\n");
+    Some(banner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_banner() {
+        assert_eq!(restore_banner(None), None);
+        assert_eq!(restore_banner(Some("  \n\n")), None);
+        assert_eq!(
+            restore_banner(Some("$ ls\na b\n\n")).unwrap(),
+            "$ ls\r\na b\r\n\x1b[0;7m[Restored session]\x1b[0m\r\n"
+        );
+    }
+
+    #[test]
+    fn test_saved_sessions_roundtrip() {
+        let saved = SavedSessions {
+            tabs: vec![SavedTab {
+                title: "Terminal 1".to_string(),
+                working_directory: Some(PathBuf::from("/tmp")),
+                scrollback: None,
+                profile: Some("logs".to_string()),
+            }],
+            active_tab: 0,
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        assert_eq!(serde_json::from_str::<SavedSessions>(&json).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_window_state_defaults() {
+        let state: WindowState =
+            serde_json::from_str(r#"{"width": 800.0, "height": 600.0, "x": 0, "y": 0}"#).unwrap();
+        assert!(!state.maximized && !state.fullscreen);
+    }
+}
@@ -0,0 +1,284 @@
+use alacritty_terminal::{
+    event::{Event, EventListener, Notify, OnResize},
+    event_loop::Msg,
+    sync::FairMutex,
+    term::Term,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+    vte::ansi::Processor,
+};
+use polling::{Event as PollingEvent, Events, PollMode, Poller};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read, Write};
+use std::num::NonZeroUsize;
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
+
+use super::graphics::{ImageAnchors, ImageStore};
+use super::output::{TappedPty, CHILD_EVENT_KEY, PTY_READ_WRITE_KEY};
+use super::EventProxy;
+
+/// Most bytes read from the PTY before waiting for the terminal lock (as in alacritty)
+const READ_BUFFER_SIZE: usize = 0x10_0000;
+
+/// Most bytes parsed while holding the terminal lock
+const MAX_LOCKED_READ: usize = u16::MAX as usize;
+
+/// Sends messages to a PTY thread, waking it up
+#[derive(Clone)]
+pub struct PtySender {
+    sender: mpsc::Sender<Msg>,
+    poller: Arc<Poller>,
+}
+
+impl PtySender {
+    pub fn send(&self, msg: Msg) -> io::Result<()> {
+        self.sender
+            .send(msg)
+            .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "PTY thread has stopped"))?;
+        self.poller.notify()
+    }
+}
+
+/// Input side of a session's PTY
+pub struct Notifier(pub PtySender);
+
+impl Notify for Notifier {
+    fn notify<B>(&self, bytes: B)
+    where
+        B: Into<Cow<'static, [u8]>>,
+    {
+        let bytes = bytes.into();
+        // 빈 입력은 쓰기 대기열을 막으므로 보내지 않음
+        if bytes.is_empty() {
+            return;
+        }
+        let _ = self.0.send(Msg::Input(bytes));
+    }
+}
+
+/// Reads the PTY into the terminal and writes input back, like alacritty's `EventLoop`.
+/// Output is parsed through [`ImageAnchors`] so images are tied to the cells the cursor
+/// is on when the parser reaches them and move with those cells.
+pub struct PtyLoop {
+    poll: Arc<Poller>,
+    pty: TappedPty,
+    receiver: mpsc::Receiver<Msg>,
+    sender: PtySender,
+    terminal: Arc<FairMutex<Term<EventProxy>>>,
+    event_proxy: EventProxy,
+    images: ImageStore,
+    parser: Processor,
+    /// PTY에 쓸 입력과 맨 앞 항목에서 이미 쓴 바이트 수
+    writes: VecDeque<Cow<'static, [u8]>>,
+    written: usize,
+}
+
+impl PtyLoop {
+    pub fn new(
+        terminal: Arc<FairMutex<Term<EventProxy>>>,
+        event_proxy: EventProxy,
+        pty: TappedPty,
+        images: ImageStore,
+    ) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let poll = Arc::new(Poller::new()?);
+        Ok(Self {
+            sender: PtySender {
+                sender,
+                poller: poll.clone(),
+            },
+            poll,
+            pty,
+            receiver,
+            terminal,
+            event_proxy,
+            images,
+            parser: Processor::new(),
+            writes: VecDeque::new(),
+            written: 0,
+        })
+    }
+
+    pub fn channel(&self) -> PtySender {
+        self.sender.clone()
+    }
+
+    /// Run on a thread of its own until shut down or the shell exits.
+    /// Output left when the shell exits is read before `Event::Exit` is sent.
+    pub fn spawn(mut self, name: String) -> io::Result<std::thread::JoinHandle<()>> {
+        std::thread::Builder::new().name(name).spawn(move || {
+            let mut buf = vec![0u8; READ_BUFFER_SIZE];
+            let mut interest = PollingEvent::readable(0);
+            // SAFETY: 등록한 PTY는 루프가 끝날 때 등록 해제하기 전까지 살아 있음
+            if let Err(e) = unsafe { self.pty.register(&self.poll, interest, PollMode::Level) } {
+                log::error!("PTY registration error: {}", e);
+                return;
+            }
+            let mut events = Events::with_capacity(NonZeroUsize::new(1024).unwrap());
+
+            // 등록하기 전에 셸이 이미 끝났을 수 있음
+            if self.child_exited(&mut buf) {
+                let _ = self.pty.deregister(&self.poll);
+                return;
+            }
+
+            'event_loop: loop {
+                // 동기화 출력이 제한 시간에 걸리면 그때 깨어나서 쌓인 출력을 그림
+                let deadline = self.parser.sync_timeout().sync_timeout();
+                let timeout =
+                    deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+                events.clear();
+                if let Err(e) = self.poll.wait(&mut events, timeout) {
+                    if e.kind() == ErrorKind::Interrupted {
+                        continue;
+                    }
+                    log::error!("PTY polling error: {}", e);
+                    break;
+                }
+                if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                    self.parser.stop_sync(&mut ImageAnchors::new(
+                        &mut *self.terminal.lock(),
+                        &self.images,
+                        &self.event_proxy,
+                    ));
+                    self.event_proxy.send_event(Event::Wakeup);
+                }
+
+                while let Ok(msg) = self.receiver.try_recv() {
+                    match msg {
+                        Msg::Input(input) => self.writes.push_back(input),
+                        Msg::Resize(window_size) => self.pty.on_resize(window_size),
+                        Msg::Shutdown => break 'event_loop,
+                    }
+                }
+
+                for event in events.iter() {
+                    match event.key {
+                        CHILD_EVENT_KEY if self.child_exited(&mut buf) => break 'event_loop,
+                        PTY_READ_WRITE_KEY => {
+                            // 닫힌 PTY에는 읽고 쓰지 않음
+                            if event.is_interrupt() {
+                                continue;
+                            }
+                            if event.readable {
+                                if let Err(e) = self.pty_read(&mut buf) {
+                                    // 셸이 끝나면 읽기가 EIO로 실패하고 곧 종료 이벤트가 옴
+                                    #[cfg(target_os = "linux")]
+                                    if e.raw_os_error() == Some(libc::EIO) {
+                                        continue;
+                                    }
+                                    log::error!("Error reading from PTY: {}", e);
+                                    break 'event_loop;
+                                }
+                            }
+                            if event.writable {
+                                if let Err(e) = self.pty_write() {
+                                    log::error!("Error writing to PTY: {}", e);
+                                    break 'event_loop;
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                let needs_write = !self.writes.is_empty();
+                if needs_write != interest.writable {
+                    interest.writable = needs_write;
+                    if let Err(e) = self.pty.reregister(&self.poll, interest, PollMode::Level) {
+                        log::error!("PTY registration error: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            let _ = self.pty.deregister(&self.poll);
+        })
+    }
+
+    /// Whether the shell has exited; if so its last output is read and the exit reported
+    fn child_exited(&mut self, buf: &mut [u8]) -> bool {
+        let Some(ChildEvent::Exited(code)) = self.pty.next_child_event() else {
+            return false;
+        };
+        if let Some(code) = code {
+            self.event_proxy.send_event(Event::ChildExit(code));
+        }
+        let _ = self.pty_read(buf);
+        self.terminal.lock().exit();
+        self.event_proxy.send_event(Event::Wakeup);
+        true
+    }
+
+    /// Read and parse what the PTY has, keeping the terminal locked only once there is output
+    fn pty_read(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let mut unprocessed = 0;
+        let mut processed = 0;
+
+        // 다음 잠금은 UI보다 PTY 읽기가 먼저 가져감
+        let _lease = self.terminal.lease();
+        let mut terminal = None;
+
+        loop {
+            match self.pty.reader().read(&mut buf[unprocessed..]) {
+                Ok(0) if unprocessed == 0 => break,
+                Ok(read) => unprocessed += read,
+                Err(e) => match e.kind() {
+                    ErrorKind::Interrupted | ErrorKind::WouldBlock => {
+                        if unprocessed == 0 {
+                            break;
+                        }
+                    }
+                    _ => return Err(e),
+                },
+            }
+
+            // 잠금을 못 얻으면 버퍼가 찰 때까지 계속 읽음
+            let terminal = match &mut terminal {
+                Some(terminal) => terminal,
+                None => terminal.insert(match self.terminal.try_lock_unfair() {
+                    None if unprocessed >= READ_BUFFER_SIZE => self.terminal.lock_unfair(),
+                    None => continue,
+                    Some(terminal) => terminal,
+                }),
+            };
+            self.parser.advance(
+                &mut ImageAnchors::new(&mut **terminal, &self.images, &self.event_proxy),
+                &buf[..unprocessed],
+            );
+            processed += unprocessed;
+            unprocessed = 0;
+
+            if processed >= MAX_LOCKED_READ {
+                break;
+            }
+        }
+
+        // 모두 동기화 출력에 쌓였으면 다시 그리지 않음
+        if processed > 0 && self.parser.sync_bytes_count() < processed {
+            self.event_proxy.send_event(Event::Wakeup);
+        }
+        Ok(())
+    }
+
+    fn pty_write(&mut self) -> io::Result<()> {
+        while let Some(input) = self.writes.front() {
+            match self.pty.writer().write(&input[self.written..]) {
+                Ok(0) => break,
+                Ok(written) => {
+                    self.written += written;
+                    if self.written >= input.len() {
+                        self.writes.pop_front();
+                        self.written = 0;
+                    }
+                }
+                Err(e) => match e.kind() {
+                    ErrorKind::Interrupted | ErrorKind::WouldBlock => break,
+                    _ => return Err(e),
+                },
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+/// Name and version reported by XTVERSION
+pub const VERSION: &str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
+/// Built-in terminfo description answered to XTGETTCAP, as (capability, value).
+/// Boolean capabilities have no value. Based on xterm-256color, with the keys sterm sends.
+const TERMINFO: &[(&str, Option<&str>)] = &[
+    ("TN", Some("xterm-256color")),
+    ("name", Some("xterm-256color")),
+    ("Co", Some("256")),
+    ("colors", Some("256")),
+    ("RGB", Some("8/8/8")),
+    ("Tc", None),
+    ("am", None),
+    ("bce", None),
+    ("ccc", None),
+    ("km", None),
+    ("mir", None),
+    ("msgr", None),
+    ("xenl", None),
+    ("it", Some("8")),
+    ("bel", Some("\x07")),
+    ("blink", Some("\x1b[5m")),
+    ("bold", Some("\x1b[1m")),
+    ("dim", Some("\x1b[2m")),
+    ("sitm", Some("\x1b[3m")),
+    ("ritm", Some("\x1b[23m")),
+    ("smul", Some("\x1b[4m")),
+    ("rmul", Some("\x1b[24m")),
+    ("rev", Some("\x1b[7m")),
+    ("invis", Some("\x1b[8m")),
+    ("smxx", Some("\x1b[9m")),
+    ("rmxx", Some("\x1b[29m")),
+    ("sgr0", Some("\x1b(B\x1b[m")),
+    ("smso", Some("\x1b[7m")),
+    ("rmso", Some("\x1b[27m")),
+    ("op", Some("\x1b[39;49m")),
+    (
+        "setaf",
+        Some("\x1b[%?%p1%{8}%<%t3%p1%d%e%p1%{16}%<%t9%p1%{8}%-%d%e38;5;%p1%d%;m"),
+    ),
+    (
+        "setab",
+        Some("\x1b[%?%p1%{8}%<%t4%p1%d%e%p1%{16}%<%t10%p1%{8}%-%d%e48;5;%p1%d%;m"),
+    ),
+    ("setrgbf", Some("\x1b[38:2:%p1%d:%p2%d:%p3%dm")),
+    ("setrgbb", Some("\x1b[48:2:%p1%d:%p2%d:%p3%dm")),
+    // 밑줄 모양과 색 (SGR 4:n, 58)
+    ("Su", None),
+    ("Smulx", Some("\x1b[4:%p1%dm")),
+    (
+        "Setulc",
+        Some("\x1b[58:2::%p1%{65536}%/%d:%p1%{256}%/%{255}%&%d:%p1%{255}%&%dm"),
+    ),
+    ("Ss", Some("\x1b[%p1%d q")),
+    ("Se", Some("\x1b[2 q")),
+    ("Sync", Some("\x1b[?2026%?%p1%{1}%-%tl%eh%;")),
+    ("Ms", Some("\x1b]52;%p1%s;%p2%s\x07")),
+    ("clear", Some("\x1b[H\x1b[2J")),
+    ("E3", Some("\x1b[3J")),
+    ("el", Some("\x1b[K")),
+    ("el1", Some("\x1b[1K")),
+    ("ed", Some("\x1b[J")),
+    ("cup", Some("\x1b[%i%p1%d;%p2%dH")),
+    ("home", Some("\x1b[H")),
+    ("civis", Some("\x1b[?25l")),
+    ("cnorm", Some("\x1b[?12l\x1b[?25h")),
+    ("cvvis", Some("\x1b[?12;25h")),
+    ("sc", Some("\x1b7")),
+    ("rc", Some("\x1b8")),
+    ("csr", Some("\x1b[%i%p1%d;%p2%dr")),
+    ("smcup", Some("\x1b[?1049h\x1b[22;0;0t")),
+    ("rmcup", Some("\x1b[?1049l\x1b[23;0;0t")),
+    ("BE", Some("\x1b[?2004h")),
+    ("BD", Some("\x1b[?2004l")),
+    ("PS", Some("\x1b[200~")),
+    ("PE", Some("\x1b[201~")),
+    ("fe", Some("\x1b[?1004h")),
+    ("fd", Some("\x1b[?1004l")),
+    ("tsl", Some("\x1b]2;")),
+    ("fsl", Some("\x07")),
+    // sterm이 보내는 키 (앱 커서 모드 없이 항상 CSI)
+    ("kbs", Some("\x7f")),
+    ("kcuu1", Some("\x1b[A")),
+    ("kcud1", Some("\x1b[B")),
+    ("kcuf1", Some("\x1b[C")),
+    ("kcub1", Some("\x1b[D")),
+    ("khome", Some("\x1b[H")),
+    ("kend", Some("\x1b[F")),
+    ("kdch1", Some("\x1b[3~")),
+    ("kpp", Some("\x1b[5~")),
+    ("knp", Some("\x1b[6~")),
+    ("kcbt", Some("\x1b[Z")),
+];
+
+/// Answer to a device attributes request with an intermediate: DA2 (`CSI > c`), which
+/// `Term` would answer with alacritty's version, and DA3 (`CSI = c`), which it ignores
+pub fn answer_attributes(intermediate: char) -> Option<String> {
+    match intermediate {
+        '>' => Some(secondary_attributes()),
+        // 장치 번호는 없으므로 0
+        '=' => Some("\x1bP!|00000000\x1b\\".to_string()),
+        _ => None,
+    }
+}
+
+/// XTVERSION answer (`CSI > q`), a query the terminal parser doesn't pass on
+pub fn answer_version() -> String {
+    format!("\x1bP>|{}\x1b\\", VERSION)
+}
+
+/// DA2: VT100 class with sterm's version as a number (0.1.0 → 100), like alacritty does
+fn secondary_attributes() -> String {
+    let version = env!("CARGO_PKG_VERSION")
+        .split('.')
+        .take(3)
+        .fold(0, |number, part| {
+            number * 100 + part.parse::<u32>().unwrap_or(0)
+        });
+    format!("\x1b[>0;{};1c", version)
+}
+
+/// XTGETTCAP answer for the body of `DCS + q name;name… ST`, names hex-encoded.
+/// Each name gets its own reply: `DCS 1 + r name=value ST` when known, `DCS 0 + r name ST` otherwise.
+pub fn answer_termcap(request: &[u8]) -> String {
+    let mut reply = String::new();
+    for encoded in request.split(|&b| b == b';') {
+        let encoded = String::from_utf8_lossy(encoded).to_ascii_uppercase();
+        let capability = decode_hex(&encoded).and_then(|name| {
+            TERMINFO
+                .iter()
+                .find(|(capability, _)| capability.as_bytes() == name)
+        });
+        let _ = match capability {
+            Some((_, Some(value))) => write!(
+                reply,
+                "\x1bP1+r{}={}\x1b\\",
+                encoded,
+                encode_hex(value.as_bytes())
+            ),
+            Some((_, None)) => write!(reply, "\x1bP1+r{}\x1b\\", encoded),
+            None => write!(reply, "\x1bP0+r{}\x1b\\", encoded),
+        };
+    }
+    reply
+}
+
+/// Bytes of a hex string; `None` if any pair (or a lone last digit) isn't hex
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GraphicsConfig;
+    use crate::terminal::graphics::{GraphicsFilter, ImageAnchors, ImageStore};
+    use crate::terminal::EventProxy;
+    use alacritty_terminal::event::{Event, WindowSize};
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::{Config, Term};
+    use alacritty_terminal::vte::ansi::Processor;
+
+    #[test]
+    fn test_termcap_replies() {
+        // TN, Tc, 없는 이름, 잘못된 16진수
+        let reply = answer_termcap(b"544e;5463;787878;5");
+        assert_eq!(
+            reply,
+            "\x1bP1+r544E=787465726D2D323536636F6C6F72\x1b\\\
+             \x1bP1+r5463\x1b\\\
+             \x1bP0+r787878\x1b\\\
+             \x1bP0+r5\x1b\\"
+        );
+        assert_eq!(
+            answer_termcap(b"6b63757531"),
+            "\x1bP1+r6B63757531=1B5B41\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_replies_reach_the_pty() {
+        use crate::terminal::{SessionOptions, TerminalSession};
+        use std::time::{Duration, Instant};
+
+        // 프로그램이 질의를 보내고 PTY에서 읽은 응답을 파일로 옮김
+        let path = std::env::temp_dir().join(format!("sterm-replies-{}", std::process::id()));
+        let expected = format!("{}\x1bP>|{}\x1b\\", secondary_attributes(), VERSION);
+        let script = format!(
+            "stty raw -echo; printf '\\033[>c\\033[>q'; head -c {} > '{}'",
+            expected.len(),
+            path.display()
+        );
+        let options = SessionOptions {
+            command: vec!["sh".into(), "-c".into(), script],
+            ..Default::default()
+        };
+        let (sender, _events) = std::sync::mpsc::channel();
+        let _session =
+            TerminalSession::new(0, &crate::config::Config::default(), options, sender).unwrap();
+
+        let start = Instant::now();
+        let mut replies = String::new();
+        while replies.len() < expected.len() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+            replies = std::fs::read_to_string(&path).unwrap_or_default();
+        }
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(replies, expected);
+    }
+
+    #[test]
+    fn test_replies_go_back_in_order() {
+        // 필터와 파서를 거쳐 PTY로 돌아갈 응답을 차례대로 확인
+        let (proxy, events) = EventProxy::new(true);
+        let size = WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 8,
+            cell_height: 16,
+        };
+        let store = ImageStore::new(1024 * 1024);
+        let mut filter = GraphicsFilter::new(store.clone(), &GraphicsConfig::default(), size);
+        let mut term = Term::new(Config::default(), &TermSize::new(80, 24), proxy.clone());
+        let mut parser: Processor = Processor::new();
+
+        let mut output = Vec::new();
+        filter.filter(
+            b"\x1b[c\x1b[>c\x1b[=c\x1b[>q\x1b[?2004h\x1b[?2004$p\x1b[4$p\x1bP+q544e\x1b\\\x1b[5n",
+            &mut output,
+        );
+        parser.advance(&mut ImageAnchors::new(&mut term, &store, &proxy), &output);
+
+        let replies: Vec<String> = events
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::PtyWrite(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            replies,
+            [
+                "\x1b[?62;4;22c".to_string(),
+                secondary_attributes(),
+                "\x1bP!|00000000\x1b\\".to_string(),
+                format!("\x1bP>|{}\x1b\\", VERSION),
+                "\x1b[?2004;1$y".to_string(),
+                "\x1b[4;2$y".to_string(),
+                "\x1bP1+r544E=787465726D2D323536636F6C6F72\x1b\\".to_string(),
+                "\x1b[0n".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use alacritty_terminal::vte::ansi::CursorShape;
+use anyhow::Result;
+use resvg::{tiny_skia, usvg};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+
+use super::ColoredTerminalContent;
+use crate::utils::font::{FontMetrics, FontStyle, FontWeight, UnderlineStyle};
+
+/// Font the UI draws the terminal with
+const FONT_FAMILY: &str = "D2Coding";
+
+/// Rectangle of cells to draw, counted from the top-left of the visible screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenRegion {
+    pub line: usize,
+    pub col: usize,
+    pub lines: usize,
+    pub cols: usize,
+}
+
+impl std::str::FromStr for ScreenRegion {
+    type Err = anyhow::Error;
+
+    /// `LINE,COL,LINES,COLS`
+    fn from_str(s: &str) -> Result<Self> {
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("Invalid region {:?}: {}", s, e))?;
+        match values[..] {
+            [line, col, lines, cols] if lines > 0 && cols > 0 => Ok(Self {
+                line,
+                col,
+                lines,
+                cols,
+            }),
+            _ => Err(anyhow::anyhow!(
+                "Expected LINE,COL,LINES,COLS with non-zero size, got {:?}",
+                s
+            )),
+        }
+    }
+}
+
+/// Draw the visible screen (or `region` of it) as a standalone SVG document.
+/// Cells are laid out on the same grid as the UI, so the picture matches the window.
+pub fn render_svg(
+    content: &ColoredTerminalContent,
+    metrics: &FontMetrics,
+    font_size: i32,
+    region: Option<ScreenRegion>,
+) -> String {
+    let region = region.unwrap_or(ScreenRegion {
+        line: 0,
+        col: 0,
+        lines: content.total_lines,
+        cols: content.total_cols,
+    });
+    let width = metrics.padding_x * 2 + region.cols as i32 * metrics.char_width;
+    let height = metrics.padding_y * 2 + region.lines as i32 * metrics.line_height;
+    // 영역 기준 셀 좌표를 픽셀로
+    let cell_x = |col: usize| metrics.padding_x + (col - region.col) as i32 * metrics.char_width;
+    let cell_y =
+        |line: usize| metrics.padding_y + (line - region.line) as i32 * metrics.line_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        content.background.to_hex()
+    );
+    let _ = writeln!(
+        svg,
+        r#"<g font-family="{FONT_FAMILY}, monospace" font-size="{font_size}" xml:space="preserve">"#
+    );
+
+    for segment in &content.segments {
+        if segment.line < region.line || segment.line >= region.line + region.lines {
+            continue;
+        }
+        let Some((start_col, end_col, text)) = clip_segment(segment, &region) else {
+            continue;
+        };
+        let x = cell_x(start_col);
+        let y = cell_y(segment.line);
+        let cell_width = (end_col - start_col) as i32 * metrics.char_width;
+
+        if segment.bg_color != content.background {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x}" y="{y}" width="{cell_width}" height="{}" fill="{}"/>"#,
+                metrics.line_height,
+                segment.bg_color.to_hex()
+            );
+        }
+        if segment.attributes.hidden || text.trim().is_empty() {
+            continue;
+        }
+
+        let mut attributes = format!(r#" fill="{}""#, segment.fg_color.to_hex());
+        if segment.attributes.weight == FontWeight::Bold {
+            attributes.push_str(r#" font-weight="bold""#);
+        }
+        if segment.attributes.style == FontStyle::Italic {
+            attributes.push_str(r#" font-style="italic""#);
+        }
+        // 한 칸짜리 문자 묶음은 폰트 폭과 상관없이 셀 격자에 맞춤
+        if text.chars().count() == end_col - start_col {
+            let _ = write!(
+                attributes,
+                r#" textLength="{cell_width}" lengthAdjust="spacingAndGlyphs""#
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x}" y="{}"{attributes}>{}</text>"#,
+            y + metrics.baseline,
+            escape_xml(&text)
+        );
+
+        if segment.attributes.underline != UnderlineStyle::None {
+            let _ = writeln!(
+                svg,
+                r#"<path transform="translate({x} {})" d="{}" stroke="{}" stroke-width="1" fill="none"/>"#,
+                y + metrics.line_height - 4,
+                segment.attributes.underline.svg_path(cell_width),
+                segment.underline_color.to_hex()
+            );
+        }
+        if segment.attributes.strikethrough {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x}" y="{}" width="{cell_width}" height="1" fill="{}"/>"#,
+                y + metrics.line_height / 2,
+                segment.fg_color.to_hex()
+            );
+        }
+    }
+    svg.push_str("</g>\n");
+
+    let cursor_visible = content.cursor_line >= region.line
+        && content.cursor_line < region.line + region.lines
+        && content.cursor_col >= region.col
+        && content.cursor_col < region.col + region.cols;
+    if cursor_visible && content.cursor_shape != CursorShape::Hidden {
+        svg.push_str(&cursor_svg(
+            content,
+            metrics,
+            cell_x(content.cursor_col),
+            cell_y(content.cursor_line),
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Part of `segment` inside the region's columns: (start column, end column, text)
+fn clip_segment(
+    segment: &super::ColoredTextSegment,
+    region: &ScreenRegion,
+) -> Option<(usize, usize, String)> {
+    let region_end = region.col + region.cols;
+    if segment.end_col <= region.col || segment.start_col >= region_end {
+        return None;
+    }
+    // 와이드 문자나 결합 문자는 단독 세그먼트이므로 통째로 넣거나 뺌
+    if segment.text.chars().count() != segment.end_col - segment.start_col {
+        let inside = segment.start_col >= region.col && segment.end_col <= region_end;
+        return inside.then(|| (segment.start_col, segment.end_col, segment.text.clone()));
+    }
+    let start = segment.start_col.max(region.col);
+    let end = segment.end_col.min(region_end);
+    let text = segment
+        .text
+        .chars()
+        .skip(start - segment.start_col)
+        .take(end - start)
+        .collect();
+    Some((start, end, text))
+}
+
+fn cursor_svg(content: &ColoredTerminalContent, metrics: &FontMetrics, x: i32, y: i32) -> String {
+    let color = content.cursor_color.to_hex();
+    let thickness = std::cmp::max(1, metrics.line_height / 8);
+    let width = if content.cursor_wide {
+        metrics.char_width * 2
+    } else {
+        metrics.char_width
+    };
+    match content.cursor_shape {
+        CursorShape::Beam => {
+            format!(
+                r#"<rect x="{x}" y="{y}" width="{thickness}" height="{}" fill="{color}"/>"#,
+                metrics.line_height
+            ) + "\n"
+        }
+        CursorShape::Underline => {
+            format!(
+                r#"<rect x="{x}" y="{}" width="{width}" height="{thickness}" fill="{color}"/>"#,
+                y + metrics.line_height - thickness
+            ) + "\n"
+        }
+        CursorShape::HollowBlock => {
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{color}"/>"#,
+                x as f32 + 0.5,
+                y as f32 + 0.5,
+                width - 1,
+                metrics.line_height - 1
+            ) + "\n"
+        }
+        // 블록 커서는 글자가 보이도록 반투명하게
+        _ => {
+            format!(
+                r#"<rect x="{x}" y="{y}" width="{width}" height="{}" fill="{color}" fill-opacity="0.5"/>"#,
+                metrics.line_height
+            ) + "\n"
+        }
+    }
+}
+
+/// Rasterize an SVG from [`render_svg`] to PNG in software, with the system fonts
+pub fn render_png(svg: &str) -> Result<Vec<u8>> {
+    // 시스템 폰트 목록은 처음 한 번만 읽음
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    let fonts = FONTS.get_or_init(|| {
+        let mut fonts = usvg::fontdb::Database::new();
+        fonts.load_system_fonts();
+        fonts.set_monospace_family(FONT_FAMILY);
+        Arc::new(fonts)
+    });
+
+    let options = usvg::Options {
+        fontdb: fonts.clone(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("Screenshot is empty"))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            // XML에 쓸 수 없는 제어 문자
+            c if c.is_control() => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::ColoredTextSegment;
+    use crate::utils::color::Color;
+    use crate::utils::font::TextAttributes;
+
+    fn content() -> ColoredTerminalContent {
+        let metrics = FontMetrics::default();
+        let background = Color::rgb(0, 0, 0);
+        let segment =
+            |line: usize, start_col: usize, text: &str, bg_color: Color| ColoredTextSegment {
+                text: text.to_string(),
+                fg_color: Color::rgb(255, 255, 255),
+                bg_color,
+                line,
+                start_col,
+                end_col: start_col + text.chars().count(),
+                x: 0,
+                y: 0,
+                width: 0,
+                height: metrics.line_height,
+                attributes: TextAttributes::new(),
+                underline_color: Color::rgb(255, 255, 255),
+            };
+        ColoredTerminalContent {
+            segments: vec![
+                segment(0, 0, "$ echo <hi>", background),
+                segment(1, 0, "hello", Color::rgb(0, 0, 255)),
+            ],
+            cursor_line: 1,
+            cursor_col: 5,
+            cursor_shape: CursorShape::Block,
+            cursor_blinking: false,
+            cursor_wide: false,
+            cursor_color: Color::rgb(200, 200, 200),
+            vi_mode: false,
+            background,
+            foreground: Color::rgb(255, 255, 255),
+            total_lines: 3,
+            total_cols: 20,
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_region_and_escaping() {
+        let metrics = FontMetrics::default();
+        let svg = render_svg(&content(), &metrics, 11, None);
+        assert!(svg.contains("&lt;hi&gt;</text>"));
+        // 기본 배경이 아닌 칸만 배경 사각형을 그림
+        assert_eq!(svg.matches("fill=\"#0000ff\"").count(), 1);
+
+        let region: ScreenRegion = "0,2,1,4".parse().unwrap();
+        let svg = render_svg(&content(), &metrics, 11, Some(region));
+        assert!(svg.contains(">echo</text>"));
+        assert!(!svg.contains("hello"));
+        assert!(!svg.contains("fill-opacity"));
+        assert!("0,0,0,4".parse::<ScreenRegion>().is_err());
+    }
+
+    #[test]
+    fn test_png_has_svg_size() {
+        let metrics = FontMetrics::default();
+        let png = render_png(&render_svg(&content(), &metrics, 11, None)).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // IHDR: 너비와 높이
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!(
+            width as i32,
+            metrics.padding_x * 2 + 20 * metrics.char_width
+        );
+        assert_eq!(
+            height as i32,
+            metrics.padding_y * 2 + 3 * metrics.line_height
+        );
+    }
+}
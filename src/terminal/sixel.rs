@@ -0,0 +1,279 @@
+use anyhow::Result;
+
+/// Largest image accepted, in pixels per side
+const MAX_SIZE: usize = 4096;
+
+/// VT340 default color registers 0-15
+const VT340_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [51, 51, 204],
+    [204, 33, 33],
+    [51, 204, 51],
+    [204, 51, 204],
+    [51, 204, 204],
+    [204, 204, 51],
+    [135, 135, 135],
+    [66, 66, 66],
+    [84, 84, 153],
+    [153, 66, 66],
+    [84, 153, 84],
+    [153, 84, 153],
+    [84, 153, 153],
+    [153, 153, 84],
+    [204, 204, 204],
+];
+
+/// Decoded Sixel image
+pub struct SixelImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+/// Pixels drawn so far; grows as sixels land outside it. 0 means not drawn.
+#[derive(Default)]
+struct Canvas {
+    stride: usize,
+    rows: usize,
+    pixels: Vec<u32>,
+    width: usize,
+    height: usize,
+}
+
+impl Canvas {
+    fn set(&mut self, x: usize, y: usize, color: u32) {
+        if x >= MAX_SIZE || y >= MAX_SIZE {
+            return;
+        }
+        if x >= self.stride {
+            let stride = (x + 1).max(self.stride * 2).min(MAX_SIZE);
+            let mut pixels = vec![0; stride * self.rows];
+            for row in 0..self.rows {
+                pixels[row * stride..row * stride + self.stride]
+                    .copy_from_slice(&self.pixels[row * self.stride..(row + 1) * self.stride]);
+            }
+            self.pixels = pixels;
+            self.stride = stride;
+        }
+        if y >= self.rows {
+            self.rows = (y + 1).max(self.rows * 2).min(MAX_SIZE);
+            self.pixels.resize(self.stride * self.rows, 0);
+        }
+        self.pixels[y * self.stride + x] = color;
+        self.width = self.width.max(x + 1);
+        self.height = self.height.max(y + 1);
+    }
+
+    fn get(&self, x: usize, y: usize) -> u32 {
+        if x < self.stride && y < self.rows {
+            self.pixels[y * self.stride + x]
+        } else {
+            0
+        }
+    }
+}
+
+/// Decode the body of `DCS P1;P2;P3 q ... ST`.
+/// `params` are the header parameters; only P2 (1 = undrawn pixels stay transparent) is used.
+/// The pixel aspect ratio comes from the raster attributes (`"Pan;Pad;Ph;Pv`), not from P1.
+pub fn decode(params: &[u8], data: &[u8]) -> Result<SixelImage> {
+    let transparent = params.split(|&b| b == b';').nth(1) == Some(b"1");
+
+    let mut palette = [0u32; 256];
+    for (register, rgb) in VT340_PALETTE.iter().enumerate() {
+        palette[register] = rgba(*rgb);
+    }
+    let mut canvas = Canvas::default();
+    let mut color = palette[0];
+    // 세로 픽셀 배율 (Pan/Pad)
+    let mut aspect = 1;
+    let mut raster = (0, 0);
+    let (mut x, mut band) = (0usize, 0usize);
+
+    let mut i = 0;
+    while i < data.len() {
+        let byte = data[i];
+        i += 1;
+        match byte {
+            // 래스터 속성: 픽셀 비율과 이미지 크기
+            b'"' => {
+                let values = parse_numbers(data, &mut i);
+                if let [pan, pad, ..] = values[..] {
+                    if let Some(ratio) = pan.checked_div(pad) {
+                        aspect = ratio.clamp(1, 16) as usize;
+                    }
+                }
+                if let [_, _, width, height, ..] = values[..] {
+                    raster = (width as usize, height as usize);
+                }
+            }
+            // 색상 선택 (#Pc) 또는 정의 (#Pc;Pu;Px;Py;Pz)
+            b'#' => {
+                let values = parse_numbers(data, &mut i);
+                let Some(&register) = values.first() else {
+                    continue;
+                };
+                let register = register as usize % palette.len();
+                if let [_, space, a, b, c, ..] = values[..] {
+                    palette[register] = match space {
+                        1 => rgba(hls_to_rgb(a, b, c)),
+                        _ => rgba([percent(a), percent(b), percent(c)]),
+                    };
+                }
+                color = palette[register];
+            }
+            // 반복: !Pn 다음 sixel 문자를 Pn번
+            b'!' => {
+                let count = parse_numbers(data, &mut i).first().copied().unwrap_or(1);
+                if let Some(&sixel @ 0x3F..=0x7E) = data.get(i) {
+                    i += 1;
+                    let count = (count.max(1) as usize).min(MAX_SIZE.saturating_sub(x));
+                    draw(&mut canvas, sixel, x, band, count, aspect, color);
+                    x += count;
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                band += 6 * aspect;
+            }
+            0x3F..=0x7E if x < MAX_SIZE => {
+                draw(&mut canvas, byte, x, band, 1, aspect, color);
+                x += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let width = canvas.width.max(raster.0).min(MAX_SIZE);
+    let height = canvas.height.max(raster.1).min(MAX_SIZE);
+    if width == 0 || height == 0 {
+        return Err(anyhow::anyhow!("Sixel image is empty"));
+    }
+    // VT340은 그리지 않은 픽셀을 0번 색으로 채움
+    let background = if transparent { 0 } else { palette[0] };
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = match canvas.get(x, y) {
+                0 => background,
+                pixel => pixel,
+            };
+            rgba.extend_from_slice(&pixel.to_le_bytes());
+        }
+    }
+    Ok(SixelImage {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+fn draw(
+    canvas: &mut Canvas,
+    sixel: u8,
+    x: usize,
+    band: usize,
+    count: usize,
+    aspect: usize,
+    color: u32,
+) {
+    let bits = sixel - 0x3F;
+    for bit in 0..6 {
+        if bits & (1 << bit) == 0 {
+            continue;
+        }
+        for dy in 0..aspect {
+            let y = band + bit * aspect + dy;
+            for dx in 0..count {
+                canvas.set(x + dx, y, color);
+            }
+        }
+    }
+}
+
+/// `;`-separated decimal numbers starting at `data[*i]`
+fn parse_numbers(data: &[u8], i: &mut usize) -> Vec<u32> {
+    let mut values = Vec::new();
+    let mut current: Option<u32> = None;
+    while let Some(&byte) = data.get(*i) {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = (byte - b'0') as u32;
+                current = Some(
+                    current
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+            }
+            b';' => values.push(current.take().unwrap_or(0)),
+            _ => break,
+        }
+        *i += 1;
+    }
+    values.extend(current);
+    values
+}
+
+fn rgba([r, g, b]: [u8; 3]) -> u32 {
+    u32::from_le_bytes([r, g, b, 255])
+}
+
+fn percent(value: u32) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// DEC HLS: hue 0 is blue, 120 red and 240 green; lightness and saturation in percent
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue % 360 + 240) % 360) as f32 / 60.0;
+    let lightness = lightness.min(100) as f32 / 100.0;
+    let saturation = saturation.min(100) as f32 / 100.0;
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f32| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    [channel(r), channel(g), channel(b)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(image: &SixelImage, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * image.width + x) * 4) as usize;
+        image.rgba[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_decode_colors_and_bands() {
+        // 빨강 2픽셀 폭 6줄, 다음 밴드에 HLS 파랑(0도) 맨 윗줄 1픽셀
+        let image = decode(b"0;1", b"#1;2;100;0;0#1!2~-#2;1;0;50;100@").unwrap();
+        assert_eq!((image.width, image.height), (2, 7));
+        assert_eq!(pixel(&image, 1, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 0, 6), [0, 0, 255, 255]);
+        // P2=1: 그리지 않은 픽셀은 투명
+        assert_eq!(pixel(&image, 1, 6), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_raster_attributes() {
+        // 1:2 비율이면 sixel 한 줄이 두 픽셀 높이, 크기는 래스터 속성으로
+        let image = decode(b"", b"\"2;1;4;14#0;2;0;100;0#3@").unwrap();
+        assert_eq!((image.width, image.height), (4, 14));
+        assert_eq!(pixel(&image, 0, 1), [51, 204, 51, 255]);
+        // 배경은 0번 레지스터 색
+        assert_eq!(pixel(&image, 0, 2), [0, 255, 0, 255]);
+        assert!(decode(b"", b"#1").is_err());
+    }
+}
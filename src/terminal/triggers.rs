@@ -0,0 +1,333 @@
+use alacritty_terminal::{
+    grid::{Dimensions, Grid},
+    index::{Column, Line},
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color as AnsiColor, Rgb},
+};
+use regex::Regex;
+
+use super::links::LineText;
+use crate::config::{TriggerAction, TriggerConfig};
+use crate::utils::color::Color;
+
+/// How far above the bottom of the screen a highlighted line is looked for
+const HIGHLIGHT_SEARCH_ROWS: i32 = 100;
+
+/// What a matching trigger asks the session to do, with captures already substituted
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerHit {
+    /// Recolor `text`, the matched part of the line
+    Highlight {
+        text: String,
+        foreground: Option<Rgb>,
+        background: Option<Rgb>,
+    },
+    Bell,
+    Notify(String),
+    Send(String),
+    Badge(String),
+}
+
+struct Trigger {
+    regex: Regex,
+    action: TriggerAction,
+    instant: bool,
+}
+
+/// Compiled triggers of one session and which of them already fired on the current line
+#[derive(Default)]
+pub struct Triggers {
+    triggers: Vec<Trigger>,
+    fired: Vec<bool>,
+}
+
+impl Triggers {
+    /// Compile the configured triggers; invalid patterns are logged and skipped
+    pub fn new<'a>(configs: impl IntoIterator<Item = &'a TriggerConfig>) -> Self {
+        let triggers: Vec<Trigger> = configs
+            .into_iter()
+            .filter_map(|config| match Regex::new(&config.regex) {
+                Ok(regex) => Some(Trigger {
+                    regex,
+                    action: config.action.clone(),
+                    instant: config.instant,
+                }),
+                Err(e) => {
+                    log::warn!(
+                        "Ignoring trigger with invalid regex {:?}: {}",
+                        config.regex,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+        let fired = vec![false; triggers.len()];
+        Self { triggers, fired }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    /// A finished line: runs every trigger that has not already fired on it as a partial line
+    pub fn line_completed(&mut self, line: &str) -> Vec<TriggerHit> {
+        let hits = self.evaluate(line, false);
+        self.fired.iter_mut().for_each(|fired| *fired = false);
+        hits
+    }
+
+    /// The unfinished last line: runs `instant` triggers, each at most once per line
+    pub fn partial_line(&mut self, line: &str) -> Vec<TriggerHit> {
+        if line.is_empty() {
+            return Vec::new();
+        }
+        self.evaluate(line, true)
+    }
+
+    fn evaluate(&mut self, line: &str, instant_only: bool) -> Vec<TriggerHit> {
+        let mut hits = Vec::new();
+        for (trigger, fired) in self.triggers.iter().zip(self.fired.iter_mut()) {
+            if *fired || (instant_only && !trigger.instant) {
+                continue;
+            }
+            let Some(captures) = trigger.regex.captures(line) else {
+                continue;
+            };
+            *fired = true;
+
+            let expand = |template: &str| {
+                let mut expanded = String::new();
+                captures.expand(template, &mut expanded);
+                expanded
+            };
+            hits.push(match &trigger.action {
+                TriggerAction::Highlight {
+                    foreground,
+                    background,
+                } => TriggerHit::Highlight {
+                    text: captures[0].to_string(),
+                    foreground: foreground.as_deref().and_then(parse_rgb),
+                    background: background.as_deref().and_then(parse_rgb),
+                },
+                TriggerAction::Bell => TriggerHit::Bell,
+                TriggerAction::Notify { message } => {
+                    TriggerHit::Notify(message.as_deref().map_or_else(|| line.to_string(), expand))
+                }
+                TriggerAction::Send { text } => TriggerHit::Send(expand(text)),
+                TriggerAction::Badge { text } => TriggerHit::Badge(expand(text)),
+            });
+        }
+        hits
+    }
+}
+
+/// Rows of one logical line: a grid line together with the rows it wrapped onto
+struct LogicalLine {
+    rows: Vec<(i32, LineText)>,
+}
+
+impl LogicalLine {
+    /// The logical line `row` is part of
+    fn at(grid: &Grid<Cell>, row: i32) -> Self {
+        let last_column = Column(grid.columns() - 1);
+        let wraps = |row: i32| grid[Line(row)][last_column].flags.contains(Flags::WRAPLINE);
+
+        let mut top = row;
+        while top > grid.topmost_line().0 && wraps(top - 1) {
+            top -= 1;
+        }
+        let mut bottom = row;
+        while bottom < grid.bottommost_line().0 && wraps(bottom) {
+            bottom += 1;
+        }
+        let rows = (top..=bottom)
+            .map(|row| (row, LineText::from_grid(grid, Line(row))))
+            .collect();
+        Self { rows }
+    }
+
+    fn top(&self) -> i32 {
+        self.rows[0].0
+    }
+
+    fn text(&self) -> String {
+        self.rows
+            .iter()
+            .map(|(_, line)| line.text.as_str())
+            .collect()
+    }
+
+    /// Recolor the first occurrence of `text`, which may run across wrapped rows
+    fn recolor(
+        &self,
+        grid: &mut Grid<Cell>,
+        text: &str,
+        foreground: Option<Rgb>,
+        background: Option<Rgb>,
+    ) -> bool {
+        let Some(start) = self.text().find(text) else {
+            return false;
+        };
+        let end = start + text.len();
+        let mut offset = 0;
+        for (row, line) in &self.rows {
+            let (from, to) = (start.max(offset), end.min(offset + line.text.len()));
+            if from < to {
+                let (start_col, end_col) = line.column_range(from - offset, to - offset);
+                for col in start_col..end_col {
+                    let cell = &mut grid[Line(*row)][Column(col)];
+                    if let Some(rgb) = foreground {
+                        cell.fg = AnsiColor::Spec(rgb);
+                    }
+                    if let Some(rgb) = background {
+                        cell.bg = AnsiColor::Spec(rgb);
+                    }
+                }
+            }
+            offset += line.text.len();
+        }
+        true
+    }
+}
+
+/// Screen text and output text only differ in whitespace (tabs, padding, trailing blanks)
+fn same_text(screen: &str, output: &str) -> bool {
+    let visible = |text: &str| {
+        text.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+    };
+    visible(screen) == visible(output)
+}
+
+/// Find where completed output lines ended up on the grid, searching upward from the
+/// bottom of the screen. Later lines are looked for first and earlier ones only above them.
+/// Returns the top row of each line's logical line; None for lines not on the grid (any more).
+pub fn locate_lines(grid: &Grid<Cell>, lines: &[&str]) -> Vec<Option<i32>> {
+    let bottom = grid.bottommost_line().0;
+    let limit = (bottom - HIGHLIGHT_SEARCH_ROWS).max(grid.topmost_line().0);
+    let mut below = bottom;
+    let mut found = vec![None; lines.len()];
+    for (index, text) in lines.iter().enumerate().rev() {
+        let mut row = below;
+        while row >= limit {
+            let line = LogicalLine::at(grid, row);
+            if same_text(&line.text(), text) {
+                found[index] = Some(line.top());
+                below = line.top() - 1;
+                break;
+            }
+            row = line.top() - 1;
+        }
+    }
+    found
+}
+
+/// Recolor the first occurrence of `text` on the logical line starting at `row`.
+/// Returns false when the line was overwritten in the meantime.
+pub fn highlight(
+    grid: &mut Grid<Cell>,
+    row: i32,
+    text: &str,
+    foreground: Option<Rgb>,
+    background: Option<Rgb>,
+) -> bool {
+    if text.is_empty() || row < grid.topmost_line().0 || row > grid.bottommost_line().0 {
+        return false;
+    }
+    LogicalLine::at(grid, row).recolor(grid, text, foreground, background)
+}
+
+fn parse_rgb(hex: &str) -> Option<Rgb> {
+    match Color::from_hex(hex) {
+        Ok(color) => Some(Rgb {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        }),
+        Err(e) => {
+            log::warn!("Invalid trigger color {:?}: {}", hex, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(regex: &str, action: TriggerAction, instant: bool) -> TriggerConfig {
+        TriggerConfig {
+            regex: regex.to_string(),
+            action,
+            instant,
+        }
+    }
+
+    #[test]
+    fn test_captures_and_instant_triggers() {
+        let configs = [
+            trigger(
+                r"Build (\w+)",
+                TriggerAction::Badge {
+                    text: "$1".to_string(),
+                },
+                false,
+            ),
+            trigger(
+                r"\(yes/no\)\? $",
+                TriggerAction::Send {
+                    text: "yes\r".to_string(),
+                },
+                true,
+            ),
+            trigger("(", TriggerAction::Bell, false),
+        ];
+        let mut triggers = Triggers::new(&configs);
+
+        assert_eq!(
+            triggers.line_completed("Build failed in 3s"),
+            vec![TriggerHit::Badge("failed".to_string())]
+        );
+
+        // 프롬프트는 줄이 끝나기 전에 한 번만 응답
+        let prompt = "Continue connecting (yes/no)? ";
+        assert_eq!(
+            triggers.partial_line(prompt),
+            vec![TriggerHit::Send("yes\r".to_string())]
+        );
+        assert!(triggers.partial_line(prompt).is_empty());
+        assert!(triggers.line_completed(prompt).is_empty());
+    }
+
+    fn write(grid: &mut Grid<Cell>, row: i32, text: &str) {
+        for (col, c) in text.chars().enumerate() {
+            grid[Line(row)][Column(col)].c = c;
+        }
+    }
+
+    #[test]
+    fn test_locate_and_highlight_wrapped_lines() {
+        let mut grid: Grid<Cell> = Grid::new(5, 10, 0);
+        write(&mut grid, 0, "ERROR x");
+        // 두 줄에 걸쳐 줄바꿈된 한 줄
+        write(&mut grid, 1, "build ERRO");
+        grid[Line(1)][Column(9)].flags.insert(Flags::WRAPLINE);
+        write(&mut grid, 2, "R done");
+        write(&mut grid, 3, "ERROR x");
+        let red = Rgb { r: 255, g: 0, b: 0 };
+
+        // 같은 내용이면 더 아래 (최근) 줄, 앞선 줄은 그보다 위에서 찾음
+        let rows = locate_lines(&grid, &["ERROR x", "build ERROR done", "ERROR x", "gone"]);
+        assert_eq!(rows, vec![Some(0), Some(1), Some(3), None]);
+
+        assert!(highlight(&mut grid, 1, "ERROR", Some(red), None));
+        assert_eq!(grid[Line(1)][Column(6)].fg, AnsiColor::Spec(red));
+        assert_eq!(grid[Line(1)][Column(9)].fg, AnsiColor::Spec(red));
+        assert_eq!(grid[Line(2)][Column(0)].fg, AnsiColor::Spec(red));
+        assert_ne!(grid[Line(2)][Column(1)].fg, AnsiColor::Spec(red));
+        assert_ne!(grid[Line(3)][Column(0)].fg, AnsiColor::Spec(red));
+        assert!(!highlight(&mut grid, 1, "WARN", Some(red), None));
+    }
+}
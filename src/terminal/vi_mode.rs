@@ -0,0 +1,279 @@
+use alacritty_terminal::{index::Direction, selection::SelectionType, vi_mode::ViMotion};
+
+/// What a key press in copy mode asks the session to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViCommand {
+    /// Key consumed without moving (pending `g`/`f`, typing a search, unknown key)
+    None,
+    Motion(ViMotion),
+    /// `gg`
+    Top,
+    /// `G`
+    Bottom,
+    /// Ctrl+U/D/B/F, in half pages; positive scrolls up into the history
+    Scroll(i32),
+    /// `f`/`F`/`t`/`T` and their `;`/`,` repeats
+    InlineSearch {
+        needle: char,
+        direction: Direction,
+        till: bool,
+    },
+    /// `/`, `?`, `n`, `N`
+    Search {
+        pattern: String,
+        direction: Direction,
+    },
+    ToggleSelection(SelectionType),
+    Yank,
+    /// Escape: clear the selection, or leave copy mode when nothing is selected
+    Cancel,
+    Exit,
+}
+
+/// Keys waiting for a second key
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pending {
+    /// `g` waiting for the second `g`
+    Goto,
+    /// `f`/`F`/`t`/`T` waiting for the character to find
+    Inline { direction: Direction, till: bool },
+}
+
+/// Key parser for vi-style copy mode
+#[derive(Debug, Default, Clone)]
+pub struct ViModeState {
+    pending: Option<Pending>,
+    /// Search being typed after `/` or `?`
+    search_input: Option<(Direction, String)>,
+    last_search: Option<(Direction, String)>,
+    last_inline: Option<(char, Direction, bool)>,
+}
+
+// Slint이 화살표/Home/End 키에 넣어 주는 사설 영역 문자
+const KEY_UP: &str = "\u{F700}";
+const KEY_DOWN: &str = "\u{F701}";
+const KEY_LEFT: &str = "\u{F702}";
+const KEY_RIGHT: &str = "\u{F703}";
+const KEY_HOME: &str = "\u{F729}";
+const KEY_END: &str = "\u{F72B}";
+const KEY_PAGE_UP: &str = "\u{F72C}";
+const KEY_PAGE_DOWN: &str = "\u{F72D}";
+
+impl ViModeState {
+    /// Search prompt shown in the status indicator while typing (`/foo`)
+    pub fn prompt(&self) -> Option<String> {
+        self.search_input.as_ref().map(|(direction, query)| {
+            let prefix = if *direction == Direction::Right {
+                '/'
+            } else {
+                '?'
+            };
+            format!("{}{}", prefix, query)
+        })
+    }
+
+    /// Parse one key press. `ctrl` is the platform's Control key (not Cmd).
+    pub fn handle_key(&mut self, text: &str, ctrl: bool) -> ViCommand {
+        let is_escape = text.is_empty() || text == "\u{1b}";
+
+        if let Some((direction, query)) = self.search_input.as_mut() {
+            match text {
+                _ if is_escape => self.search_input = None,
+                "\r" | "\n" => {
+                    let (direction, query) = (*direction, std::mem::take(query));
+                    self.search_input = None;
+                    if query.is_empty() {
+                        return ViCommand::None;
+                    }
+                    self.last_search = Some((direction, query.clone()));
+                    return ViCommand::Search {
+                        pattern: query,
+                        direction,
+                    };
+                }
+                "\u{08}" | "\u{7f}" if query.pop().is_none() => self.search_input = None,
+                "\u{08}" | "\u{7f}" => {}
+                _ if !ctrl && !text.chars().any(char::is_control) => query.push_str(text),
+                _ => {}
+            }
+            return ViCommand::None;
+        }
+
+        if let Some(pending) = self.pending.take() {
+            let mut chars = text.chars();
+            return match (pending, chars.next(), chars.next()) {
+                (Pending::Goto, Some('g'), None) => ViCommand::Top,
+                (Pending::Inline { direction, till }, Some(needle), None)
+                    if !is_escape && !needle.is_control() =>
+                {
+                    self.last_inline = Some((needle, direction, till));
+                    ViCommand::InlineSearch {
+                        needle,
+                        direction,
+                        till,
+                    }
+                }
+                _ => ViCommand::None,
+            };
+        }
+
+        if ctrl {
+            return match text {
+                "u" | "\u{15}" => ViCommand::Scroll(1),
+                "d" | "\u{04}" => ViCommand::Scroll(-1),
+                "b" | "\u{02}" => ViCommand::Scroll(2),
+                "f" | "\u{06}" => ViCommand::Scroll(-2),
+                "v" | "\u{16}" => ViCommand::ToggleSelection(SelectionType::Block),
+                "c" | "\u{03}" => ViCommand::Exit,
+                _ => ViCommand::None,
+            };
+        }
+
+        match text {
+            _ if is_escape => ViCommand::Cancel,
+            "q" | "i" => ViCommand::Exit,
+            "h" | KEY_LEFT => ViCommand::Motion(ViMotion::Left),
+            "j" | KEY_DOWN => ViCommand::Motion(ViMotion::Down),
+            "k" | KEY_UP => ViCommand::Motion(ViMotion::Up),
+            "l" | KEY_RIGHT => ViCommand::Motion(ViMotion::Right),
+            "w" => ViCommand::Motion(ViMotion::SemanticRight),
+            "b" => ViCommand::Motion(ViMotion::SemanticLeft),
+            "e" => ViCommand::Motion(ViMotion::SemanticRightEnd),
+            "W" => ViCommand::Motion(ViMotion::WordRight),
+            "B" => ViCommand::Motion(ViMotion::WordLeft),
+            "E" => ViCommand::Motion(ViMotion::WordRightEnd),
+            "0" | KEY_HOME => ViCommand::Motion(ViMotion::First),
+            "$" | KEY_END => ViCommand::Motion(ViMotion::Last),
+            "^" => ViCommand::Motion(ViMotion::FirstOccupied),
+            "H" => ViCommand::Motion(ViMotion::High),
+            "M" => ViCommand::Motion(ViMotion::Middle),
+            "L" => ViCommand::Motion(ViMotion::Low),
+            "%" => ViCommand::Motion(ViMotion::Bracket),
+            "{" => ViCommand::Motion(ViMotion::ParagraphUp),
+            "}" => ViCommand::Motion(ViMotion::ParagraphDown),
+            "G" => ViCommand::Bottom,
+            KEY_PAGE_UP => ViCommand::Scroll(2),
+            KEY_PAGE_DOWN => ViCommand::Scroll(-2),
+            "g" => {
+                self.pending = Some(Pending::Goto);
+                ViCommand::None
+            }
+            "f" | "F" | "t" | "T" => {
+                let direction = if text == "f" || text == "t" {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                };
+                let till = text == "t" || text == "T";
+                self.pending = Some(Pending::Inline { direction, till });
+                ViCommand::None
+            }
+            ";" | "," => match self.last_inline {
+                Some((needle, direction, till)) => ViCommand::InlineSearch {
+                    needle,
+                    direction: if text == ";" {
+                        direction
+                    } else {
+                        direction.opposite()
+                    },
+                    till,
+                },
+                None => ViCommand::None,
+            },
+            "/" | "?" => {
+                let direction = if text == "/" {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                };
+                self.search_input = Some((direction, String::new()));
+                ViCommand::None
+            }
+            "n" | "N" => match &self.last_search {
+                Some((direction, pattern)) => ViCommand::Search {
+                    pattern: pattern.clone(),
+                    direction: if text == "n" {
+                        *direction
+                    } else {
+                        direction.opposite()
+                    },
+                },
+                None => ViCommand::None,
+            },
+            "v" => ViCommand::ToggleSelection(SelectionType::Simple),
+            "V" => ViCommand::ToggleSelection(SelectionType::Lines),
+            "y" => ViCommand::Yank,
+            _ => ViCommand::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_keys() {
+        let mut state = ViModeState::default();
+        assert_eq!(state.handle_key("g", false), ViCommand::None);
+        assert_eq!(state.handle_key("g", false), ViCommand::Top);
+
+        assert_eq!(state.handle_key("t", false), ViCommand::None);
+        let till_x = ViCommand::InlineSearch {
+            needle: 'x',
+            direction: Direction::Right,
+            till: true,
+        };
+        assert_eq!(state.handle_key("x", false), till_x);
+        assert_eq!(state.handle_key(";", false), till_x);
+        assert_eq!(
+            state.handle_key(",", false),
+            ViCommand::InlineSearch {
+                needle: 'x',
+                direction: Direction::Left,
+                till: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_search_input() {
+        let mut state = ViModeState::default();
+        state.handle_key("?", false);
+        state.handle_key("e", false);
+        state.handle_key("r", false);
+        assert_eq!(state.prompt().as_deref(), Some("?er"));
+
+        let search = state.handle_key("\r", false);
+        assert_eq!(
+            search,
+            ViCommand::Search {
+                pattern: "er".to_string(),
+                direction: Direction::Left
+            }
+        );
+        assert!(state.prompt().is_none());
+        assert_eq!(
+            state.handle_key("N", false),
+            ViCommand::Search {
+                pattern: "er".to_string(),
+                direction: Direction::Right
+            }
+        );
+    }
+
+    #[test]
+    fn test_selection_and_exit_keys() {
+        let mut state = ViModeState::default();
+        assert_eq!(
+            state.handle_key("V", false),
+            ViCommand::ToggleSelection(SelectionType::Lines)
+        );
+        assert_eq!(
+            state.handle_key("v", true),
+            ViCommand::ToggleSelection(SelectionType::Block)
+        );
+        assert_eq!(state.handle_key("\u{1b}", false), ViCommand::Cancel);
+        assert_eq!(state.handle_key("q", false), ViCommand::Exit);
+    }
+}
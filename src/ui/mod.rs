@@ -1,39 +1,72 @@
 use anyhow::Result;
-use slint::{ComponentHandle, Model, ModelRc, VecModel, Weak};
-use std::sync::mpsc;
+use slint::{
+    CloseRequestResponse, ComponentHandle, Model, ModelRc, Rgba8Pixel, SharedPixelBuffer, VecModel,
+    Weak,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Arc;
 // Duration import removed - no longer using timers
 use tokio::sync::Mutex;
 
-use crate::terminal::{SessionId, TerminalManager};
+use alacritty_terminal::vte::ansi::CursorShape;
+
+use crate::config::{ClipboardAccess, ClipboardConfig};
+use crate::config::{Config, ScreenshotFormat};
+use crate::ipc::server::IpcRequest;
+use crate::ipc::{Request, Response, SessionInfo};
+use crate::terminal::graphics::ImageSlice;
+use crate::terminal::hints::{HintInput, HintState};
+use crate::terminal::persistence::{SavedSessions, WindowState};
+use crate::terminal::triggers::TriggerHit;
+use crate::terminal::{
+    ColoredTerminalContent, SessionExitAction, SessionId, SessionOptions, TerminalManager,
+    ZoomAction,
+};
+use crate::utils::color::ColorTheme;
 use crate::utils::font::FontMetrics;
+use crate::utils::keybinding::KeyBinding;
 use crate::utils::korean_ime::KoreanIME;
-use crate::{ColorSegment, CursorInfo, MainWindow, TerminalKeyEvent};
+use crate::{
+    ColorSegment, CursorInfo, HintLabel, ImageStrip, LinkHover, MainWindow, TerminalKeyEvent,
+};
 
-/// 터미널로 전달하기에 안전한 키 입력인지 확인하고 필요시 변환  
-fn process_and_filter_terminal_input(event: &TerminalKeyEvent, korean_ime: &Arc<Mutex<KoreanIME>>, session_id: SessionId) -> Option<(String, Option<char>)> {
+thread_local! {
+    /// 이미지 id별로 만든 Slint 이미지 (UI 스레드에서만 사용)
+    static IMAGE_CACHE: RefCell<HashMap<u64, slint::Image>> = RefCell::new(HashMap::new());
+}
+
+/// 터미널로 전달하기에 안전한 키 입력인지 확인하고 필요시 변환
+fn process_and_filter_terminal_input(
+    event: &TerminalKeyEvent,
+    korean_ime: &Arc<Mutex<KoreanIME>>,
+    session_id: SessionId,
+) -> Option<(String, Option<char>)> {
     let input = &event.text.to_string();
     log::debug!("process_and_filter_terminal_input called with: {:?}", input);
-    
+
     if input.is_empty() {
         log::debug!("Filtered: empty input");
         return None;
     }
-    
+
     // 완전히 공백으로만 구성된 문자열 필터링
-    
-    
+
     // 먼저 필터링할 문자들을 체크 (단일 문자인지 확인)
     if input.chars().count() == 1 {
         let ch = input.chars().next().unwrap();
         match ch {
             // 나머지 제어 문자들은 필터링
-            '\u{00}'..='\u{1f}' | '\u{7f}' => {
-                // 허용할 제어 문자들 제외 (ESC 포함)
-                if !matches!(ch, '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' | '\u{1b}') {
-                    log::debug!("Filtered control character: {:?} (\\u{{{:04x}}})", ch, ch as u32);
-                    return None;
-                }
+            // 허용할 제어 문자들 제외 (ESC 포함)
+            '\u{00}'..='\u{1f}' | '\u{7f}'
+                if !matches!(ch, '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' | '\u{1b}') =>
+            {
+                log::debug!(
+                    "Filtered control character: {:?} (\\u{{{:04x}}})",
+                    ch,
+                    ch as u32
+                );
+                return None;
             }
             // macOS 특수 키 처리 (방향키 등) - IME에서 처리하도록 이동
             '\u{f700}' | '\u{f701}' | '\u{f702}' | '\u{f703}' => {
@@ -42,39 +75,49 @@ fn process_and_filter_terminal_input(event: &TerminalKeyEvent, korean_ime: &Arc<
             }
             // 기타 macOS 특수 키 범위는 필터링
             '\u{f704}'..='\u{f8ff}' => {
-                log::debug!("Filtered macOS special key: {:?} (\\u{{{:04x}}})", ch, ch as u32);
+                log::debug!(
+                    "Filtered macOS special key: {:?} (\\u{{{:04x}}})",
+                    ch,
+                    ch as u32
+                );
                 return None;
             }
             _ => {}
         }
     } else {
         // 멀티바이트 문자열의 경우
-        
+
         // macOS 특수 키들이 포함된 경우 필터링
-     /*   if input.chars().any(|c| matches!(c, '\u{f700}'..='\u{f8ff}')) {
+        /*   if input.chars().any(|c| matches!(c, '\u{f700}'..='\u{f8ff}')) {
             log::debug!("Filtered macOS special key sequence: {:?}", input);
             return None;
         }*/
-        
+
         // escape sequence 필터링 (단순 ESC는 제외)
         if input.starts_with('\u{1b}') && input.chars().count() > 1 {
             log::debug!("Filtered escape sequence: {:?}", input);
             return None;
         }
-        
+
         // 대부분 제어 문자로만 구성된 경우 필터링
-        if input.chars().all(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' | '\u{1b}')) {
+        if input.chars().all(|c| {
+            c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' | '\u{1b}')
+        }) {
             log::debug!("Filtered control sequence: {:?}", input);
             return None;
         }
     }
-    
+
     // 모든 입력에 대해 한국어 IME 처리
     eprintln!("💫 ENTERING IME PROCESSING with: {:?}", input);
     if let Ok(mut ime) = korean_ime.try_lock() {
         eprintln!("💫 IME LOCK SUCCESS - calling process_input");
-        let (completed_text, _is_composing, current_composition) = ime.process_input(session_id, input);
-        eprintln!("💫 IME RESULT: completed_text={:?}, composition={:?}", completed_text, current_composition);
+        let (completed_text, _is_composing, current_composition) =
+            ime.process_input(session_id, input);
+        eprintln!(
+            "💫 IME RESULT: completed_text={:?}, composition={:?}",
+            completed_text, current_composition
+        );
         if !completed_text.is_empty() {
             eprintln!("💫 RETURNING NON-EMPTY: {:?}", completed_text);
             Some((completed_text, current_composition))
@@ -88,28 +131,64 @@ fn process_and_filter_terminal_input(event: &TerminalKeyEvent, korean_ime: &Arc<
     }
 }
 
-
-
-// UI 업데이트 메시지 타입
-#[derive(Debug, Clone)]
-pub enum UIUpdateMessage {
-    ColoredTerminalContent {
+/// 사용자 확인을 기다리는 OSC 52 클립보드 요청
+enum ClipboardRequest {
+    Store {
         session_id: SessionId,
-        segments: Vec<crate::terminal::ColoredTextSegment>,
+        text: String,
     },
-    SessionClosed {
+    Load {
         session_id: SessionId,
+        format: Arc<dyn Fn(&str) -> String + Send + Sync>,
     },
 }
 
+impl ClipboardRequest {
+    /// 세션 정책 중 이 요청에 해당하는 쪽 (쓰기 또는 읽기)
+    fn access(&self, policy: &ClipboardConfig) -> ClipboardAccess {
+        match self {
+            ClipboardRequest::Store { .. } => policy.osc52_write,
+            ClipboardRequest::Load { .. } => policy.osc52_read,
+        }
+    }
+
+    fn session_id(&self) -> SessionId {
+        match self {
+            ClipboardRequest::Store { session_id, .. }
+            | ClipboardRequest::Load { session_id, .. } => *session_id,
+        }
+    }
+
+    /// 확인 창에 표시할 문구
+    fn prompt(&self) -> String {
+        match self {
+            ClipboardRequest::Store { session_id, text } => format!(
+                "Terminal {} wants to copy {} characters to the clipboard",
+                session_id + 1,
+                text.chars().count()
+            ),
+            ClipboardRequest::Load { session_id, .. } => {
+                format!("Terminal {} wants to read the clipboard", session_id + 1)
+            }
+        }
+    }
+}
+
+/// 실행 중인 프로그램 때문에 확인을 기다리는 닫기 요청
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingClose {
+    Tab(SessionId),
+    Window,
+}
+
 pub struct UIManager {
     window: Weak<MainWindow>,
     terminal_manager: Arc<Mutex<TerminalManager>>,
-    ui_update_sender: mpsc::Sender<UIUpdateMessage>,
-    ui_update_receiver: Option<mpsc::Receiver<UIUpdateMessage>>,
     korean_ime: Arc<Mutex<KoreanIME>>,
     last_control_key_time: Arc<Mutex<std::time::Instant>>,
-
+    hint_state: Arc<Mutex<Option<HintState>>>,
+    pending_clipboard: Arc<Mutex<Option<ClipboardRequest>>>,
+    pending_close: Arc<Mutex<Option<PendingClose>>>,
 }
 
 impl UIManager {
@@ -117,46 +196,748 @@ impl UIManager {
         window: Weak<MainWindow>,
         terminal_manager: Arc<Mutex<TerminalManager>>,
     ) -> Result<Self> {
-        let (ui_update_sender, ui_update_receiver) = mpsc::channel();
         Ok(Self {
             window,
             terminal_manager,
-            ui_update_sender,
-            ui_update_receiver: Some(ui_update_receiver),
             korean_ime: Arc::new(Mutex::new(KoreanIME::new())),
             last_control_key_time: Arc::new(Mutex::new(std::time::Instant::now())),
-
+            hint_state: Arc::new(Mutex::new(None)),
+            pending_clipboard: Arc::new(Mutex::new(None)),
+            pending_close: Arc::new(Mutex::new(None)),
         })
     }
 
     /// 윈도우 크기를 기반으로 터미널 크기를 계산하고 검증
-    fn calculate_terminal_size(window_width: i32, window_height: i32, font_size: i32) -> (u16, u16) {
+    fn calculate_terminal_size(
+        window_width: i32,
+        window_height: i32,
+        font_size: i32,
+    ) -> (u16, u16) {
         let font_metrics = FontMetrics::for_d2coding(font_size);
-        
+
         // TabBar 높이를 고려한 실제 터미널 영역 계산
         let tab_bar_height = 32; // TabBar 높이 (main_window.slint에서 정의)
         let available_height = window_height - tab_bar_height;
-        
+
         // 패딩을 제외한 실제 사용 가능한 영역 계산
         let usable_width = window_width - (font_metrics.padding_x * 2);
         let usable_height = available_height - (font_metrics.padding_y * 2);
-        
+
         // 터미널 크기 계산 및 제한 적용
-        let cols = std::cmp::max(10, std::cmp::min(300, (usable_width / font_metrics.char_width) as u16));
-        let rows = std::cmp::max(3, std::cmp::min(100, (usable_height / font_metrics.line_height) as u16));
-        
+        let cols = ((usable_width / font_metrics.char_width) as u16).clamp(10, 300);
+        let rows = ((usable_height / font_metrics.line_height) as u16).clamp(3, 100);
+
         (cols, rows)
     }
 
-    /// 색상 세그먼트들을 렌더링 가능한 텍스트로 변환
-    fn render_colored_segments(segments: &[crate::terminal::ColoredTextSegment]) -> String {
-        // TODO: 실제 색상 렌더링 구현
-        // 현재는 텍스트만 연결하여 반환 (색상 정보는 로그에 기록됨)
-        let mut result = String::new();
-        for segment in segments {
-            result.push_str(&segment.text);
+    /// calculate_terminal_size의 역: cols × rows 셀이 들어가는 창 크기
+    fn window_size_for(cols: u16, rows: u16, font_size: i32) -> (i32, i32) {
+        let font_metrics = FontMetrics::for_d2coding(font_size);
+        let tab_bar_height = 32; // TabBar 높이 (main_window.slint에서 정의)
+
+        let width = cols as i32 * font_metrics.char_width + font_metrics.padding_x * 2;
+        let height =
+            rows as i32 * font_metrics.line_height + font_metrics.padding_y * 2 + tab_bar_height;
+        (width, height)
+    }
+
+    /// 현재 탭의 폰트 크기에 맞는 메트릭 (terminal_state.font_size는 활성 탭 기준)
+    fn active_font_metrics(window: &MainWindow) -> FontMetrics {
+        FontMetrics::for_d2coding(window.get_terminal_state().font_size)
+    }
+
+    /// 폰트 확대/축소 후 바뀐 셀 크기에 맞춰 세션들을 리사이즈하고 다시 그림
+    fn apply_zoom(
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        window: &MainWindow,
+        action: ZoomAction,
+    ) {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            log::warn!("Could not acquire terminal manager lock for zoom");
+            return;
+        };
+        let Some(active_id) = tm.get_active_session().map(|session| session.id) else {
+            return;
+        };
+
+        let scale_factor = window.window().scale_factor();
+        let size = window.window().size().to_logical(scale_factor);
+        for session_id in tm.zoom(active_id, action) {
+            let font_size = tm.font_size_for(session_id);
+            let (cols, rows) =
+                Self::calculate_terminal_size(size.width as i32, size.height as i32, font_size);
+            if let Err(e) = tm.resize_session(session_id, cols, rows) {
+                log::error!("Failed to resize session {} after zoom: {}", session_id, e);
+            }
+        }
+
+        let mut terminal_state = window.get_terminal_state();
+        terminal_state.font_size = tm.font_size_for(active_id);
+        window.set_terminal_state(terminal_state);
+        tm.request_redraw(active_id);
+    }
+
+    /// 픽셀 좌표를 뷰포트 셀 (row, col)로 변환 (패딩 밖이면 None)
+    fn pixel_to_cell(x: i32, y: i32, font_metrics: &FontMetrics) -> Option<(usize, usize)> {
+        let x = x - font_metrics.padding_x;
+        let y = y - font_metrics.padding_y;
+        if x < 0 || y < 0 {
+            return None;
+        }
+        Some((
+            (y / font_metrics.line_height) as usize,
+            (x / font_metrics.char_width) as usize,
+        ))
+    }
+
+    /// 화면에 보이는 이미지 줄을 Slint 모델로 (UI 스레드에서 호출).
+    /// 이번 화면에 없는 이미지는 캐시에서 뺌
+    fn image_strips(slices: &[ImageSlice]) -> ModelRc<ImageStrip> {
+        IMAGE_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.retain(|id, _| slices.iter().any(|slice| slice.image.id == *id));
+            let strips: Vec<ImageStrip> = slices
+                .iter()
+                .map(|slice| {
+                    let image = cache
+                        .entry(slice.image.id)
+                        .or_insert_with(|| {
+                            slint::Image::from_rgba8(
+                                SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+                                    &slice.image.rgba,
+                                    slice.image.width,
+                                    slice.image.height,
+                                ),
+                            )
+                        })
+                        .clone();
+                    ImageStrip {
+                        image,
+                        x: slice.x,
+                        y: slice.y,
+                        width: slice.width,
+                        height: slice.height,
+                        source_x: slice.source_x as i32,
+                        source_y: slice.source_y as i32,
+                        source_width: slice.source_width as i32,
+                        source_height: slice.source_height as i32,
+                        below_text: slice.z_index < 0,
+                    }
+                })
+                .collect();
+            ModelRc::new(VecModel::from(strips))
+        })
+    }
+
+    /// 커서 모양에 따라 커서 사각형 위치/크기를 계산
+    fn cursor_info_for(content: &ColoredTerminalContent, font_metrics: &FontMetrics) -> CursorInfo {
+        let x = font_metrics.padding_x + (content.cursor_col as i32) * font_metrics.char_width;
+        let y = font_metrics.padding_y + (content.cursor_line as i32) * font_metrics.line_height;
+        // Beam/Underline 두께는 셀 크기에 비례 (최소 1px)
+        let thickness = std::cmp::max(1, font_metrics.line_height / 8);
+        // 와이드 문자 위에서는 두 칸 폭
+        let cell_width = if content.cursor_wide {
+            font_metrics.char_width * 2
+        } else {
+            font_metrics.char_width
+        };
+
+        let (x, y, width, height) = match content.cursor_shape {
+            CursorShape::Beam => (x, y, thickness, font_metrics.line_height),
+            CursorShape::Underline => (
+                x,
+                y + font_metrics.line_height - thickness,
+                cell_width,
+                thickness,
+            ),
+            _ => (x, y, cell_width, font_metrics.line_height),
+        };
+
+        CursorInfo {
+            x,
+            y,
+            width,
+            height,
+            visible: content.cursor_shape != CursorShape::Hidden,
+            hollow: content.cursor_shape == CursorShape::HollowBlock,
+            blinking: content.cursor_blinking && !content.vi_mode,
+            color: content.cursor_color.to_slint_color(),
+            vi_mode: content.vi_mode,
+        }
+    }
+
+    /// 힌트 모드 키 처리. 힌트 단축키이거나 힌트 표시 중이면 키를 소비하고 true 반환
+    fn handle_hint_key(
+        event: &TerminalKeyEvent,
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        hint_state: &Arc<Mutex<Option<HintState>>>,
+        window: &MainWindow,
+    ) -> bool {
+        let Ok(tm) = terminal_manager.try_lock() else {
+            return false;
+        };
+        let Some(session_id) = tm.get_active_session().map(|session| session.id) else {
+            return false;
+        };
+        let Ok(mut state) = hint_state.try_lock() else {
+            return false;
+        };
+
+        if let Some(hints) = state.as_mut() {
+            let text = event.text.as_str();
+            if text.is_empty() || text == "\u{1b}" {
+                // ESC: 힌트 모드 취소
+                *state = None;
+            } else if text == "\u{08}" || text == "\u{7f}" {
+                hints.pop();
+            } else if let (Some(ch), None) = (text.chars().next(), text.chars().nth(1)) {
+                if let HintInput::Selected(hint) = hints.push(ch) {
+                    let action = hints.action;
+                    *state = None;
+                    tm.run_hint_action(session_id, action, &hint);
+                }
+            }
+            Self::update_hint_labels(window, state.as_ref());
+            return true;
+        }
+
+        let modifiers = &event.modifiers;
+        let Some(hint) = tm.hint_for_key(
+            &event.text,
+            modifiers.control,
+            modifiers.alt,
+            modifiers.meta,
+            modifiers.shift,
+        ) else {
+            return false;
+        };
+
+        *state = tm.start_hints(session_id, hint);
+        if state.is_none() {
+            log::info!("No matches on screen for hint {:?}", hint.name);
+        }
+        Self::update_hint_labels(window, state.as_ref());
+        true
+    }
+
+    /// 복사 모드 키 처리. 토글 단축키이거나 복사 모드 중이면 키를 소비하고 true 반환
+    fn handle_vi_key(
+        event: &TerminalKeyEvent,
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        window: &MainWindow,
+    ) -> bool {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            return false;
+        };
+        let Some(session_id) = tm.get_active_session().map(|session| session.id) else {
+            return false;
+        };
+
+        let modifiers = &event.modifiers;
+        if tm.is_vi_mode_key(
+            &event.text,
+            modifiers.control,
+            modifiers.alt,
+            modifiers.meta,
+            modifiers.shift,
+        ) {
+            tm.toggle_vi_mode(session_id);
+        } else if tm.is_vi_mode(session_id) {
+            // macOS에서 Slint는 Ctrl을 meta로 보고함
+            let ctrl = if cfg!(target_os = "macos") {
+                modifiers.meta
+            } else {
+                modifiers.control
+            };
+            tm.vi_key(session_id, &event.text, ctrl);
+        } else {
+            return false;
+        }
+
+        window.set_vi_mode_status(tm.vi_status(session_id).unwrap_or_default().into());
+        true
+    }
+
+    /// 셸이 종료된 채 열려 있는 세션의 키 처리. Enter로 재시작하고 나머지 키는 소비
+    fn handle_held_session_key(
+        event: &TerminalKeyEvent,
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+    ) -> bool {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            return false;
+        };
+        let Some(session_id) = tm.get_active_session().map(|session| session.id) else {
+            return false;
+        };
+        if !tm.is_session_held(session_id) {
+            return false;
+        }
+
+        if event.text == "\n" || event.text == "\r" {
+            match tm.restart_session(session_id) {
+                Ok(stopped) => {
+                    drop(tm);
+                    if let Some(stopped) = stopped {
+                        tokio::spawn(stopped.join());
+                    }
+                }
+                Err(e) => log::error!("Failed to restart session {}: {}", session_id, e),
+            }
+        }
+        true
+    }
+
+    /// 닫기 확인 문구를 표시하고 확인 후 실행할 요청을 기억
+    fn ask_close(
+        window_weak: &Weak<MainWindow>,
+        pending_close: &Arc<Mutex<Option<PendingClose>>>,
+        target: PendingClose,
+        prompt: String,
+    ) {
+        let Ok(mut pending) = pending_close.try_lock() else {
+            return;
+        };
+        *pending = Some(target);
+        if let Some(window) = window_weak.upgrade() {
+            window.set_close_prompt(prompt.into());
+        }
+    }
+
+    /// 세션을 종료하고 탭을 제거
+    fn close_tab(
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        window_weak: &Weak<MainWindow>,
+        session_id: SessionId,
+    ) {
+        let terminal_manager = terminal_manager.clone();
+        let window_weak = window_weak.clone();
+
+        slint::invoke_from_event_loop(move || {
+            tokio::spawn(async move {
+                let mut tm = terminal_manager.lock().await;
+                let stopped = match tm.close_session(session_id).await {
+                    Ok(stopped) => stopped,
+                    Err(e) => {
+                        log::error!("Failed to close session: {}", e);
+                        return;
+                    }
+                };
+                let broadcast_members = tm.broadcast_members();
+                drop(tm);
+
+                // UI 업데이트
+                slint::invoke_from_event_loop(move || {
+                    if let Some(window) = window_weak.upgrade() {
+                        Self::remove_tab_from_ui(&window, session_id);
+                        Self::show_broadcast(&window, &broadcast_members);
+                    }
+                })
+                .unwrap_or_else(|e| log::error!("Failed to invoke UI update: {:?}", e));
+
+                // 잠금을 놓은 뒤 PTY 스레드가 남은 출력을 처리하고 끝나기를 기다림
+                if let Some(stopped) = stopped {
+                    stopped.join().await;
+                }
+            });
+        })
+        .unwrap_or_else(|e| log::error!("Failed to invoke from event loop: {:?}", e));
+    }
+
+    /// 세션 정책에 따라 OSC 52 요청을 실행, 거부하거나 사용자 확인을 요청
+    fn handle_clipboard_request(
+        tm: &TerminalManager,
+        request: ClipboardRequest,
+        pending_clipboard: &Arc<Mutex<Option<ClipboardRequest>>>,
+        window_weak: &Weak<MainWindow>,
+    ) {
+        match request.access(tm.clipboard_policy(request.session_id())) {
+            ClipboardAccess::Allow => Self::run_clipboard_request(tm, &request, window_weak),
+            ClipboardAccess::Deny => log::info!("Denied OSC 52 request: {}", request.prompt()),
+            ClipboardAccess::Ask => {
+                let mut pending = pending_clipboard.blocking_lock();
+                if pending.is_some() {
+                    log::warn!(
+                        "Denied OSC 52 request while another is awaiting confirmation: {}",
+                        request.prompt()
+                    );
+                    return;
+                }
+                let prompt = request.prompt();
+                *pending = Some(request);
+
+                let window_weak = window_weak.clone();
+                slint::invoke_from_event_loop(move || {
+                    if let Some(window) = window_weak.upgrade() {
+                        window.set_clipboard_prompt(prompt.into());
+                    }
+                })
+                .unwrap_or_else(|e| log::error!("Failed to invoke clipboard prompt: {:?}", e));
+            }
+        }
+    }
+
+    /// 허용된 OSC 52 요청 실행. 클립보드를 읽으면 화면에 알림 표시
+    fn run_clipboard_request(
+        tm: &TerminalManager,
+        request: &ClipboardRequest,
+        window_weak: &Weak<MainWindow>,
+    ) {
+        match request {
+            ClipboardRequest::Store { session_id, text } => {
+                tm.store_clipboard(*session_id, text);
+            }
+            ClipboardRequest::Load { session_id, format } => {
+                if tm.load_clipboard(*session_id, format.as_ref()) {
+                    let notice = format!("Clipboard read by Terminal {}", session_id + 1);
+                    let window_weak = window_weak.clone();
+                    slint::invoke_from_event_loop(move || {
+                        if let Some(window) = window_weak.upgrade() {
+                            window.set_clipboard_notice(notice.into());
+                        }
+                    })
+                    .unwrap_or_else(|e| log::error!("Failed to invoke clipboard notice: {:?}", e));
+                }
+            }
+        }
+    }
+
+    /// 남은 힌트 라벨을 UI에 반영
+    fn update_hint_labels(window: &MainWindow, state: Option<&HintState>) {
+        let font_metrics = Self::active_font_metrics(window);
+        let labels: Vec<HintLabel> = state
+            .into_iter()
+            .flat_map(|hints| {
+                hints.visible().map(|hint| HintLabel {
+                    x: font_metrics.padding_x + (hint.start_col as i32) * font_metrics.char_width,
+                    y: font_metrics.padding_y + (hint.row as i32) * font_metrics.line_height,
+                    width: ((hint.end_col - hint.start_col) as i32) * font_metrics.char_width,
+                    height: font_metrics.line_height,
+                    label: hint
+                        .label
+                        .chars()
+                        .skip(hints.typed.chars().count())
+                        .collect::<String>()
+                        .into(),
+                })
+            })
+            .collect();
+        window.set_hint_labels(ModelRc::new(VecModel::from(labels)));
+    }
+
+    /// 창을 만들기 전에 백엔드 선택. 창 클래스는 winit 창 속성으로만 지정할 수 있음
+    pub fn select_backend(config: &Config) -> Result<()> {
+        let Some(class) = config.ui.window_class.clone() else {
+            return Ok(());
+        };
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            use slint::winit_030::winit::platform::{
+                wayland::WindowAttributesExtWayland, x11::WindowAttributesExtX11,
+            };
+
+            slint::BackendSelector::new()
+                .backend_name("winit".into())
+                .with_winit_window_attributes_hook(move |attributes| {
+                    let attributes = WindowAttributesExtX11::with_name(attributes, &class, &class);
+                    WindowAttributesExtWayland::with_name(attributes, &class, &class)
+                })
+                .select()?;
         }
-        result
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        log::info!("Window class {:?} is only used on X11/Wayland", class);
+
+        Ok(())
+    }
+
+    /// 저장된 탭을 다시 열기. 복원된 탭이 없으면 false
+    pub async fn restore_sessions(&self, saved: &SavedSessions) -> Result<bool> {
+        let window = self
+            .window
+            .upgrade()
+            .ok_or_else(|| anyhow::anyhow!("Failed to upgrade window weak reference"))?;
+
+        let mut tm = self.terminal_manager.lock().await;
+        let restored = tm.restore_state(saved);
+        let Some((active_id, _)) = restored.get(saved.active_tab).or(restored.first()) else {
+            return Ok(false);
+        };
+        Self::show_tabs(&window, &restored, *active_id);
+        Self::show_logging(&window, &tm.logging_sessions());
+
+        log::info!("Restored {} tab(s) from the last session", restored.len());
+        Ok(true)
+    }
+
+    /// 탭 바를 주어진 탭들로 교체
+    pub fn show_tabs(window: &MainWindow, tabs: &[(SessionId, String)], active_id: SessionId) {
+        let tabs: Vec<crate::TabInfo> = tabs
+            .iter()
+            .map(|(id, title)| crate::TabInfo {
+                title: title.into(),
+                active: *id == active_id,
+                id: *id as i32,
+                broadcast: false,
+                badge: "".into(),
+                logging: false,
+            })
+            .collect();
+        window.set_tabs(ModelRc::new(VecModel::from(tabs)));
+        window.set_active_tab(active_id as i32);
+    }
+
+    /// `sterm msg` 요청을 받아 처리
+    pub fn start_ipc_processing(&self, mut requests: tokio::sync::mpsc::Receiver<IpcRequest>) {
+        let terminal_manager = self.terminal_manager.clone();
+        let window_weak = self.window.clone();
+
+        tokio::spawn(async move {
+            while let Some(IpcRequest { request, reply }) = requests.recv().await {
+                let response = Self::handle_ipc_request(&terminal_manager, &window_weak, request)
+                    .await
+                    .unwrap_or_else(|e| Response::error(e.to_string()));
+                let _ = reply.send(response);
+            }
+        });
+    }
+
+    async fn handle_ipc_request(
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        window_weak: &Weak<MainWindow>,
+        request: Request,
+    ) -> Result<Response> {
+        let mut tm = terminal_manager.lock().await;
+
+        let response = match request {
+            Request::List => {
+                let mut ids = tm.get_all_sessions();
+                ids.sort_unstable();
+                let active = tm.active_session_id();
+                let sessions = ids
+                    .into_iter()
+                    .filter_map(|id| {
+                        let session = tm.get_session(id)?;
+                        Some(SessionInfo {
+                            id,
+                            title: session.title.clone(),
+                            active: active == Some(id),
+                            cols: session.size.num_cols,
+                            rows: session.size.num_lines,
+                            working_directory: session.working_directory(),
+                            running: session.foreground_process(),
+                        })
+                    })
+                    .collect();
+                Response::Sessions { sessions }
+            }
+            Request::Create {
+                command,
+                working_directory,
+                title,
+                env,
+                hold,
+                profile,
+                raise,
+            } => {
+                let id = tm.create_session(SessionOptions {
+                    command,
+                    working_directory,
+                    title,
+                    env,
+                    hold,
+                    profile,
+                    ..SessionOptions::default()
+                })?;
+                let title = tm
+                    .get_session(id)
+                    .map(|s| s.title.clone())
+                    .unwrap_or_default();
+                // 탭 클릭과 같은 경로로 활성화해서 폰트 크기와 창 크기를 맞춤
+                Self::run_in_ui(window_weak, move |window| {
+                    Self::add_tab_to_ui(window, id, &title);
+                    window.invoke_tab_clicked(id as i32);
+                    if raise {
+                        Self::raise_window(window);
+                    }
+                });
+                Response::Created { id }
+            }
+            Request::Close { id } => {
+                let id = tm.resolve_session(id)?;
+                // PTY 스레드는 이 요청이 잠금을 놓은 뒤에 정리
+                if let Some(stopped) = tm.close_session(id).await? {
+                    tokio::spawn(stopped.join());
+                }
+                let next = tm.active_session_id();
+                Self::run_in_ui(window_weak, move |window| {
+                    Self::remove_tab_from_ui(window, id);
+                    if let Some(next) = next {
+                        window.invoke_tab_clicked(next as i32);
+                    }
+                });
+                Response::Ok
+            }
+            Request::Write { id, text } => {
+                let id = tm.resolve_session(id)?;
+                tm.write_to_session(id, &text)?;
+                Response::Ok
+            }
+            Request::Resize { id, cols, rows } => {
+                let id = tm.resolve_session(id)?;
+                tm.resize_session(id, cols, rows)?;
+                tm.request_redraw(id);
+                Response::Ok
+            }
+            Request::GetText { id, scrollback } => {
+                let id = tm.resolve_session(id)?;
+                let text = tm.session_text(id, scrollback).unwrap_or_default();
+                Response::Text { text }
+            }
+            Request::Focus { id } => {
+                let id = tm.resolve_session(id)?;
+                Self::run_in_ui(window_weak, move |window| {
+                    window.invoke_tab_clicked(id as i32);
+                });
+                Response::Ok
+            }
+            Request::Export {
+                id,
+                format,
+                selection,
+                path,
+                clipboard,
+            } => {
+                let id = tm.resolve_session(id)?;
+                let text = tm.export_session(id, format, selection)?;
+                if let Some(path) = path {
+                    std::fs::write(&path, text).map_err(|e| {
+                        anyhow::anyhow!("Could not write {}: {}", path.display(), e)
+                    })?;
+                    Response::Ok
+                } else if clipboard {
+                    crate::utils::platform::Platform::copy_to_clipboard(&text)?;
+                    Response::Ok
+                } else {
+                    Response::Text { text }
+                }
+            }
+            Request::Screenshot {
+                id,
+                format,
+                region,
+                path,
+            } => {
+                let id = tm.resolve_session(id)?;
+                let image = tm.screenshot(id, format, region)?;
+                match (path, format) {
+                    (Some(path), _) => {
+                        std::fs::write(&path, image).map_err(|e| {
+                            anyhow::anyhow!("Could not write {}: {}", path.display(), e)
+                        })?;
+                        Response::Ok
+                    }
+                    (None, ScreenshotFormat::Svg) => Response::Text {
+                        text: String::from_utf8(image)?,
+                    },
+                    (None, ScreenshotFormat::Png) => {
+                        return Err(anyhow::anyhow!("PNG screenshots need an output path"))
+                    }
+                }
+            }
+            Request::SetTitle { id, title } => {
+                let id = tm.resolve_session(id)?;
+                if let Some(session) = tm.get_session_mut(id) {
+                    session.title = title.clone();
+                }
+                Self::run_in_ui(window_weak, move |window| {
+                    Self::set_tab_title(window, id, &title);
+                });
+                Response::Ok
+            }
+        };
+        Ok(response)
+    }
+
+    /// 다른 `sterm` 실행이 이 창에 탭을 열었을 때 창을 앞으로 가져옴
+    fn raise_window(window: &MainWindow) {
+        use slint::winit_030::WinitWindowAccessor;
+
+        window.window().set_minimized(false);
+        window
+            .window()
+            .with_winit_window(|winit_window| winit_window.focus_window());
+    }
+
+    /// 메인 스레드에서 창을 업데이트
+    fn run_in_ui(
+        window_weak: &Weak<MainWindow>,
+        update: impl FnOnce(&MainWindow) + Send + 'static,
+    ) {
+        let window_weak = window_weak.clone();
+        slint::invoke_from_event_loop(move || {
+            if let Some(window) = window_weak.upgrade() {
+                update(&window);
+            }
+        })
+        .unwrap_or_else(|e| log::error!("Failed to invoke UI update: {:?}", e));
+    }
+
+    /// 지난번 창 상태를 복원하고, 없으면 설정된 열 × 줄에 맞는 크기로 창을 염
+    pub fn apply_window_state(&self, config: &Config, saved: Option<WindowState>) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        match saved {
+            Some(state) => {
+                window
+                    .window()
+                    .set_size(slint::LogicalSize::new(state.width, state.height));
+                window
+                    .window()
+                    .set_position(slint::PhysicalPosition::new(state.x, state.y));
+                window.window().set_maximized(state.maximized);
+                window.window().set_fullscreen(state.fullscreen);
+            }
+            None => {
+                let font_size = window.get_terminal_state().font_size;
+                let (width, height) = Self::window_size_for(
+                    config.ui.initial_columns,
+                    config.ui.initial_rows,
+                    font_size,
+                );
+                window
+                    .window()
+                    .set_size(slint::LogicalSize::new(width as f32, height as f32));
+            }
+        }
+    }
+
+    /// 현재 창 상태. 최대화/전체 화면이면 크기와 위치는 이전 값을 유지
+    pub fn window_state(&self, previous: Option<WindowState>) -> Option<WindowState> {
+        let window = self.window.upgrade()?;
+        let maximized = window.window().is_maximized();
+        let fullscreen = window.window().is_fullscreen();
+
+        let mut state = match previous {
+            Some(previous) if maximized || fullscreen => previous,
+            _ => {
+                let scale_factor = window.window().scale_factor();
+                let size = window.window().size().to_logical(scale_factor);
+                let position = window.window().position();
+                WindowState {
+                    width: size.width,
+                    height: size.height,
+                    x: position.x,
+                    y: position.y,
+                    maximized: false,
+                    fullscreen: false,
+                }
+            }
+        };
+        state.maximized = maximized;
+        state.fullscreen = fullscreen;
+        Some(state)
     }
 
     pub async fn setup_event_handlers(&mut self) -> Result<()> {
@@ -185,12 +966,26 @@ impl UIManager {
                             log::error!("Failed to set active session: {}", e);
                             return;
                         }
+                        let font_size = tm.font_size_for(tab_id as SessionId);
+                        let broadcast_members = tm.broadcast_members();
+                        let logging_sessions = tm.logging_sessions();
                         drop(tm);
 
                         // UI 업데이트는 다시 메인 스레드로
                         slint::invoke_from_event_loop(move || {
                             if let Some(window) = window_weak.upgrade() {
                                 window.set_active_tab(tab_id);
+                                Self::show_broadcast(&window, &broadcast_members);
+                                Self::show_logging(&window, &logging_sessions);
+                                // 탭마다 폰트 크기가 다를 수 있으므로 맞춘 뒤 현재 창 크기로 리사이즈
+                                let mut terminal_state = window.get_terminal_state();
+                                if terminal_state.font_size != font_size {
+                                    terminal_state.font_size = font_size;
+                                    window.set_terminal_state(terminal_state);
+                                }
+                                let scale_factor = window.window().scale_factor();
+                                let size = window.window().size().to_logical(scale_factor);
+                                window.invoke_window_resized(size.width as i32, size.height as i32);
                                 // 터미널 내용 업데이트는 타이머로 처리됨
                             }
                         })
@@ -215,6 +1010,8 @@ impl UIManager {
                         let mut tm = terminal_manager.lock().await;
                         match tm.create_new_session() {
                             Ok(session_id) => {
+                                let broadcast_members = tm.broadcast_members();
+                                let logging_sessions = tm.logging_sessions();
                                 // UI 업데이트
                                 slint::invoke_from_event_loop(move || {
                                     if let Some(window) = window_weak.upgrade() {
@@ -224,6 +1021,8 @@ impl UIManager {
                                             &format!("Terminal {}", session_id + 1),
                                         );
                                         window.set_active_tab(session_id as i32);
+                                        Self::show_broadcast(&window, &broadcast_members);
+                                        Self::show_logging(&window, &logging_sessions);
                                     }
                                 })
                                 .unwrap_or_else(|e| {
@@ -240,33 +1039,81 @@ impl UIManager {
             });
         }
 
-        // 탭 닫기 이벤트 핸들러
+        // 탭 닫기 이벤트 핸들러 - 실행 중인 프로그램이 있으면 먼저 확인
         {
             let terminal_manager = self.terminal_manager.clone();
             let window_weak = self.window.clone();
+            let pending_close = self.pending_close.clone();
 
             window.on_close_tab_clicked(move |tab_id| {
-                let terminal_manager = terminal_manager.clone();
-                let window_weak = window_weak.clone();
+                let session_id = tab_id as SessionId;
+                let running = terminal_manager
+                    .try_lock()
+                    .ok()
+                    .and_then(|tm| tm.running_process(session_id));
+                if let Some(name) = running {
+                    let prompt = format!("{} is still running in this tab — close anyway?", name);
+                    Self::ask_close(
+                        &window_weak,
+                        &pending_close,
+                        PendingClose::Tab(session_id),
+                        prompt,
+                    );
+                    return;
+                }
+                Self::close_tab(&terminal_manager, &window_weak, session_id);
+            });
+        }
 
-                slint::invoke_from_event_loop(move || {
-                    tokio::spawn(async move {
-                        let mut tm = terminal_manager.lock().await;
-                        if let Err(e) = tm.close_session(tab_id as SessionId).await {
-                            log::error!("Failed to close session: {}", e);
-                            return;
-                        }
+        // 창 닫기 요청 - 실행 중인 프로그램이 있으면 먼저 확인
+        {
+            let terminal_manager = self.terminal_manager.clone();
+            let window_weak = self.window.clone();
+            let pending_close = self.pending_close.clone();
 
-                        // UI 업데이트
-                        slint::invoke_from_event_loop(move || {
-                            if let Some(window) = window_weak.upgrade() {
-                                Self::remove_tab_from_ui(&window, tab_id as SessionId);
-                            }
-                        })
-                        .unwrap_or_else(|e| log::error!("Failed to invoke UI update: {:?}", e));
-                    });
-                })
-                .unwrap_or_else(|e| log::error!("Failed to invoke from event loop: {:?}", e));
+            window.window().on_close_requested(move || {
+                let running = match terminal_manager.try_lock() {
+                    Ok(tm) => tm.running_processes(),
+                    Err(_) => Vec::new(),
+                };
+                let prompt = match running.as_slice() {
+                    [] => return CloseRequestResponse::HideWindow,
+                    [(_, name)] => format!("{} is still running — quit anyway?", name),
+                    _ => format!(
+                        "{} programs are still running — quit anyway?",
+                        running.len()
+                    ),
+                };
+                Self::ask_close(&window_weak, &pending_close, PendingClose::Window, prompt);
+                CloseRequestResponse::KeepWindowShown
+            });
+        }
+
+        // 닫기 확인 응답
+        {
+            let terminal_manager = self.terminal_manager.clone();
+            let window_weak = self.window.clone();
+            let pending_close = self.pending_close.clone();
+
+            window.on_close_prompt_answered(move |confirmed| {
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_close_prompt("".into());
+                }
+                let Some(target) = pending_close.try_lock().ok().and_then(|mut p| p.take()) else {
+                    return;
+                };
+                if !confirmed {
+                    return;
+                }
+                match target {
+                    PendingClose::Tab(session_id) => {
+                        Self::close_tab(&terminal_manager, &window_weak, session_id)
+                    }
+                    PendingClose::Window => {
+                        slint::quit_event_loop()
+                            .unwrap_or_else(|e| log::error!("Failed to quit event loop: {:?}", e));
+                    }
+                }
             });
         }
 
@@ -276,23 +1123,128 @@ impl UIManager {
             let korean_ime = self.korean_ime.clone();
             let window_weak = self.window.clone();
             let last_control_key_time = self.last_control_key_time.clone();
+            let hint_state = self.hint_state.clone();
+            let (
+                fullscreen_key,
+                zoom_keys,
+                broadcast_keys,
+                logging_key,
+                export_key,
+                screenshot_key,
+            ) = {
+                let tm = self.terminal_manager.lock().await;
+                let keybindings = &tm.config().keybindings;
+                let zoom_keys: Vec<(KeyBinding, ZoomAction)> = [
+                    (&keybindings.zoom_in, ZoomAction::In),
+                    (&keybindings.zoom_out, ZoomAction::Out),
+                    (&keybindings.zoom_reset, ZoomAction::Reset),
+                ]
+                .into_iter()
+                .filter_map(|(binding, action)| Some((KeyBinding::parse(binding)?, action)))
+                .collect();
+                // (단축키, 모든 탭 여부)
+                let broadcast_keys: Vec<(KeyBinding, bool)> = [
+                    (&keybindings.broadcast_all, true),
+                    (&keybindings.broadcast_toggle, false),
+                ]
+                .into_iter()
+                .filter_map(|(binding, all)| Some((KeyBinding::parse(binding)?, all)))
+                .collect();
+                (
+                    KeyBinding::parse(&keybindings.toggle_fullscreen),
+                    zoom_keys,
+                    broadcast_keys,
+                    KeyBinding::parse(&keybindings.toggle_logging),
+                    KeyBinding::parse(&keybindings.export_scrollback),
+                    KeyBinding::parse(&keybindings.screenshot),
+                )
+            };
+
+            window.on_terminal_input(move |event| {
+                let terminal_manager = terminal_manager.clone();
+                let korean_ime = korean_ime.clone();
+                let window_weak = window_weak.clone();
+                let last_control_key_time = last_control_key_time.clone();
+
+                // 전체 화면 전환
+                let modifiers = &event.modifiers;
+                if fullscreen_key.as_ref().is_some_and(|key| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                }) {
+                    if let Some(window) = window_weak.upgrade() {
+                        let fullscreen = window.window().is_fullscreen();
+                        window.window().set_fullscreen(!fullscreen);
+                    }
+                    return;
+                }
+                // 폰트 확대/축소
+                let zoom = zoom_keys.iter().find(|(key, _)| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                });
+                if let Some((_, action)) = zoom {
+                    if let Some(window) = window_weak.upgrade() {
+                        Self::apply_zoom(&terminal_manager, &window, *action);
+                    }
+                    return;
+                }
+                // 입력 브로드캐스트 켜기/끄기
+                let broadcast = broadcast_keys.iter().find(|(key, _)| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                });
+                if let Some((_, all)) = broadcast {
+                    if let Some(window) = window_weak.upgrade() {
+                        Self::toggle_broadcast(&terminal_manager, &window, *all);
+                    }
+                    return;
+                }
+                // 출력 기록 시작/중지
+                if logging_key.as_ref().is_some_and(|key| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                }) {
+                    if let Some(window) = window_weak.upgrade() {
+                        Self::toggle_logging(&terminal_manager, &window);
+                    }
+                    return;
+                }
+                // 스크롤백(또는 선택 영역) 내보내기
+                if export_key.as_ref().is_some_and(|key| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                }) {
+                    Self::export_active_session(&terminal_manager);
+                    return;
+                }
+                // 화면 스크린샷
+                if screenshot_key.as_ref().is_some_and(|key| {
+                    key.matches(&event.text, modifiers.control, modifiers.alt, modifiers.meta, modifiers.shift)
+                }) {
+                    Self::screenshot_active_session(&terminal_manager);
+                    return;
+                }
+                // 종료된 셸 재시작 대기 중
+                if Self::handle_held_session_key(&event, &terminal_manager) {
+                    return;
+                }
+                if let Some(window) = window_weak.upgrade() {
+                    // 힌트 모드 (단축키로 시작, 라벨 입력으로 선택)
+                    if Self::handle_hint_key(&event, &terminal_manager, &hint_state, &window) {
+                        return;
+                    }
+                    // 복사 모드 (vi 키로 스크롤백 이동/선택/복사)
+                    if Self::handle_vi_key(&event, &terminal_manager, &window) {
+                        return;
+                    }
+                }
 
-            window.on_terminal_input(move |event| {
-                let terminal_manager = terminal_manager.clone();
-                let korean_ime = korean_ime.clone();
-                let window_weak = window_weak.clone();
-                let last_control_key_time = last_control_key_time.clone();
-                
-                eprintln!("🔥 BASIC INPUT EVENT: text={:?}, len={}, chars={}", 
+                eprintln!("🔥 BASIC INPUT EVENT: text={:?}, len={}, chars={}",
                     event.text, event.text.len(), event.text.chars().count());
-                    
+
                 // 엔터키 특별 디버깅
                 if event.text == "\n" || event.text == "\r" {
                     eprintln!("🔥 ENTER KEY DETECTED! Checking processing path...");
                 }
-                log::debug!("Received terminal input event: text={:?}, modifiers={{alt:{}, ctrl:{}, meta:{}, shift:{}}}, repeat:{}", 
+                log::debug!("Received terminal input event: text={:?}, modifiers={{alt:{}, ctrl:{}, meta:{}, shift:{}}}, repeat:{}",
                     event.text, event.modifiers.alt, event.modifiers.control, event.modifiers.meta, event.modifiers.shift, event.repeat);
-                
+
                 // ESC 키 특별 처리 - 빈 텍스트일 때 ESC로 가정
                 if event.text.is_empty() && !event.modifiers.alt && !event.modifiers.control && !event.modifiers.meta && !event.modifiers.shift {
                     log::debug!("Empty text event detected - assuming ESC key");
@@ -300,32 +1252,31 @@ impl UIManager {
                     if let Ok(tm) = terminal_manager.try_lock() {
                         if let Some(active_session) = tm.get_active_session() {
                             let session_id = active_session.id;
-                            
+
                             // 한글 조합 중인 경우 조합 완료 후 ESC 전송
-                            let was_composing = if let Ok(mut ime) = korean_ime.try_lock() {
+                            if let Ok(mut ime) = korean_ime.try_lock() {
                                 let composing = ime.terminal_states.get(&session_id).map(|state| state.is_composing).unwrap_or(false);
                                 if composing {
                                     let (completed_text, _is_composing, current_composition) = ime.process_input(session_id, "\u{1b}");
                                     if !completed_text.is_empty() {
-                                        let _ = tm.write_to_session(session_id, &completed_text);
+                                        let _ = tm.write_input(session_id, &completed_text);
                                     }
-                                    
+
                                     // UI 업데이트
                                     if let Some(window) = window_weak.upgrade() {
                                         // composition_text 직접 업데이트
                                         let composition_str = current_composition.map(|c| c.to_string()).unwrap_or_default();
-                                        
+
                                         // terminal_state 업데이트
                                         let mut terminal_state = window.get_terminal_state();
                                         terminal_state.composition_text = composition_str.into();
                                         window.set_terminal_state(terminal_state);
                                     }
                                 }
-                                composing
-                            } else { false };
-                            
+                            }
+
                             // ESC 전송 (조합 중이 아니었거나 조합 완료 후)
-                            if let Err(e) = tm.write_to_session(session_id, "\u{1b}") {
+                            if let Err(e) = tm.write_input(session_id, "\u{1b}") {
                                 log::error!("Failed to write ESC to session {}: {}", session_id, e);
                             } else {
                                 log::debug!("ESC key sent to PTY for session {}", session_id);
@@ -334,7 +1285,7 @@ impl UIManager {
                     }
                     return;
                 }
-                
+
                 // Control 키가 눌렸을 때 시간 기록
                 if event.modifiers.control {
                     if let Ok(mut last_time) = last_control_key_time.try_lock() {
@@ -347,15 +1298,15 @@ impl UIManager {
                     if let Ok(tm) = terminal_manager.try_lock() {
                         if let Some(active_session) = tm.get_active_session() {
                             let session_id = active_session.id;
-                            
+
                             // 한글 IME에서 백스페이스 처리
                             if let Ok(mut ime) = korean_ime.try_lock() {
                                 let consumed = ime.handle_backspace(session_id);
-                                
+
                                 // 한글 조합 상태 업데이트
                                 let current_composition = ime.terminal_states.get(&session_id)
                                     .and_then(|state| if state.is_composing { state.get_display_char() } else { None });
-                                
+
                                 // UI 업데이트
                                 if let Some(window) = window_weak.upgrade() {
                                     let mut terminal_state = window.get_terminal_state();
@@ -366,10 +1317,10 @@ impl UIManager {
                                     window.set_terminal_state(terminal_state);
                                     log::debug!("Korean composition after backspace: {:?}", current_composition);
                                 }
-                                
+
                                 // IME가 처리하지 않은 경우만 터미널로 전송
                                 if !consumed {
-                                    if let Err(e) = tm.write_to_session(session_id, "\u{08}") {
+                                    if let Err(e) = tm.write_input(session_id, "\u{08}") {
                                         log::error!("Failed to write backspace to terminal: {}", e);
                                     }
                                 }
@@ -378,7 +1329,7 @@ impl UIManager {
                     }
                     return;
                 }
-                
+
                 // 엔터키도 일반 IME 경로로 처리하도록 변경
                 // (기존 별도 처리 제거)
 
@@ -387,24 +1338,24 @@ impl UIManager {
                     let key_name = if event.modifiers.meta { "Cmd+R" } else { "Ctrl+R" };
                     log::info!("🔄 Manual resize triggered by {}", key_name);
                     eprintln!("🔄 Manual resize triggered by {}", key_name);
-                    
+
                     let tm = terminal_manager.clone();
                     let ww = window_weak.clone();
-                    
+
                     // 윈도우 정보를 먼저 메인 스레드에서 가져오기
                     if let Some(window) = ww.upgrade() {
                         let font_size = window.get_terminal_state().font_size;
-                        
+
                         // 실제 윈도우 크기 가져오기
                         let window_size = window.window().size();
                         let width = window_size.width as i32;
                         let height = window_size.height as i32;
-                        
+
                         let (cols, rows) = Self::calculate_terminal_size(width, height, font_size);
-                        
+
                         log::info!("🔄 Manual resize: actual window {}x{} -> {}x{} chars", width, height, cols, rows);
                         eprintln!("🔄 Manual resize: actual window {}x{} -> {}x{} chars", width, height, cols, rows);
-                        
+
                         // 비동기 작업은 invoke_from_event_loop로 처리
                         let terminal_manager = tm.clone();
                         slint::invoke_from_event_loop(move || {
@@ -412,10 +1363,10 @@ impl UIManager {
                                 let mut terminal_manager = terminal_manager.lock().await;
                                 if let Some(active_session) = terminal_manager.get_active_session() {
                                     let session_id = active_session.id;
-                                    
-                                    log::info!("📏 Resizing terminal session {} to {}x{} characters", 
+
+                                    log::info!("📏 Resizing terminal session {} to {}x{} characters",
                                         session_id, cols, rows);
-                                        
+
                                     if let Err(e) = terminal_manager.resize_session(session_id, cols, rows) {
                                         log::error!("❌ Failed to manually resize terminal: {}", e);
                                         eprintln!("❌ Failed to manually resize terminal: {}", e);
@@ -429,21 +1380,21 @@ impl UIManager {
                     }
                     return;
                 }
-                
+
                 // tterm 스타일: modifier 키가 눌렸는데 텍스트가 비어있으면 무시
                 if (event.modifiers.control || event.modifiers.alt || event.modifiers.meta) && event.text.is_empty() {
-                    log::debug!("Ignoring empty text event with modifier keys: ctrl:{}, alt:{}, meta:{}", 
+                    log::debug!("Ignoring empty text event with modifier keys: ctrl:{}, alt:{}, meta:{}",
                         event.modifiers.control, event.modifiers.alt, event.modifiers.meta);
                     return;
                 }
-                
+
                 // 특수키 및 Modifier 키 조합을 터미널 바이트로 변환
                 if let Some(key_bytes) = Self::convert_key_event_to_terminal_bytes(&event) {
                     if let Ok(tm) = terminal_manager.try_lock() {
                         if let Some(active_session) = tm.get_active_session() {
                             let session_id = active_session.id;
                             let bytes_str = String::from_utf8_lossy(&key_bytes);
-                            
+
                             // 특수키를 IME를 통해 처리 (조합 상태 확인 포함)
                             let (filtered_input, current_composition) = match process_and_filter_terminal_input(&TerminalKeyEvent {
                                 text: bytes_str.to_string().into(),
@@ -456,16 +1407,16 @@ impl UIManager {
                                     return;
                                 }
                             };
-                            
+
                             // 완성된 텍스트만 터미널로 전송
                             if !filtered_input.is_empty() {
-                                if let Err(e) = tm.write_to_session(session_id, &filtered_input) {
+                                if let Err(e) = tm.write_input(session_id, &filtered_input) {
                                     log::error!("Failed to write special key to terminal: {}", e);
                                 } else {
                                     log::debug!("Sent special key: {:?} -> {}", key_bytes, filtered_input.escape_debug());
                                 }
                             }
-                            
+
                             // 조합 중인 글자 UI 업데이트
                             if let Some(window) = window_weak.upgrade() {
                                 let mut terminal_state = window.get_terminal_state();
@@ -486,7 +1437,7 @@ impl UIManager {
                 if let Ok(tm) = terminal_manager.try_lock() {
                     if let Some(active_session) = tm.get_active_session() {
                         let session_id = active_session.id;
-                        
+
                         // Slint의 중복 이벤트 방지: Ctrl 키 직후의 텍스트 이벤트는 무시
                         if !event.modifiers.control && !event.modifiers.alt && !event.modifiers.meta {
                             if let Ok(last_time) = last_control_key_time.try_lock() {
@@ -498,7 +1449,7 @@ impl UIManager {
                                 }
                             }
                         }
-                        
+
                         // 한글 IME 처리 및 필터링
                         eprintln!("💫 CALLING process_and_filter_terminal_input with: {:?}", event.text);
                         let (filtered_input, current_composition) = match process_and_filter_terminal_input(&event, &korean_ime, session_id) {
@@ -515,7 +1466,7 @@ impl UIManager {
                         // 완성된 텍스트만 터미널로 전송
                         if !filtered_input.is_empty() {
                             eprintln!("💫 WRITING TO PTY: {:?}", filtered_input);
-                            if let Err(e) = tm.write_to_session(session_id, &filtered_input) {
+                            if let Err(e) = tm.write_input(session_id, &filtered_input) {
                                 eprintln!("💫 PTY WRITE ERROR: {}", e);
                             } else {
                                 eprintln!("💫 PTY WRITE SUCCESS!");
@@ -523,7 +1474,7 @@ impl UIManager {
                         } else {
                             eprintln!("💫 EMPTY INPUT - NOT WRITING TO PTY");
                         }
-                        
+
                         // 조합 중인 글자 UI 업데이트
                         if let Some(window) = window_weak.upgrade() {
                             let mut terminal_state = window.get_terminal_state();
@@ -541,17 +1492,13 @@ impl UIManager {
             });
         }
 
-
-
         // 윈도우 리사이즈 이벤트 핸들러 (개선된 버전)
         {
             let terminal_manager = self.terminal_manager.clone();
-            let window_weak = self.window.clone();
             let last_resize_time = Arc::new(Mutex::new(std::time::Instant::now()));
 
             window.on_window_resized(move |width, height| {
                 let terminal_manager = terminal_manager.clone();
-                let window_weak = window_weak.clone();
                 let last_resize_time = last_resize_time.clone();
 
                 log::info!("🔄 WINDOW RESIZE EVENT RECEIVED: {}x{}", width, height);
@@ -564,39 +1511,46 @@ impl UIManager {
                             let mut last_time = last_resize_time.lock().await;
                             *last_time = std::time::Instant::now();
                         }
-                        
+
                         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                        
+
                         // 100ms 후에도 여전히 가장 최근 리사이즈라면 처리
                         let should_process = {
                             let last_time = last_resize_time.lock().await;
                             last_time.elapsed() >= std::time::Duration::from_millis(100)
                         };
-                        
+
                         if !should_process {
                             return;
                         }
 
                         let mut tm = terminal_manager.lock().await;
                         if let Some(active_session) = tm.get_active_session() {
-                            // 현재 폰트 설정을 가져오기 (기본값 사용)
-                            let font_size = if let Some(window) = window_weak.upgrade() {
-                                window.get_terminal_state().font_size
-                            } else {
-                                11 // 기본 폰트 크기
-                            };
-                            
-                            // 터미널 크기 계산
-                            let (cols, rows) = Self::calculate_terminal_size(width, height, font_size);
+                            // 현재 탭에 적용된 폰트 크기 (확대/축소 반영)
                             let session_id = active_session.id;
-                            
-                            log::debug!("Resizing terminal session {} to {}x{} characters ({}x{} pixels)", 
-                                session_id, cols, rows, width, height);
-                                
+                            let font_size = tm.font_size_for(session_id);
+
+                            // 터미널 크기 계산
+                            let (cols, rows) =
+                                Self::calculate_terminal_size(width, height, font_size);
+
+                            log::debug!(
+                                "Resizing terminal session {} to {}x{} characters ({}x{} pixels)",
+                                session_id,
+                                cols,
+                                rows,
+                                width,
+                                height
+                            );
+
                             if let Err(e) = tm.resize_session(session_id, cols, rows) {
                                 log::error!("Failed to resize terminal: {}", e);
                             } else {
-                                log::info!("Terminal resized successfully to {}x{} characters", cols, rows);
+                                log::info!(
+                                    "Terminal resized successfully to {}x{} characters",
+                                    cols,
+                                    rows
+                                );
                             }
                         }
                     });
@@ -605,6 +1559,103 @@ impl UIManager {
             });
         }
 
+        // 포커스 변경 이벤트 핸들러 (hollow 커서, focus in/out 리포트)
+        {
+            let terminal_manager = self.terminal_manager.clone();
+
+            window.on_focus_changed(move |focused| {
+                if let Ok(tm) = terminal_manager.try_lock() {
+                    if let Some(active_session) = tm.get_active_session() {
+                        tm.set_session_focus(active_session.id, focused);
+                    }
+                } else {
+                    log::warn!("Could not acquire terminal manager lock for focus change");
+                }
+            });
+        }
+
+        // 링크 hover 이벤트 핸들러 (밑줄 + 툴팁)
+        {
+            let terminal_manager = self.terminal_manager.clone();
+            let window_weak = self.window.clone();
+
+            window.on_mouse_moved(move |x, y| {
+                let Some(window) = window_weak.upgrade() else {
+                    return;
+                };
+                let font_metrics = Self::active_font_metrics(&window);
+                let link = Self::pixel_to_cell(x, y, &font_metrics).and_then(|(row, col)| {
+                    let tm = terminal_manager.try_lock().ok()?;
+                    let session_id = tm.get_active_session()?.id;
+                    tm.link_at(session_id, row, col).map(|link| (row, link))
+                });
+
+                let hover = match link {
+                    Some((row, link)) => LinkHover {
+                        active: true,
+                        x: font_metrics.padding_x
+                            + (link.start_col as i32) * font_metrics.char_width,
+                        y: font_metrics.padding_y + (row as i32) * font_metrics.line_height,
+                        width: ((link.end_col - link.start_col) as i32) * font_metrics.char_width,
+                        height: font_metrics.line_height,
+                        target: link.target.into(),
+                    },
+                    None => LinkHover::default(),
+                };
+                if window.get_link_hover() != hover {
+                    window.set_link_hover(hover);
+                }
+            });
+        }
+
+        // Ctrl/Cmd+클릭 링크 열기 이벤트 핸들러
+        {
+            let terminal_manager = self.terminal_manager.clone();
+
+            window.on_link_clicked(move |x, y| {
+                let Ok(tm) = terminal_manager.try_lock() else {
+                    log::warn!("Could not acquire terminal manager lock for link click");
+                    return;
+                };
+                let Some(session_id) = tm.get_active_session().map(|session| session.id) else {
+                    return;
+                };
+                let font_metrics = tm.font_metrics(session_id);
+                let Some((row, col)) = Self::pixel_to_cell(x, y, &font_metrics) else {
+                    return;
+                };
+                if let Some(link) = tm.link_at(session_id, row, col) {
+                    tm.open_link(session_id, &link);
+                }
+            });
+        }
+
+        // OSC 52 클립보드 접근 확인 응답 핸들러
+        {
+            let terminal_manager = self.terminal_manager.clone();
+            let pending_clipboard = self.pending_clipboard.clone();
+            let window_weak = self.window.clone();
+
+            window.on_clipboard_prompt_answered(move |allow| {
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_clipboard_prompt("".into());
+                }
+                let Some(request) = pending_clipboard.try_lock().ok().and_then(|mut p| p.take())
+                else {
+                    return;
+                };
+                if !allow {
+                    log::info!("User denied OSC 52 request: {}", request.prompt());
+                    return;
+                }
+                if let Ok(tm) = terminal_manager.try_lock() {
+                    Self::run_clipboard_request(&tm, &request, &window_weak);
+                } else {
+                    log::warn!("Could not acquire terminal manager lock for clipboard request");
+                }
+            });
+        }
+
         // 클립보드 복사 이벤트 핸들러
         {
             window.on_copy_selected(move || {
@@ -636,7 +1687,7 @@ impl UIManager {
                         // 클립보드에서 텍스트 가져오기
                         match crate::utils::platform::Platform::paste_from_clipboard() {
                             Ok(text) => {
-                                let mut tm = terminal_manager.lock().await;
+                                let tm = terminal_manager.lock().await;
                                 if let Some(active_session) = tm.get_active_session() {
                                     let session_id = active_session.id;
                                     if let Err(e) = tm.write_to_session(session_id, &text) {
@@ -654,6 +1705,9 @@ impl UIManager {
             });
         }
 
+        // 설정된 색상/커서 적용
+        self.apply_config_to_window(&window).await;
+
         // 초기 탭 설정
         self.setup_initial_tabs(&window).await?;
 
@@ -661,7 +1715,7 @@ impl UIManager {
         self.start_pty_event_processing().await?;
 
         // 네이티브 윈도우 리사이즈 콜백에 의존 (on_window_resized)
-        
+
         // 수동 리사이즈 테스트를 위한 로깅 강화
         log::info!("Window resize handlers are set up. Try resizing the window manually.");
 
@@ -673,8 +1727,8 @@ impl UIManager {
 
     async fn start_pty_event_processing(&self) -> Result<()> {
         let terminal_manager = self.terminal_manager.clone();
-        let ui_update_sender = self.ui_update_sender.clone();
         let window_weak = self.window.clone();
+        let pending_clipboard = self.pending_clipboard.clone();
         // TerminalManager로부터 이벤트 수신기 가져오기
         let event_receiver = {
             let mut tm = terminal_manager.lock().await;
@@ -695,32 +1749,28 @@ impl UIManager {
 
                                 match &event {
                                     // PTY 출력이나 터미널 상태 변경 시 UI 업데이트
-                                    alacritty_terminal::event::Event::Wakeup  => {
+                                    // (동기화 출력(mode 2026) 중에는 파서가 끝날 때까지, 최대 150ms 출력을 모아 두고 Wakeup도 보내지 않음)
+                                    alacritty_terminal::event::Event::Wakeup
+                                    | alacritty_terminal::event::Event::CursorBlinkingChange => {
+                                        // 출력 트리거는 화면에 반영된 뒤 검사 (색칠한 셀이 바로 아래 렌더링에 포함됨)
+                                        let hits = terminal_manager.blocking_lock().process_output(session_id);
+                                        if !hits.is_empty() {
+                                            Self::run_in_ui(&window_weak, move |window| {
+                                                Self::apply_trigger_hits(window, session_id, hits)
+                                            });
+                                        }
                                         // Wakeup이나 Title 변경 시에도 터미널 내용 업데이트
                                         if let Ok(mut tm) = terminal_manager.try_lock() {
                                             log::debug!("Terminal content updated on {:?} for session {}:", event, session_id);
                                                                                         // 색상 정보 추출 및 UI로 전송 - 폰트 메트릭 사용
-                                            let font_metrics = FontMetrics::default(); // 임시로 기본값 사용
+                                            let font_metrics = tm.font_metrics(session_id);
                                             if let Some(colored_content) = tm.extract_session_colored_content(session_id, &font_metrics) {
                                                 log::debug!("Color segments for session {} ({}): {} segments", session_id, match &event { alacritty_terminal::event::Event::Wakeup => "Wakeup", alacritty_terminal::event::Event::Title(_) => "Title", _ => "Other" }, colored_content.segments.len());
-                                                if colored_content.segments.len() > 0 {
+                                                if !colored_content.segments.is_empty() {
                                                     for (i, segment) in colored_content.segments.iter().take(5).enumerate() {
                                                         log::debug!("  Segment {}: '{}' x={} y={} w={} h={}", i, segment.text.chars().take(20).collect::<String>(), segment.x, segment.y, segment.width, segment.height);
                                                     }
-                                                    let cursor_info =  {
-                                                        let font_metrics = FontMetrics::default(); // 임시로 기본값 사용
-                                                        {
-                                                            let cursor_x = font_metrics.padding_x + (colored_content.cursor_col as i32) * font_metrics.char_width;
-                                                            let cursor_y = font_metrics.padding_y + (colored_content.cursor_line as i32) * font_metrics.line_height;
-                                                            CursorInfo {
-                                                                x: cursor_x,
-                                                                y: cursor_y,
-                                                                width: font_metrics.char_width,
-                                                                height: font_metrics.line_height,
-                                                                visible: true,
-                                                            }
-                                                        }
-                                                    } ;
+                                                    let cursor_info = Self::cursor_info_for(&colored_content, &font_metrics);
                                                     let slint_segments: Vec<ColorSegment> = colored_content.segments.iter().map(|seg| {
                                                         ColorSegment {
                                                             text: seg.text.clone().into(),
@@ -734,16 +1784,38 @@ impl UIManager {
                                                             y: seg.y,      // 이미 계산된 절대 Y 위치
                                                             width: seg.width,  // 이미 계산된 폭
                                                             height: seg.height, // 이미 계산된 높이
+                                                            bold: seg.attributes.is_bold(),
+                                                            italic: seg.attributes.is_italic(),
+                                                            underline: seg.attributes.underline.as_code(),
+                                                            underline_path: seg.attributes.underline.svg_path(seg.width).into(),
+                                                            underline_color: seg.underline_color.to_slint_color(),
+                                                            strikethrough: seg.attributes.strikethrough,
+                                                            hidden: seg.attributes.hidden,
+                                                            default_bg: seg.bg_color == colored_content.background,
                                                         }
                                                     }).collect();
+                                                    // OSC 10/11로 변경될 수 있는 기본 전경/배경색
+                                                    let background = colored_content.background.to_slint_color();
+                                                    let foreground = colored_content.foreground.to_slint_color();
+                                                    let image_slices = colored_content.images;
                                                     let window_weak = window_weak.clone();
                                                     slint::invoke_from_event_loop(move || {
                                                         if let Some(window) = window_weak.upgrade() {
                                                             // 색상 세그먼트 설정
                                                             let model = ModelRc::new(VecModel::from(slint_segments));
                                                             window.set_color_segments(model);
+                                                            window.set_image_strips(Self::image_strips(&image_slices));
                                                             window.set_cursor_info(cursor_info);
 
+                                                            let mut terminal_state = window.get_terminal_state();
+                                                            if terminal_state.background_color != background
+                                                                || terminal_state.foreground_color != foreground
+                                                            {
+                                                                terminal_state.background_color = background;
+                                                                terminal_state.foreground_color = foreground;
+                                                                window.set_terminal_state(terminal_state);
+                                                            }
+
                                                         }
                                                     }).unwrap_or_else(|e|
                                                         log::error!("Failed to invoke colored UI update: {:?}", e));
@@ -751,13 +1823,40 @@ impl UIManager {
                                             }
                                         }
                                     }
+                                    // 색상 질의 (OSC 4/10/11/12 ;?) 응답 - 응답이 누락되지 않도록 blocking lock 사용
+                                    alacritty_terminal::event::Event::ColorRequest(index, format) => {
+                                        let tm = terminal_manager.blocking_lock();
+                                        tm.respond_color_request(session_id, *index, format.as_ref());
+                                    }
+                                    // OSC 52 클립보드 쓰기/읽기 - 세션(프로필)의 정책에 따라 처리
+                                    alacritty_terminal::event::Event::ClipboardStore(_, text) => {
+                                        let tm = terminal_manager.blocking_lock();
+                                        let request = ClipboardRequest::Store { session_id, text: text.clone() };
+                                        Self::handle_clipboard_request(&tm, request, &pending_clipboard, &window_weak);
+                                    }
+                                    alacritty_terminal::event::Event::ClipboardLoad(_, format) => {
+                                        let tm = terminal_manager.blocking_lock();
+                                        let request = ClipboardRequest::Load { session_id, format: format.clone() };
+                                        Self::handle_clipboard_request(&tm, request, &pending_clipboard, &window_weak);
+                                    }
+                                    // 셸 종료 코드 기록 (Exit보다 먼저 도착)
+                                    alacritty_terminal::event::Event::ChildExit(code) => {
+                                        let mut tm = terminal_manager.blocking_lock();
+                                        tm.record_child_exit(session_id, *code);
+                                    }
+                                    // 세션 하나가 끝나도 다른 세션 이벤트는 계속 처리
                                     alacritty_terminal::event::Event::Exit => {
                                         log::info!("Terminal session {} exited", session_id);
-                                        // 세션 종료 메시지 전송
-                                        if let Err(e) = ui_update_sender.send(UIUpdateMessage::SessionClosed { session_id }) {
-                                            log::error!("Failed to send session closed message: {}", e);
+                                        let action = terminal_manager.blocking_lock().handle_session_exit(session_id);
+                                        if action == SessionExitAction::Close {
+                                            let window_weak = window_weak.clone();
+                                            slint::invoke_from_event_loop(move || {
+                                                if let Some(window) = window_weak.upgrade() {
+                                                    window.invoke_close_tab_clicked(session_id as i32);
+                                                }
+                                            }).unwrap_or_else(|e|
+                                                log::error!("Failed to close exited session tab: {:?}", e));
                                         }
-                                        break;
                                     }
                                     _ => {
                                         // 다른 이벤트들은 무시
@@ -779,7 +1878,18 @@ impl UIManager {
         Ok(())
     }
 
-
+    /// 설정의 테마 색상과 커서 깜빡임 간격을 윈도우에 적용
+    async fn apply_config_to_window(&self, window: &MainWindow) {
+        let tm = self.terminal_manager.lock().await;
+        let config = tm.config();
+        let theme = ColorTheme::from_config(&config.ui);
+
+        let mut terminal_state = window.get_terminal_state();
+        terminal_state.background_color = theme.background.to_slint_color();
+        terminal_state.foreground_color = theme.foreground.to_slint_color();
+        window.set_terminal_state(terminal_state);
+        window.set_cursor_blink_interval_ms(config.ui.cursor_blink_interval_ms as i32);
+    }
 
     async fn setup_initial_tabs(&self, window: &MainWindow) -> Result<()> {
         // 초기 탭 데이터 설정
@@ -787,6 +1897,9 @@ impl UIManager {
             title: "Terminal 1".into(),
             active: true,
             id: 0,
+            broadcast: false,
+            badge: "".into(),
+            logging: false,
         }];
 
         let tabs_model = VecModel::from(initial_tabs);
@@ -798,31 +1911,6 @@ impl UIManager {
         Ok(())
     }
 
-    async fn setup_ui_update_callback(&self) -> Result<()> {
-        let terminal_manager = self.terminal_manager.clone();
-        let window_weak = self.window.clone();
-
-        // 터미널 매니저에 UI 업데이트 콜백 설정
-        let mut tm = terminal_manager.lock().await;
-        tm.set_ui_update_callback(Box::new(move |session_id: SessionId, content: String| {
-            let window_weak = window_weak.clone();
-
-            // UI 업데이트를 메인 스레드에서 실행 - color_segments 우선 사용
-            // slint::invoke_from_event_loop(move || {
-            //     if let Some(window) = window_weak.upgrade() {
-            //         window.set_terminal_content(content.into());
-            //         log::debug!("UI updated with terminal content for session {}", session_id);
-            //     }
-            // }).unwrap_or_else(|e| log::error!("Failed to invoke UI update: {:?}", e));
-            log::debug!(
-                "Skipping direct terminal content update for session {} (using color_segments)",
-                session_id
-            );
-        }));
-
-        Ok(())
-    }
-
     fn add_tab_to_ui(window: &MainWindow, session_id: SessionId, title: &str) {
         let tabs = window.get_tabs();
         let mut tab_data = Vec::new();
@@ -840,12 +1928,176 @@ impl UIManager {
             title: title.into(),
             active: true,
             id: session_id as i32,
+            broadcast: false,
+            badge: "".into(),
+            logging: false,
         });
 
         let new_tabs_model = VecModel::from(tab_data);
         window.set_tabs(ModelRc::new(new_tabs_model));
     }
 
+    /// 브로드캐스트 입력을 받는 탭에 테두리를, 보이는 탭이 받는 중이면 상태 표시
+    fn show_broadcast(window: &MainWindow, members: &[SessionId]) {
+        let tabs = window.get_tabs();
+        for i in 0..tabs.row_count() {
+            if let Some(mut tab) = tabs.row_data(i) {
+                let broadcast = members.contains(&(tab.id as SessionId));
+                if tab.broadcast != broadcast {
+                    tab.broadcast = broadcast;
+                    tabs.set_row_data(i, tab);
+                }
+            }
+        }
+
+        let active = window.get_active_tab() as SessionId;
+        let status = if members.contains(&active) {
+            format!("BROADCAST → {} tabs", members.len())
+        } else {
+            String::new()
+        };
+        window.set_broadcast_status(status.into());
+    }
+
+    /// 브로드캐스트 단축키 처리 (모든 탭 또는 현재 탭을 그룹에 넣기/빼기)
+    fn toggle_broadcast(
+        terminal_manager: &Arc<Mutex<TerminalManager>>,
+        window: &MainWindow,
+        all: bool,
+    ) {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            log::warn!("Could not acquire terminal manager lock for broadcast");
+            return;
+        };
+        if all {
+            tm.toggle_broadcast_all();
+        } else if let Some(session_id) = tm.active_session_id() {
+            tm.toggle_broadcast_member(session_id);
+        }
+        Self::show_broadcast(window, &tm.broadcast_members());
+    }
+
+    /// 현재 탭의 출력 기록 시작/중지 단축키 처리
+    fn toggle_logging(terminal_manager: &Arc<Mutex<TerminalManager>>, window: &MainWindow) {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            log::warn!("Could not acquire terminal manager lock for logging");
+            return;
+        };
+        let Some(session_id) = tm.active_session_id() else {
+            return;
+        };
+        match tm.toggle_logging(session_id) {
+            Ok(Some(path)) => {
+                let message = format!("Logging to {}", path.display());
+                if let Err(e) =
+                    crate::utils::platform::Platform::show_notification("sterm", &message)
+                {
+                    log::warn!("Failed to show logging notification: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to start logging session {}: {}", session_id, e),
+        }
+        Self::show_logging(window, &tm.logging_sessions());
+    }
+
+    /// 내보내기 단축키 처리. 저장한 경로를 알림으로 표시
+    fn export_active_session(terminal_manager: &Arc<Mutex<TerminalManager>>) {
+        let Ok(tm) = terminal_manager.try_lock() else {
+            log::warn!("Could not acquire terminal manager lock for export");
+            return;
+        };
+        let Some(session_id) = tm.active_session_id() else {
+            return;
+        };
+        match tm.export_to_file(session_id) {
+            Ok(path) => {
+                let message = format!("Exported to {}", path.display());
+                if let Err(e) =
+                    crate::utils::platform::Platform::show_notification("sterm", &message)
+                {
+                    log::warn!("Failed to show export notification: {}", e);
+                }
+            }
+            Err(e) => log::error!("Failed to export session {}: {}", session_id, e),
+        }
+    }
+
+    /// 스크린샷 단축키 처리. 저장한 경로를 알림으로 표시
+    fn screenshot_active_session(terminal_manager: &Arc<Mutex<TerminalManager>>) {
+        let Ok(mut tm) = terminal_manager.try_lock() else {
+            log::warn!("Could not acquire terminal manager lock for screenshot");
+            return;
+        };
+        let Some(session_id) = tm.active_session_id() else {
+            return;
+        };
+        match tm.screenshot_to_file(session_id) {
+            Ok(path) => {
+                let message = format!("Screenshot saved to {}", path.display());
+                if let Err(e) =
+                    crate::utils::platform::Platform::show_notification("sterm", &message)
+                {
+                    log::warn!("Failed to show screenshot notification: {}", e);
+                }
+            }
+            Err(e) => log::error!("Failed to take screenshot of session {}: {}", session_id, e),
+        }
+    }
+
+    /// 출력을 기록 중인 탭 표시
+    pub fn show_logging(window: &MainWindow, sessions: &[SessionId]) {
+        let tabs = window.get_tabs();
+        for i in 0..tabs.row_count() {
+            if let Some(mut tab) = tabs.row_data(i) {
+                let logging = sessions.contains(&(tab.id as SessionId));
+                if tab.logging != logging {
+                    tab.logging = logging;
+                    tabs.set_row_data(i, tab);
+                }
+            }
+        }
+    }
+
+    /// 트리거 결과 중 UI가 처리할 것: 탭 배지, 벨(창 주의 요청)
+    fn apply_trigger_hits(window: &MainWindow, session_id: SessionId, hits: Vec<TriggerHit>) {
+        use slint::winit_030::{winit::window::UserAttentionType, WinitWindowAccessor};
+
+        for hit in hits {
+            match hit {
+                TriggerHit::Badge(badge) => {
+                    let tabs = window.get_tabs();
+                    for i in 0..tabs.row_count() {
+                        if let Some(mut tab) = tabs.row_data(i) {
+                            if tab.id == session_id as i32 {
+                                tab.badge = badge.as_str().into();
+                                tabs.set_row_data(i, tab);
+                            }
+                        }
+                    }
+                }
+                TriggerHit::Bell => {
+                    window.window().with_winit_window(|winit_window| {
+                        winit_window.request_user_attention(Some(UserAttentionType::Informational))
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn set_tab_title(window: &MainWindow, session_id: SessionId, title: &str) {
+        let tabs = window.get_tabs();
+        for i in 0..tabs.row_count() {
+            if let Some(mut tab) = tabs.row_data(i) {
+                if tab.id == session_id as i32 {
+                    tab.title = title.into();
+                    tabs.set_row_data(i, tab);
+                }
+            }
+        }
+    }
+
     fn remove_tab_from_ui(window: &MainWindow, session_id: SessionId) {
         let tabs = window.get_tabs();
         let tab_id = session_id as i32;
@@ -863,264 +2115,127 @@ impl UIManager {
         let new_tabs_model = VecModel::from(tab_data);
         window.set_tabs(ModelRc::new(new_tabs_model));
     }
-    
+
     /// tterm 스타일: 키 이벤트를 터미널 바이트로 변환 (특수키 + modifier 조합)
     fn convert_key_event_to_terminal_bytes(event: &TerminalKeyEvent) -> Option<Vec<u8>> {
         let text = event.text.as_str();
-        log::debug!("convert_key_event_to_terminal_bytes called with: {:?}", text);
-        
+        log::debug!(
+            "convert_key_event_to_terminal_bytes called with: {:?}",
+            text
+        );
+
         // 1. 먼저 특수키들을 처리 (텍스트와 무관한 키들)
         if let Some(special_bytes) = Self::handle_special_keys(text) {
             return Some(special_bytes);
         }
-        
+
         // 2. Ctrl 키 조합 처리 macOS에서는 meta
         if event.modifiers.meta {
             return Self::ctrl_key_to_bytes(text);
         }
-        
-        // 3. Alt 키 조합 처리 (ESC + 키)  
+
+        // 3. Alt 키 조합 처리 (ESC + 키)
         if event.modifiers.alt {
             return Self::alt_key_to_bytes(text);
         }
-        
+
         // 4. Meta (Cmd) 키는 보통 애플리케이션 단축키이므로 무시 macOs에서는 ctrl
         if event.modifiers.control {
             return None;
         }
-        
+
         None
     }
-    
+
     /// 특수키들을 터미널 바이트로 변환 (tterm 스타일)
     fn handle_special_keys(text: &str) -> Option<Vec<u8>> {
         log::debug!("handle_special_keys called with: {:?}", text);
         match text {
             // 백스페이스 (\u{08})
             "\u{08}" => Some(vec![0x7F]), // DEL (127)
-            
+
             // Tab
             "\t" => Some(b"\t".to_vec()),
-            
+
             // Enter/Newline - 일반 텍스트 경로로 처리하도록 특수키에서 제외
             // "\n" | "\r" => Some(b"\r".to_vec()), // 주석 처리
-            
+
             // Escape
             "\u{1B}" => Some(b"\x1b".to_vec()),
-            
+
             // 화살표 키들 (ANSI escape sequences)
             // 주의: 이 패턴들은 실제 키보드 입력에서는 잘 안나타나고
             // 보통 Key 이벤트로 처리되지만, 대비해서 넣어둠
             _ if text.starts_with("\u{1B}[") => {
                 match text {
-                    "\u{1B}[A" => Some(b"\x1b[A".to_vec()), // Up Arrow
-                    "\u{1B}[B" => Some(b"\x1b[B".to_vec()), // Down Arrow  
-                    "\u{1B}[C" => Some(b"\x1b[C".to_vec()), // Right Arrow
-                    "\u{1B}[D" => Some(b"\x1b[D".to_vec()), // Left Arrow
+                    "\u{1B}[A" => Some(b"\x1b[A".to_vec()),   // Up Arrow
+                    "\u{1B}[B" => Some(b"\x1b[B".to_vec()),   // Down Arrow
+                    "\u{1B}[C" => Some(b"\x1b[C".to_vec()),   // Right Arrow
+                    "\u{1B}[D" => Some(b"\x1b[D".to_vec()),   // Left Arrow
                     "\u{1B}[3~" => Some(b"\x1b[3~".to_vec()), // Delete
-                    "\u{1B}[H" => Some(b"\x1b[H".to_vec()), // Home
-                    "\u{1B}[F" => Some(b"\x1b[F".to_vec()), // End
+                    "\u{1B}[H" => Some(b"\x1b[H".to_vec()),   // Home
+                    "\u{1B}[F" => Some(b"\x1b[F".to_vec()),   // End
                     "\u{1B}[5~" => Some(b"\x1b[5~".to_vec()), // Page Up
                     "\u{1B}[6~" => Some(b"\x1b[6~".to_vec()), // Page Down
                     _ => None,
                 }
             }
-            
+
             _ => None,
         }
     }
-    
-    /// tterm 스타일: 백스페이스 키 처리 (한글 IME 우선)
-    fn handle_backspace_key(
-        terminal_manager: &Arc<Mutex<TerminalManager>>,
-        korean_ime: &Arc<Mutex<KoreanIME>>,
-        window_weak: &Weak<MainWindow>
-    ) {
-        if let Ok(tm) = terminal_manager.try_lock() {
-            if let Some(active_session) = tm.get_active_session() {
-                let session_id = active_session.id;
-                
-                // 한글 IME에서 백스페이스 처리
-                if let Ok(mut ime) = korean_ime.try_lock() {
-                    let consumed = ime.handle_backspace(session_id);
-                    
-                    // 한글 조합 상태 업데이트
-                    let current_composition = ime.terminal_states.get(&session_id)
-                        .and_then(|state| if state.is_composing { 
-                            state.get_display_char() 
-                        } else { 
-                            None 
-                        });
-                    
-                    // UI 업데이트 (조합 중인 글자 표시)
-                    if let Some(window) = window_weak.upgrade() {
-                        let mut terminal_state = window.get_terminal_state();
-                        terminal_state.composition_text = current_composition
-                            .map(|c| c.to_string())
-                            .unwrap_or_default()
-                            .into();
-                        window.set_terminal_state(terminal_state);
-                        log::debug!("Korean composition after backspace: {:?}", current_composition);
-                    }
-                    
-                    // 한글 IME에서 처리했으면 터미널로 백스페이스 보내지 않음
-                    if consumed {
-                        return;
-                    }
-                }
-                
-                // 한글 IME에서 처리하지 않았으면 터미널로 백스페이스 전송
-                if let Err(e) = tm.write_to_session(session_id, "\u{7f}") {
-                    log::error!("Failed to write backspace to terminal: {}", e);
-                }
-            }
-        }
-    }
-    
-    /// tterm 스타일: 엔터 키 처리 (한글 조합 완료, 엔터는 조합 중이 아닐 때만 전송)
-    fn handle_enter_key(
-        terminal_manager: &Arc<Mutex<TerminalManager>>,
-        korean_ime: &Arc<Mutex<KoreanIME>>,
-        window_weak: &Weak<MainWindow>
-    ) {
-        if let Ok(tm) = terminal_manager.try_lock() {
-            if let Some(active_session) = tm.get_active_session() {
-                let session_id = active_session.id;
-                
-                // 한글 조합 중인지 확인
-                let was_composing = if let Ok(ime) = korean_ime.try_lock() {
-                    ime.terminal_states.get(&session_id)
-                        .map(|state| state.is_composing)
-                        .unwrap_or(false)
-                } else {
-                    false
-                };
-                
-                // 한글 조합 완료 처리
-                if let Ok(mut ime) = korean_ime.try_lock() {
-                    if let Some(state) = ime.terminal_states.get_mut(&session_id) {
-                        if state.is_composing {
-                            if let Some(completed) = state.get_current_char() {
-                                // 조합 중인 글자 완성해서 터미널로 전송
-                                if let Err(e) = tm.write_to_session(session_id, &completed.to_string()) {
-                                    log::error!("Failed to write completed Korean char to terminal: {}", e);
-                                }
-                            }
-                            state.reset();
-                            
-                            // UI 업데이트 - 조합 완료로 composition_text 비우기
-                            if let Some(window) = window_weak.upgrade() {
-                                let mut terminal_state = window.get_terminal_state();
-                                terminal_state.composition_text = "".into();
-                                window.set_terminal_state(terminal_state);
-                                log::debug!("Korean composition completed on Enter (Enter not sent)");
-                            }
-                        }
-                    }
-                }
-                
-                // 조합 중이었으면 엔터 전송하지 않음, 조합 중이 아니었으면 엔터 전송
-                if !was_composing {
-                    if let Err(e) = tm.write_to_session(session_id, "\r") {
-                        log::error!("Failed to write enter to terminal: {}", e);
-                    }
-                }
-            }
-        }
-    }
-    
-    /// tterm 스타일: 스페이스 키 처리 (한글 조합 완료 후 스페이스)
-    fn handle_space_key(
-        terminal_manager: &Arc<Mutex<TerminalManager>>,
-        korean_ime: &Arc<Mutex<KoreanIME>>,
-        window_weak: &Weak<MainWindow>
-    ) {
-        if let Ok(tm) = terminal_manager.try_lock() {
-            if let Some(active_session) = tm.get_active_session() {
-                let session_id = active_session.id;
-                
-                // 한글 조합 완료 처리
-                if let Ok(mut ime) = korean_ime.try_lock() {
-                    if let Some(state) = ime.terminal_states.get_mut(&session_id) {
-                        if state.is_composing {
-                            if let Some(completed) = state.get_current_char() {
-                                // 조합 중인 글자 완성해서 터미널로 전송
-                                if let Err(e) = tm.write_to_session(session_id, &completed.to_string()) {
-                                    log::error!("Failed to write completed Korean char to terminal: {}", e);
-                                }
-                            }
-                            state.reset();
-                            
-                            // UI 업데이트 - 조합 완료로 composition_text 비우기
-                            if let Some(window) = window_weak.upgrade() {
-                                let mut terminal_state = window.get_terminal_state();
-                                terminal_state.composition_text = "".into();
-                                window.set_terminal_state(terminal_state);
-                                log::debug!("Korean composition completed on Space");
-                            }
-                        }
-                    }
-                }
-                
-                // 스페이스 전송
-                if let Err(e) = tm.write_to_session(session_id, " ") {
-                    log::error!("Failed to write space to terminal: {}", e);
-                }
-            }
-        }
-    }
-    
-    /// Convert Ctrl key combinations to terminal control bytes  
+
+    /// Convert Ctrl key combinations to terminal control bytes
     fn ctrl_key_to_bytes(text: &str) -> Option<Vec<u8>> {
         // Slint에서 Ctrl 키 조합 시 텍스트가 비어있을 수 있음을 고려
         if text.is_empty() {
             // Ctrl 키만 눌린 경우 - 일반적인 Ctrl 조합들 처리 불가
             return None;
         }
-        
+
         // Single character Ctrl combinations
         if text.chars().count() == 1 {
             let ch = text.chars().next()?;
             match ch.to_ascii_lowercase() {
-                'a' => Some(b"\x01".to_vec()), // Ctrl+A
-                'b' => Some(b"\x02".to_vec()), // Ctrl+B  
-                'c' => Some(b"\x03".to_vec()), // Ctrl+C (SIGINT)
-                'd' => Some(b"\x04".to_vec()), // Ctrl+D (EOF)
-                'e' => Some(b"\x05".to_vec()), // Ctrl+E
-                'f' => Some(b"\x06".to_vec()), // Ctrl+F
-                'g' => Some(b"\x07".to_vec()), // Ctrl+G (Bell)
-                'h' => Some(b"\x08".to_vec()), // Ctrl+H (Backspace)
-                'i' => Some(b"\x09".to_vec()), // Ctrl+I (Tab)
-                'j' => Some(b"\x0a".to_vec()), // Ctrl+J (LF)
-                'k' => Some(b"\x0b".to_vec()), // Ctrl+K
-                'l' => Some(b"\x0c".to_vec()), // Ctrl+L (Clear screen)
-                'm' => Some(b"\x0d".to_vec()), // Ctrl+M (CR)
-                'n' => Some(b"\x0e".to_vec()), // Ctrl+N
-                'o' => Some(b"\x0f".to_vec()), // Ctrl+O
-                'p' => Some(b"\x10".to_vec()), // Ctrl+P
-                'q' => Some(b"\x11".to_vec()), // Ctrl+Q (XON)
-                'r' => Some(b"\x12".to_vec()), // Ctrl+R
-                's' => Some(b"\x13".to_vec()), // Ctrl+S (XOFF)
-                't' => Some(b"\x14".to_vec()), // Ctrl+T
-                'u' => Some(b"\x15".to_vec()), // Ctrl+U
-                'v' => Some(b"\x16".to_vec()), // Ctrl+V
-                'w' => Some(b"\x17".to_vec()), // Ctrl+W
-                'x' => Some(b"\x18".to_vec()), // Ctrl+X
-                'y' => Some(b"\x19".to_vec()), // Ctrl+Y
-                'z' => Some(b"\x1a".to_vec()), // Ctrl+Z (SIGTSTP)
-                '[' => Some(b"\x1b".to_vec()), // Ctrl+[ (ESC)
+                'a' => Some(b"\x01".to_vec()),  // Ctrl+A
+                'b' => Some(b"\x02".to_vec()),  // Ctrl+B
+                'c' => Some(b"\x03".to_vec()),  // Ctrl+C (SIGINT)
+                'd' => Some(b"\x04".to_vec()),  // Ctrl+D (EOF)
+                'e' => Some(b"\x05".to_vec()),  // Ctrl+E
+                'f' => Some(b"\x06".to_vec()),  // Ctrl+F
+                'g' => Some(b"\x07".to_vec()),  // Ctrl+G (Bell)
+                'h' => Some(b"\x08".to_vec()),  // Ctrl+H (Backspace)
+                'i' => Some(b"\x09".to_vec()),  // Ctrl+I (Tab)
+                'j' => Some(b"\x0a".to_vec()),  // Ctrl+J (LF)
+                'k' => Some(b"\x0b".to_vec()),  // Ctrl+K
+                'l' => Some(b"\x0c".to_vec()),  // Ctrl+L (Clear screen)
+                'm' => Some(b"\x0d".to_vec()),  // Ctrl+M (CR)
+                'n' => Some(b"\x0e".to_vec()),  // Ctrl+N
+                'o' => Some(b"\x0f".to_vec()),  // Ctrl+O
+                'p' => Some(b"\x10".to_vec()),  // Ctrl+P
+                'q' => Some(b"\x11".to_vec()),  // Ctrl+Q (XON)
+                'r' => Some(b"\x12".to_vec()),  // Ctrl+R
+                's' => Some(b"\x13".to_vec()),  // Ctrl+S (XOFF)
+                't' => Some(b"\x14".to_vec()),  // Ctrl+T
+                'u' => Some(b"\x15".to_vec()),  // Ctrl+U
+                'v' => Some(b"\x16".to_vec()),  // Ctrl+V
+                'w' => Some(b"\x17".to_vec()),  // Ctrl+W
+                'x' => Some(b"\x18".to_vec()),  // Ctrl+X
+                'y' => Some(b"\x19".to_vec()),  // Ctrl+Y
+                'z' => Some(b"\x1a".to_vec()),  // Ctrl+Z (SIGTSTP)
+                '[' => Some(b"\x1b".to_vec()),  // Ctrl+[ (ESC)
                 '\\' => Some(b"\x1c".to_vec()), // Ctrl+\
-                ']' => Some(b"\x1d".to_vec()), // Ctrl+]
-                '^' => Some(b"\x1e".to_vec()), // Ctrl+^
-                '_' => Some(b"\x1f".to_vec()), // Ctrl+_
-                ' ' => Some(b"\x00".to_vec()), // Ctrl+Space (NUL)
+                ']' => Some(b"\x1d".to_vec()),  // Ctrl+]
+                '^' => Some(b"\x1e".to_vec()),  // Ctrl+^
+                '_' => Some(b"\x1f".to_vec()),  // Ctrl+_
+                ' ' => Some(b"\x00".to_vec()),  // Ctrl+Space (NUL)
                 _ => None,
             }
         } else {
             None
         }
     }
-    
+
     /// Convert Alt/Meta key combinations to escape sequences
     fn alt_key_to_bytes(text: &str) -> Option<Vec<u8>> {
         if !text.is_empty() {
@@ -1139,3 +2254,85 @@ impl Drop for UIManager {
         log::info!("UIManager dropped");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClipboardOverrides, ProfileConfig};
+    use crate::terminal::SessionOptions;
+
+    fn pending_session(pending: &Arc<Mutex<Option<ClipboardRequest>>>) -> Option<SessionId> {
+        pending
+            .blocking_lock()
+            .as_ref()
+            .map(ClipboardRequest::session_id)
+    }
+
+    #[test]
+    fn test_clipboard_requests_follow_session_policy() {
+        // 전역: 쓰기 허용, 읽기 확인 / remote 프로필: 쓰기 확인, 읽기 거부, 8바이트
+        let mut config = Config::default();
+        config.profiles.insert(
+            "remote".into(),
+            ProfileConfig {
+                clipboard: ClipboardOverrides {
+                    osc52_write: Some(ClipboardAccess::Ask),
+                    osc52_read: Some(ClipboardAccess::Deny),
+                    osc52_max_bytes: Some(8),
+                },
+                ..Default::default()
+            },
+        );
+        let mut tm = TerminalManager::new(config).unwrap();
+        let command = vec!["sh".to_string(), "-c".to_string(), "sleep 2".to_string()];
+        let remote = tm
+            .create_session(SessionOptions {
+                command: command.clone(),
+                profile: Some("remote".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        let plain = tm
+            .create_session(SessionOptions {
+                command,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let pending = Arc::new(Mutex::new(None));
+        let window = Weak::default();
+        let load = |session_id| ClipboardRequest::Load {
+            session_id,
+            format: Arc::new(|text: &str| text.to_string()),
+        };
+        let store = |session_id| ClipboardRequest::Store {
+            session_id,
+            text: "copied".into(),
+        };
+
+        // 거부된 읽기는 확인을 기다리지 않음
+        UIManager::handle_clipboard_request(&tm, load(remote), &pending, &window);
+        assert_eq!(pending_session(&pending), None);
+        // 허용된 쓰기는 바로 실행
+        UIManager::handle_clipboard_request(&tm, store(plain), &pending, &window);
+        assert_eq!(pending_session(&pending), None);
+        // 확인할 요청은 대기, 대기 중에 온 다른 확인 요청은 거부
+        UIManager::handle_clipboard_request(&tm, store(remote), &pending, &window);
+        assert_eq!(pending_session(&pending), Some(remote));
+        UIManager::handle_clipboard_request(&tm, load(plain), &pending, &window);
+        assert!(matches!(
+            pending.blocking_lock().as_ref(),
+            Some(ClipboardRequest::Store { session_id, .. }) if *session_id == remote
+        ));
+
+        // 크기 제한은 세션마다 따로
+        let format = |text: &str| format!("<{}>", text);
+        assert!(!tm.store_clipboard(remote, "123456789"));
+        assert_eq!(
+            tm.clipboard_reply(remote, "12345678", &format).as_deref(),
+            Some("<12345678>")
+        );
+        assert_eq!(tm.clipboard_reply(remote, "123456789", &format), None);
+        assert!(tm.clipboard_reply(plain, "123456789", &format).is_some());
+    }
+}
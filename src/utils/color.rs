@@ -1,6 +1,9 @@
+use alacritty_terminal::term::color::{Colors, COUNT};
 use alacritty_terminal::vte::ansi::{self, NamedColor};
 use anyhow::Result;
 
+use crate::config::UIConfig;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: u8,
@@ -32,18 +35,14 @@ impl Color {
         Ok(Self::rgb(r, g, b))
     }
 
-    pub fn to_hex(&self) -> String {
+    pub fn to_hex(self) -> String {
         format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
     }
 
-    pub fn to_slint_color(&self) -> slint::Color {
+    pub fn to_slint_color(self) -> slint::Color {
         slint::Color::from_rgb_u8(self.r, self.g, self.b)
     }
 
-    pub fn from_slint_color(color: slint::Color) -> Self {
-        Self::new(color.red(), color.green(), color.blue(), color.alpha())
-    }
-
     pub fn blend(&self, other: &Color, alpha: f32) -> Self {
         let alpha = alpha.clamp(0.0, 1.0);
         let inv_alpha = 1.0 - alpha;
@@ -57,11 +56,11 @@ impl Color {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct ColorTheme {
     pub background: Color,
     pub foreground: Color,
     pub cursor: Color,
-    pub selection: Color,
     pub black: Color,
     pub red: Color,
     pub green: Color,
@@ -92,7 +91,6 @@ impl ColorTheme {
             background: Color::from_hex("#1e1e1e").unwrap(),
             foreground: Color::from_hex("#ffffff").unwrap(),
             cursor: Color::from_hex("#ffffff").unwrap(),
-            selection: Color::from_hex("#404040").unwrap(),
             black: Color::from_hex("#000000").unwrap(),
             red: Color::from_hex("#cd0000").unwrap(),
             green: Color::from_hex("#00cd00").unwrap(),
@@ -117,7 +115,6 @@ impl ColorTheme {
             background: Color::from_hex("#ffffff").unwrap(),
             foreground: Color::from_hex("#000000").unwrap(),
             cursor: Color::from_hex("#000000").unwrap(),
-            selection: Color::from_hex("#b5d5ff").unwrap(),
             black: Color::from_hex("#000000").unwrap(),
             red: Color::from_hex("#cd0000").unwrap(),
             green: Color::from_hex("#00cd00").unwrap(),
@@ -137,6 +134,36 @@ impl ColorTheme {
         }
     }
 
+    /// Build the theme selected by `ui.theme`, overriding background, foreground and cursor with
+    /// the configured colors. The cursor takes the foreground color unless `cursor_color` is set;
+    /// invalid color strings fall back to the theme's own values.
+    pub fn from_config(ui: &UIConfig) -> Self {
+        let mut theme = match ui.theme.as_str() {
+            "light" => Self::light_theme(),
+            _ => Self::dark_theme(),
+        };
+
+        match Color::from_hex(&ui.background_color) {
+            Ok(color) => theme.background = color,
+            Err(e) => log::warn!("Ignoring background_color {:?}: {}", ui.background_color, e),
+        }
+        match Color::from_hex(&ui.foreground_color) {
+            Ok(color) => {
+                theme.foreground = color;
+                theme.cursor = color;
+            }
+            Err(e) => log::warn!("Ignoring foreground_color {:?}: {}", ui.foreground_color, e),
+        }
+        if let Some(cursor_color) = &ui.cursor_color {
+            match Color::from_hex(cursor_color) {
+                Ok(color) => theme.cursor = color,
+                Err(e) => log::warn!("Ignoring cursor_color {:?}: {}", cursor_color, e),
+            }
+        }
+
+        theme
+    }
+
     pub fn get_ansi_color(&self, index: u8) -> Color {
         match index {
             0 => self.black,
@@ -160,45 +187,43 @@ impl ColorTheme {
     }
 
     /// Convert alacritty's Color to our Color
-    pub fn convert_ansi_color(&self, color: &ansi::Color) -> Color {
+    ///
+    /// `overrides` holds the palette entries set at runtime through OSC 4/10/11/12; entries that
+    /// are `None` (never set, or reset via OSC 104/110/111/112) fall back to the theme.
+    pub fn convert_ansi_color(&self, color: &ansi::Color, overrides: &Colors) -> Color {
         match color {
-            ansi::Color::Named(named_color) => self.get_named_color(named_color),
+            ansi::Color::Named(named_color) => self.resolve_index(*named_color as usize, overrides),
             ansi::Color::Spec(rgb) => Color::rgb(rgb.r, rgb.g, rgb.b),
-            ansi::Color::Indexed(indexed_color) => self.get_indexed_color(*indexed_color),
+            ansi::Color::Indexed(indexed_color) => {
+                self.resolve_index(*indexed_color as usize, overrides)
+            }
         }
     }
 
-    fn get_named_color(&self, named_color: &NamedColor) -> Color {
-        match named_color {
-            NamedColor::Foreground => self.foreground,
-            NamedColor::Background => self.background,
-            NamedColor::Black => self.black,
-            NamedColor::Red => self.red,
-            NamedColor::Green => self.green,
-            NamedColor::Yellow => self.yellow,
-            NamedColor::Blue => self.blue,
-            NamedColor::Magenta => self.magenta,
-            NamedColor::Cyan => self.cyan,
-            NamedColor::White => self.white,
-            NamedColor::BrightBlack => self.bright_black,
-            NamedColor::BrightRed => self.bright_red,
-            NamedColor::BrightGreen => self.bright_green,
-            NamedColor::BrightYellow => self.bright_yellow,
-            NamedColor::BrightBlue => self.bright_blue,
-            NamedColor::BrightMagenta => self.bright_magenta,
-            NamedColor::BrightCyan => self.bright_cyan,
-            NamedColor::BrightWhite => self.bright_white,
-            NamedColor::BrightForeground => self.bright_white,
-            NamedColor::DimForeground => self.foreground.blend(&self.background, 0.5),
-            NamedColor::DimBlack => self.black.blend(&self.background, 0.5),
-            NamedColor::DimRed => self.red.blend(&self.background, 0.5),
-            NamedColor::DimGreen => self.green.blend(&self.background, 0.5),
-            NamedColor::DimYellow => self.yellow.blend(&self.background, 0.5),
-            NamedColor::DimBlue => self.blue.blend(&self.background, 0.5),
-            NamedColor::DimMagenta => self.magenta.blend(&self.background, 0.5),
-            NamedColor::DimCyan => self.cyan.blend(&self.background, 0.5),
-            NamedColor::DimWhite => self.white.blend(&self.background, 0.5),
-            NamedColor::Cursor => self.cursor,
+    /// Resolve a palette index using alacritty's `Colors` layout (0..256 indexed, then
+    /// foreground, background, cursor, dim colors, bright/dim foreground).
+    pub fn resolve_index(&self, index: usize, overrides: &Colors) -> Color {
+        if let Some(rgb) = (index < COUNT).then(|| overrides[index]).flatten() {
+            return Color::rgb(rgb.r, rgb.g, rgb.b);
+        }
+
+        let dim = |base: usize| {
+            let background = self.resolve_index(NamedColor::Background as usize, overrides);
+            self.resolve_index(base, overrides).blend(&background, 0.5)
+        };
+
+        match index {
+            0..=255 => self.get_indexed_color(index as u8),
+            i if i == NamedColor::Foreground as usize => self.foreground,
+            i if i == NamedColor::Background as usize => self.background,
+            i if i == NamedColor::Cursor as usize => self.cursor,
+            i if i == NamedColor::BrightForeground as usize => self.bright_white,
+            i if i == NamedColor::DimForeground as usize => dim(NamedColor::Foreground as usize),
+            // DimBlack..=DimWhite: 대응하는 기본 색상(0..8)을 배경과 섞음
+            i if (NamedColor::DimBlack as usize..=NamedColor::DimWhite as usize).contains(&i) => {
+                dim(i - NamedColor::DimBlack as usize)
+            }
+            _ => self.foreground,
         }
     }
 
@@ -226,3 +251,131 @@ impl ColorTheme {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::terminal::TerminalSession;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::{Config as TermConfig, Term};
+    use alacritty_terminal::vte::ansi::{CursorShape, Processor, Rgb};
+
+    #[test]
+    fn test_palette_overrides() {
+        let theme = ColorTheme::dark_theme();
+        let mut overrides = Colors::default();
+
+        let red = ansi::Color::Named(NamedColor::Red);
+        assert_eq!(theme.convert_ansi_color(&red, &overrides), theme.red);
+
+        // OSC 4;1;rgb:12/34/56
+        overrides[1] = Some(Rgb {
+            r: 0x12,
+            g: 0x34,
+            b: 0x56,
+        });
+        assert_eq!(
+            theme.convert_ansi_color(&red, &overrides),
+            Color::rgb(0x12, 0x34, 0x56)
+        );
+        assert_eq!(
+            theme.convert_ansi_color(&ansi::Color::Indexed(1), &overrides),
+            Color::rgb(0x12, 0x34, 0x56)
+        );
+
+        // OSC 104;1 으로 리셋하면 테마 색상으로 복귀
+        overrides[1] = None;
+        assert_eq!(theme.convert_ansi_color(&red, &overrides), theme.red);
+    }
+
+    #[test]
+    fn test_dim_follows_overridden_background() {
+        let theme = ColorTheme::dark_theme();
+        let mut overrides = Colors::default();
+        overrides[NamedColor::Background] = Some(Rgb {
+            r: 0xff,
+            g: 0xff,
+            b: 0xff,
+        });
+
+        let dim_black = theme.resolve_index(NamedColor::DimBlack as usize, &overrides);
+        assert_eq!(
+            dim_black,
+            theme.black.blend(&Color::rgb(0xff, 0xff, 0xff), 0.5)
+        );
+    }
+
+    #[test]
+    fn test_cursor_color_from_config() {
+        let mut ui = Config::default().ui;
+        ui.foreground_color = "#c0c0c0".into();
+        // 설정이 없으면 글자색
+        assert_eq!(
+            ColorTheme::from_config(&ui).cursor,
+            Color::rgb(0xc0, 0xc0, 0xc0)
+        );
+
+        ui.cursor_color = Some("#ff8800".into());
+        let theme = ColorTheme::from_config(&ui);
+        assert_eq!(theme.cursor, Color::rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.foreground, Color::rgb(0xc0, 0xc0, 0xc0));
+        assert_eq!(
+            theme.resolve_index(NamedColor::Cursor as usize, &Colors::default()),
+            theme.cursor
+        );
+
+        // 잘못된 값은 무시하고 글자색
+        ui.cursor_color = Some("orange".into());
+        assert_eq!(
+            ColorTheme::from_config(&ui).cursor,
+            Color::rgb(0xc0, 0xc0, 0xc0)
+        );
+
+        // OSC 12로 바꾼 색이 설정보다 우선
+        let mut overrides = Colors::default();
+        overrides[NamedColor::Cursor] = Some(Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(
+            theme.resolve_index(NamedColor::Cursor as usize, &overrides),
+            Color::rgb(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn test_cursor_shape() {
+        let mut term = Term::new(TermConfig::default(), &TermSize::new(20, 5), VoidListener);
+        term.is_focused = true;
+        let mut parser: Processor = Processor::new();
+        let shape = |term: &Term<VoidListener>| TerminalSession::cursor_shape(term, true);
+
+        assert_eq!(shape(&term), CursorShape::Block);
+        // DECSCUSR: 3/4 밑줄, 5/6 막대, 0은 기본값으로
+        for (sequence, expected) in [
+            ("\x1b[4 q", CursorShape::Underline),
+            ("\x1b[5 q", CursorShape::Beam),
+            ("\x1b[2 q", CursorShape::Block),
+            ("\x1b[3 q", CursorShape::Underline),
+            ("\x1b[0 q", CursorShape::Block),
+        ] {
+            parser.advance(&mut term, sequence.as_bytes());
+            assert_eq!(shape(&term), expected, "{:?}", sequence);
+        }
+
+        // 포커스를 잃으면 앱이 정한 모양과 관계없이 속이 빈 블록
+        parser.advance(&mut term, b"\x1b[6 q");
+        term.is_focused = false;
+        assert_eq!(shape(&term), CursorShape::HollowBlock);
+        term.is_focused = true;
+        assert_eq!(shape(&term), CursorShape::Beam);
+
+        // 숨긴 커서나 화면 밖 커서는 그리지 않음
+        assert_eq!(
+            TerminalSession::cursor_shape(&term, false),
+            CursorShape::Hidden
+        );
+        parser.advance(&mut term, b"\x1b[?25l");
+        term.is_focused = false;
+        assert_eq!(shape(&term), CursorShape::Hidden);
+    }
+}
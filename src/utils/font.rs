@@ -11,6 +11,9 @@ pub struct FontMetrics {
 }
 
 impl FontMetrics {
+    /// 확대/축소하지 않았을 때의 폰트 크기
+    pub const DEFAULT_FONT_SIZE: i32 = 11;
+
     /// D2Coding 폰트의 메트릭을 폰트 크기에 따라 계산
     pub fn for_d2coding(font_size: i32) -> Self {
         // D2Coding은 고정폭 폰트이므로 폰트 크기에 비례한 계산
@@ -28,115 +31,17 @@ impl FontMetrics {
     }
 
     /// 기본 메트릭 (11pt D2Coding)
+    #[cfg(test)]
     pub fn default() -> Self {
-        Self::for_d2coding(11)
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct FontConfig {
-    pub family: String,
-    pub size: f32,
-    pub bold: bool,
-    pub italic: bool,
-}
-
-impl Default for FontConfig {
-    fn default() -> Self {
-        Self {
-            family: "Monaco".to_string(),
-            size: 14.0,
-            bold: false,
-            italic: false,
-        }
-    }
-}
-
-impl FontConfig {
-    pub fn new(family: String, size: f32) -> Self {
-        Self {
-            family,
-            size,
-            bold: false,
-            italic: false,
-        }
-    }
-
-    pub fn with_bold(mut self, bold: bool) -> Self {
-        self.bold = bold;
-        self
-    }
-
-    pub fn with_italic(mut self, italic: bool) -> Self {
-        self.italic = italic;
-        self
-    }
-
-    pub fn calculate_char_dimensions(&self) -> (f32, f32) {
-        // 고정 폭 폰트의 문자 크기 계산
-        // 실제 구현에서는 폰트 렌더링 라이브러리를 사용해야 함
-        let char_width = self.size * 0.6; // 근사치
-        let char_height = self.size * 1.2; // 근사치
-        (char_width, char_height)
-    }
-
-    pub fn is_monospace(&self) -> bool {
-        // 일반적인 고정 폭 폰트들
-        matches!(
-            self.family.to_lowercase().as_str(),
-            "monaco"
-                | "consolas"
-                | "courier"
-                | "courier new"
-                | "menlo"
-                | "source code pro"
-                | "fira code"
-                | "hack"
-                | "inconsolata"
-                | "jetbrains mono"
-        )
+        Self::for_d2coding(Self::DEFAULT_FONT_SIZE)
     }
 }
 
 pub struct FontManager;
 
 impl FontManager {
-    pub fn get_available_fonts() -> Vec<String> {
-        // macOS에서 사용 가능한 고정 폭 폰트들
-        vec![
-            "Monaco".to_string(),
-            "Menlo".to_string(),
-            "SF Mono".to_string(),
-            "Courier".to_string(),
-            "Courier New".to_string(),
-            "Consolas".to_string(),
-            "Source Code Pro".to_string(),
-            "Fira Code".to_string(),
-            "JetBrains Mono".to_string(),
-            "Hack".to_string(),
-            "Inconsolata".to_string(),
-        ]
-    }
-
-    pub fn is_font_available(font_name: &str) -> bool {
-        Self::get_available_fonts()
-            .iter()
-            .any(|f| f.eq_ignore_ascii_case(font_name))
-    }
-
-    pub fn get_default_font() -> FontConfig {
-        // macOS에서 기본 터미널 폰트
-        if Self::is_font_available("Monaco") {
-            FontConfig::new("Monaco".to_string(), 14.0)
-        } else if Self::is_font_available("Menlo") {
-            FontConfig::new("Menlo".to_string(), 14.0)
-        } else {
-            FontConfig::new("Courier".to_string(), 14.0)
-        }
-    }
-
     pub fn validate_font_size(size: f32) -> Result<f32> {
-        if size < 6.0 || size > 72.0 {
+        if !(6.0..=72.0).contains(&size) {
             Err(anyhow::anyhow!(
                 "Font size must be between 6 and 72 pixels, got: {}",
                 size
@@ -150,6 +55,22 @@ impl FontManager {
         let new_size = current_size * scale_factor;
         Self::validate_font_size(new_size).unwrap_or(current_size)
     }
+
+    /// 확대/축소 한 단계 (10%씩, 작은 크기에서도 최소 1px씩 변경)
+    pub fn step_font_size(current_size: i32, zoom_in: bool) -> i32 {
+        let factor = if zoom_in { 1.1 } else { 1.0 / 1.1 };
+        let scaled = Self::scale_font_size(current_size as f32, factor).round() as i32;
+        let next = if scaled != current_size {
+            scaled
+        } else if zoom_in {
+            current_size + 1
+        } else {
+            current_size - 1
+        };
+        Self::validate_font_size(next as f32)
+            .map(|size| size as i32)
+            .unwrap_or(current_size)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -164,12 +85,70 @@ pub enum FontStyle {
     Italic,
 }
 
-#[derive(Debug, Clone)]
+/// 밑줄 종류 (SGR 4, 4:2 ~ 4:5, 21)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnderlineStyle {
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// UI로 전달할 정수 코드 (0 = 없음)
+    pub fn as_code(&self) -> i32 {
+        match self {
+            UnderlineStyle::None => 0,
+            UnderlineStyle::Single => 1,
+            UnderlineStyle::Double => 2,
+            UnderlineStyle::Curly => 3,
+            UnderlineStyle::Dotted => 4,
+            UnderlineStyle::Dashed => 5,
+        }
+    }
+
+    /// 밑줄 모양에 맞는 SVG path 생성 (viewbox: width x 4)
+    pub fn svg_path(&self, width: i32) -> String {
+        let width = width.max(0);
+        match self {
+            UnderlineStyle::None => String::new(),
+            UnderlineStyle::Single => format!("M 0 2.5 L {} 2.5", width),
+            UnderlineStyle::Double => format!("M 0 1 L {0} 1 M 0 3.5 L {0} 3.5", width),
+            UnderlineStyle::Curly => {
+                // 주기 4px의 물결
+                let mut path = String::from("M 0 2 Q 1 0 2 2");
+                let mut x = 2;
+                while x < width {
+                    x += 2;
+                    path.push_str(&format!(" T {} 2", x));
+                }
+                path
+            }
+            UnderlineStyle::Dotted | UnderlineStyle::Dashed => {
+                let (dash, step) = if *self == UnderlineStyle::Dotted {
+                    (1, 2)
+                } else {
+                    (4, 6)
+                };
+                (0..width)
+                    .step_by(step)
+                    .map(|x| format!("M {} 2.5 L {} 2.5", x, (x + dash).min(width)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextAttributes {
     pub weight: FontWeight,
     pub style: FontStyle,
-    pub underline: bool,
+    pub underline: UnderlineStyle,
     pub strikethrough: bool,
+    pub hidden: bool,
 }
 
 impl Default for TextAttributes {
@@ -177,8 +156,9 @@ impl Default for TextAttributes {
         Self {
             weight: FontWeight::Normal,
             style: FontStyle::Normal,
-            underline: false,
+            underline: UnderlineStyle::None,
             strikethrough: false,
+            hidden: false,
         }
     }
 }
@@ -198,7 +178,7 @@ impl TextAttributes {
         self
     }
 
-    pub fn with_underline(mut self, underline: bool) -> Self {
+    pub fn with_underline(mut self, underline: UnderlineStyle) -> Self {
         self.underline = underline;
         self
     }
@@ -208,6 +188,11 @@ impl TextAttributes {
         self
     }
 
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
     pub fn is_bold(&self) -> bool {
         matches!(self.weight, FontWeight::Bold)
     }
@@ -216,3 +201,17 @@ impl TextAttributes {
         matches!(self.style, FontStyle::Italic)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_font_size() {
+        assert_eq!(FontManager::step_font_size(11, true), 12);
+        assert_eq!(FontManager::step_font_size(11, false), 10);
+        assert_eq!(FontManager::step_font_size(7, false), 6);
+        assert_eq!(FontManager::step_font_size(6, false), 6);
+        assert_eq!(FontManager::step_font_size(72, true), 72);
+    }
+}
@@ -0,0 +1,125 @@
+/// "ctrl+shift+u" 형식으로 설정에 적힌 단축키
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub cmd: bool,
+    pub key: String,
+}
+
+impl KeyBinding {
+    /// 단축키 문자열을 파싱합니다. 마지막 항목이 키, 나머지는 수정자입니다.
+    pub fn parse(binding: &str) -> Option<Self> {
+        let mut parsed = Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            cmd: false,
+            key: String::new(),
+        };
+
+        let parts: Vec<&str> = binding.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last()?;
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => parsed.ctrl = true,
+                "alt" | "option" | "opt" => parsed.alt = true,
+                "shift" => parsed.shift = true,
+                "cmd" | "command" | "super" | "meta" => parsed.cmd = true,
+                other => {
+                    log::warn!("Unknown modifier {:?} in key binding {:?}", other, binding);
+                    return None;
+                }
+            }
+        }
+
+        if key.is_empty() {
+            return None;
+        }
+        // 글자가 아닌 키는 Slint 이벤트 텍스트와 같은 형태로 저장
+        parsed.key = match key.to_lowercase().as_str() {
+            "space" => " ".to_string(),
+            "tab" => "\t".to_string(),
+            "enter" | "return" => "\n".to_string(),
+            "escape" | "esc" => "\u{1b}".to_string(),
+            other => match Self::function_key(other) {
+                Some(text) => text,
+                None => other.to_string(),
+            },
+        };
+        Some(parsed)
+    }
+
+    /// Slint 키 이벤트와 비교합니다.
+    ///
+    /// macOS에서 Slint는 Cmd를 `control`, Ctrl을 `meta`로 보고하므로 플랫폼에 맞게 바꿔서 비교합니다.
+    pub fn matches(&self, text: &str, control: bool, alt: bool, meta: bool, shift: bool) -> bool {
+        let (ctrl_pressed, cmd_pressed) = if cfg!(target_os = "macos") {
+            (meta, control)
+        } else {
+            (control, meta)
+        };
+
+        self.ctrl == ctrl_pressed
+            && self.cmd == cmd_pressed
+            && self.alt == alt
+            && self.shift == shift
+            && (text == self.key || Self::key_text(text).as_deref() == Some(self.key.as_str()))
+    }
+
+    /// F1..F24 는 Slint가 U+F704 부터의 사설 영역 문자로 보냄
+    fn function_key(name: &str) -> Option<String> {
+        let number: u32 = name.strip_prefix('f')?.parse().ok()?;
+        if !(1..=24).contains(&number) {
+            return None;
+        }
+        char::from_u32(0xF704 + number - 1).map(String::from)
+    }
+
+    /// 이벤트 텍스트를 키 이름으로 정규화 (Ctrl 조합의 제어 문자도 원래 글자로 되돌림)
+    fn key_text(text: &str) -> Option<String> {
+        let mut chars = text.chars();
+        let ch = chars.next()?;
+        if chars.next().is_some() {
+            return None;
+        }
+        let ch = match ch as u32 {
+            // Ctrl+A..Ctrl+Z 는 0x01..0x1a 로 들어올 수 있음
+            0x01..=0x1a => char::from_u32(ch as u32 + 0x60)?,
+            _ => ch,
+        };
+        Some(ch.to_lowercase().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_binding() {
+        let binding = KeyBinding::parse("ctrl+shift+U").unwrap();
+        assert!(binding.ctrl && binding.shift && !binding.alt && !binding.cmd);
+        assert_eq!(binding.key, "u");
+        assert_eq!(KeyBinding::parse("ctrl+shift+space").unwrap().key, " ");
+        assert_eq!(KeyBinding::parse("F11").unwrap().key, "\u{F70E}");
+        assert_eq!(KeyBinding::parse("f").unwrap().key, "f");
+
+        assert!(KeyBinding::parse("hyper+u").is_none());
+        assert!(KeyBinding::parse("ctrl+").is_none());
+    }
+
+    #[test]
+    fn test_key_binding_matches() {
+        let binding = KeyBinding::parse("ctrl+shift+u").unwrap();
+        let (control, meta) = if cfg!(target_os = "macos") {
+            (false, true)
+        } else {
+            (true, false)
+        };
+        assert!(binding.matches("U", control, false, meta, true));
+        assert!(binding.matches("\u{15}", control, false, meta, true));
+        assert!(!binding.matches("U", control, false, meta, false));
+    }
+}
@@ -5,10 +5,10 @@ use std::collections::HashMap;
 /// State for Korean character composition for a single terminal session
 #[derive(Debug, Clone)]
 pub struct KoreanInputState {
-    pub chosung: Option<char>,    // 초성 (initial consonant)
-    pub jungsung: Option<char>,   // 중성 (medial vowel)
-    pub jongsung: Option<char>,   // 종성 (final consonant)
-    pub is_composing: bool,       // Whether we're currently composing a character
+    pub chosung: Option<char>,  // 초성 (initial consonant)
+    pub jungsung: Option<char>, // 중성 (medial vowel)
+    pub jongsung: Option<char>, // 종성 (final consonant)
+    pub is_composing: bool,     // Whether we're currently composing a character
 }
 
 impl KoreanInputState {
@@ -32,17 +32,17 @@ impl KoreanInputState {
     pub fn get_current_char(&self) -> Option<char> {
         if let Some(cho) = self.chosung {
             let cho_idx = get_chosung_index(cho)?;
-            
+
             // 중성이 없으면 불완전한 조합이므로 None 반환
             let jung_idx = self.jungsung.and_then(get_jungsung_index)?;
             let jong_idx = self.jongsung.and_then(get_jongsung_index).unwrap_or(0);
-            
+
             Some(compose_korean(cho_idx, jung_idx, jong_idx))
         } else {
             None
         }
     }
-    
+
     /// Get character for UI display - shows individual jamo for incomplete compositions
     pub fn get_display_char(&self) -> Option<char> {
         if let Some(cho) = self.chosung {
@@ -85,19 +85,20 @@ impl KoreanIME {
 
     /// Get or create Korean input state for a terminal
     pub fn get_or_create_state(&mut self, terminal_id: usize) -> &mut KoreanInputState {
-        self.terminal_states.entry(terminal_id).or_insert_with(KoreanInputState::new)
-    }
-
-    /// Clean up state for a closed terminal
-    pub fn remove_terminal(&mut self, terminal_id: usize) {
-        self.terminal_states.remove(&terminal_id);
+        self.terminal_states
+            .entry(terminal_id)
+            .or_insert_with(KoreanInputState::new)
     }
 
     /// Process input text and return (completed_chars, is_composing, current_composition)
-    pub fn process_input(&mut self, terminal_id: usize, input_text: &str) -> (String, bool, Option<char>) {
+    pub fn process_input(
+        &mut self,
+        terminal_id: usize,
+        input_text: &str,
+    ) -> (String, bool, Option<char>) {
         let state = self.get_or_create_state(terminal_id);
         let mut result = String::new();
-        
+
         // ANSI escape sequence 처리 (방향키 등)
         if input_text.starts_with("\x1b[") {
             if state.is_composing {
@@ -111,13 +112,13 @@ impl KoreanIME {
                 // 조합 중이 아니면 정상적으로 전송
                 result.push_str(input_text);
             }
-            
+
             let current_composition = if state.is_composing {
                 state.get_display_char()
             } else {
                 None
             };
-            
+
             return (result, state.is_composing, current_composition);
         }
 
@@ -127,7 +128,7 @@ impl KoreanIME {
                 if state.is_composing {
                     // 조합 중: 불완전한 조합 상태인지 확인
                     let is_incomplete = state.chosung.is_some() && state.jungsung.is_none();
-                    
+
                     if is_incomplete {
                         // 불완전한 상태: 자음만 출력
                         if let Some(cho) = state.chosung {
@@ -146,13 +147,16 @@ impl KoreanIME {
                     // 조합 중이 아닐 때: ANSI escape sequence로 변환하여 전송
                     let ansi_seq = match ch {
                         '\u{f700}' => "\u{1b}OA", // Up Arrow
-                        '\u{f701}' => "\u{1b}OB", // Down Arrow  
+                        '\u{f701}' => "\u{1b}OB", // Down Arrow
                         '\u{f702}' => "\u{1b}OD", // Left Arrow
                         '\u{f703}' => "\u{1b}OC", // Right Arrow
                         _ => unreachable!(),
                     };
                     result.push_str(ansi_seq);
-                    eprintln!("💫 macOS Arrow key - converted to ANSI: {:?} -> {:?}", ch, ansi_seq);
+                    eprintln!(
+                        "💫 macOS Arrow key - converted to ANSI: {:?} -> {:?}",
+                        ch, ansi_seq
+                    );
                 }
             } else if is_korean_jamo(ch) {
                 let completed = Self::process_korean_char(state, ch);
@@ -162,7 +166,7 @@ impl KoreanIME {
                 if state.is_composing {
                     // 불완전한 조합 상태 (중성이 없음)인지 확인
                     let is_incomplete = state.chosung.is_some() && state.jungsung.is_none();
-                    
+
                     if is_incomplete {
                         // 불완전한 상태: 자음만 출력
                         if let Some(cho) = state.chosung {
@@ -175,7 +179,7 @@ impl KoreanIME {
                         }
                     }
                     state.reset();
-                    
+
                     // 엔터키는 조합만 완료하고 무효화, ESC는 조합 완료 후 전송
                     if ch == '\r' || ch == '\n' {
                         // 엔터키는 조합만 완료하고 전송하지 않음
@@ -205,24 +209,16 @@ impl KoreanIME {
             None
         };
 
-        log::debug!("KoreanIME::process_input result: {:?}, is_composing: {}, current_composition: {:?}", 
-                   result, state.is_composing, current_composition);
+        log::debug!(
+            "KoreanIME::process_input result: {:?}, is_composing: {}, current_composition: {:?}",
+            result,
+            state.is_composing,
+            current_composition
+        );
 
         (result, state.is_composing, current_composition)
     }
 
-    /// Finalize any pending composition for a terminal
-    pub fn finalize_composition(&mut self, terminal_id: usize) -> Option<char> {
-        if let Some(state) = self.terminal_states.get_mut(&terminal_id) {
-            if state.is_composing {
-                let completed = state.get_current_char();
-                state.reset();
-                return completed;
-            }
-        }
-        None
-    }
-
     /// Handle backspace for a terminal
     pub fn handle_backspace(&mut self, terminal_id: usize) -> bool {
         if let Some(state) = self.terminal_states.get_mut(&terminal_id) {
@@ -313,25 +309,61 @@ pub fn is_korean_jamo(ch: char) -> bool {
 
 /// Check if character is a consonant
 pub fn is_consonant(ch: char) -> bool {
-    matches!(ch, 
-        'ㄱ' | 'ㄲ' | 'ㄴ' | 'ㄷ' | 'ㄸ' | 'ㄹ' | 'ㅁ' | 'ㅂ' | 'ㅃ' | 
-        'ㅅ' | 'ㅆ' | 'ㅇ' | 'ㅈ' | 'ㅉ' | 'ㅊ' | 'ㅋ' | 'ㅌ' | 'ㅍ' | 'ㅎ'
+    matches!(
+        ch,
+        'ㄱ' | 'ㄲ'
+            | 'ㄴ'
+            | 'ㄷ'
+            | 'ㄸ'
+            | 'ㄹ'
+            | 'ㅁ'
+            | 'ㅂ'
+            | 'ㅃ'
+            | 'ㅅ'
+            | 'ㅆ'
+            | 'ㅇ'
+            | 'ㅈ'
+            | 'ㅉ'
+            | 'ㅊ'
+            | 'ㅋ'
+            | 'ㅌ'
+            | 'ㅍ'
+            | 'ㅎ'
     )
 }
 
 /// Check if character is a vowel
 pub fn is_vowel(ch: char) -> bool {
-    matches!(ch,
-        'ㅏ' | 'ㅐ' | 'ㅑ' | 'ㅒ' | 'ㅓ' | 'ㅔ' | 'ㅕ' | 'ㅖ' | 'ㅗ' | 'ㅘ' | 
-        'ㅙ' | 'ㅚ' | 'ㅛ' | 'ㅜ' | 'ㅝ' | 'ㅞ' | 'ㅟ' | 'ㅠ' | 'ㅡ' | 'ㅢ' | 'ㅣ'
+    matches!(
+        ch,
+        'ㅏ' | 'ㅐ'
+            | 'ㅑ'
+            | 'ㅒ'
+            | 'ㅓ'
+            | 'ㅔ'
+            | 'ㅕ'
+            | 'ㅖ'
+            | 'ㅗ'
+            | 'ㅘ'
+            | 'ㅙ'
+            | 'ㅚ'
+            | 'ㅛ'
+            | 'ㅜ'
+            | 'ㅝ'
+            | 'ㅞ'
+            | 'ㅟ'
+            | 'ㅠ'
+            | 'ㅡ'
+            | 'ㅢ'
+            | 'ㅣ'
     )
 }
 
 /// Get chosung (initial consonant) index
 pub fn get_chosung_index(ch: char) -> Option<usize> {
     let chosungs = [
-        'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 
-        'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ'
+        'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ',
+        'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
     ];
     chosungs.iter().position(|&c| c == ch)
 }
@@ -339,8 +371,8 @@ pub fn get_chosung_index(ch: char) -> Option<usize> {
 /// Get jungsung (medial vowel) index
 pub fn get_jungsung_index(ch: char) -> Option<usize> {
     let jungsungs = [
-        'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 
-        'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ', 'ㅝ', 'ㅞ', 'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ'
+        'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ', 'ㅝ',
+        'ㅞ', 'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ',
     ];
     jungsungs.iter().position(|&c| c == ch)
 }
@@ -348,9 +380,8 @@ pub fn get_jungsung_index(ch: char) -> Option<usize> {
 /// Get jongsung (final consonant) index (0 = no jongsung)
 pub fn get_jongsung_index(ch: char) -> Option<usize> {
     let jongsungs = [
-        '\0', 'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ', 'ㄻ', 'ㄼ',
-        'ㄽ', 'ㄾ', 'ㄿ', 'ㅀ', 'ㅁ', 'ㅂ', 'ㅄ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ',
-        'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ'
+        '\0', 'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ', 'ㄻ', 'ㄼ', 'ㄽ', 'ㄾ', 'ㄿ',
+        'ㅀ', 'ㅁ', 'ㅂ', 'ㅄ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
     ];
     jongsungs.iter().position(|&c| c == ch)
 }
@@ -400,20 +431,20 @@ mod tests {
     #[test]
     fn test_korean_composition() {
         let mut ime = KoreanIME::new();
-        
+
         // Test "안녕" composition
         let (result, composing, _) = ime.process_input(0, "ㅇ");
         assert_eq!(result, "");
         assert!(composing);
-        
+
         let (result, composing, _) = ime.process_input(0, "ㅏ");
         assert_eq!(result, "");
         assert!(composing);
-        
+
         let (result, composing, _) = ime.process_input(0, "ㄴ");
         assert_eq!(result, "");
         assert!(composing);
-        
+
         let (result, composing, _) = ime.process_input(0, "ㄴ"); // This should complete "안" and start "ㄴ"
         assert_eq!(result, "안");
         assert!(composing);
@@ -422,12 +453,12 @@ mod tests {
     #[test]
     fn test_vowel_combination() {
         let mut ime = KoreanIME::new();
-        
+
         // Test "과" composition (ㄱ + ㅗ + ㅏ = ㄱ + ㅘ)
         ime.process_input(0, "ㄱ");
         ime.process_input(0, "ㅗ");
         let (result, composing, current) = ime.process_input(0, "ㅏ");
-        
+
         assert_eq!(result, "");
         assert!(composing);
         assert_eq!(current, Some('과'));
@@ -436,15 +467,15 @@ mod tests {
     #[test]
     fn test_consonant_combination() {
         let mut ime = KoreanIME::new();
-        
-        // Test "갉" composition (ㄱ + ㅏ + ㄱ + ㅅ = ㄱ + ㅏ + ㄳ)
+
+        // Test "갃" composition (ㄱ + ㅏ + ㄱ + ㅅ = ㄱ + ㅏ + ㄳ)
         ime.process_input(0, "ㄱ");
         ime.process_input(0, "ㅏ");
         ime.process_input(0, "ㄱ");
         let (result, composing, current) = ime.process_input(0, "ㅅ");
-        
+
         assert_eq!(result, "");
         assert!(composing);
-        assert_eq!(current, Some('갉'));
+        assert_eq!(current, Some('갃'));
     }
 }
@@ -1,35 +1,17 @@
-use anyhow::Result;
-use std::path::PathBuf;
-
 pub mod color;
 pub mod font;
+pub mod keybinding;
 pub mod korean_ime;
 pub mod platform;
 
-pub use color::*;
-pub use font::*;
-pub use platform::*;
-
-/// 애플리케이션 데이터 디렉토리를 반환합니다.
-pub fn get_app_data_dir() -> Result<PathBuf> {
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-
-    Ok(home_dir.join(".config").join("sterm"))
-}
-
-/// 로그 파일 경로를 반환합니다.
-pub fn get_log_file_path() -> Result<PathBuf> {
-    let app_data_dir = get_app_data_dir()?;
-    Ok(app_data_dir.join("logs").join("sterm.log"))
-}
-
 /// 문자열이 유효한 색상 코드인지 확인합니다.
+#[cfg(test)]
 pub fn is_valid_color(color: &str) -> bool {
     color.starts_with('#') && color.len() == 7 && color[1..].chars().all(|c| c.is_ascii_hexdigit())
 }
 
 /// 바이트 크기를 사람이 읽기 쉬운 형태로 변환합니다.
+#[cfg(test)]
 pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     const THRESHOLD: f64 = 1024.0;
@@ -53,12 +35,8 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
-/// 텍스트가 UTF-8인지 확인합니다.
-pub fn is_valid_utf8(bytes: &[u8]) -> bool {
-    std::str::from_utf8(bytes).is_ok()
-}
-
 /// 문자열을 안전하게 자릅니다 (UTF-8 경계 고려).
+#[cfg(test)]
 pub fn safe_truncate(s: &str, max_len: usize) -> &str {
     if s.len() <= max_len {
         return s;
@@ -6,39 +6,6 @@ use std::path::PathBuf;
 pub struct Platform;
 
 impl Platform {
-    /// 현재 플랫폼의 이름을 반환합니다.
-    pub fn name() -> &'static str {
-        if cfg!(target_os = "macos") {
-            "macOS"
-        } else if cfg!(target_os = "linux") {
-            "Linux"
-        } else if cfg!(target_os = "windows") {
-            "Windows"
-        } else {
-            "Unknown"
-        }
-    }
-
-    /// 기본 셸을 반환합니다.
-    pub fn default_shell() -> String {
-        std::env::var("SHELL").unwrap_or_else(|_| {
-            if cfg!(target_os = "windows") {
-                "cmd.exe".to_string()
-            } else {
-                "/bin/sh".to_string()
-            }
-        })
-    }
-
-    /// 시스템 설정 디렉토리를 반환합니다.
-    pub fn config_dir() -> Result<PathBuf> {
-        if let Some(config_dir) = dirs::config_dir() {
-            Ok(config_dir.join("sterm"))
-        } else {
-            Err(anyhow::anyhow!("Could not find config directory"))
-        }
-    }
-
     /// 애플리케이션 데이터 디렉토리를 반환합니다.
     pub fn data_dir() -> Result<PathBuf> {
         if let Some(data_dir) = dirs::data_dir() {
@@ -48,13 +15,17 @@ impl Platform {
         }
     }
 
-    /// 캐시 디렉토리를 반환합니다.
-    pub fn cache_dir() -> Result<PathBuf> {
-        if let Some(cache_dir) = dirs::cache_dir() {
-            Ok(cache_dir.join("sterm"))
-        } else {
-            Err(anyhow::anyhow!("Could not find cache directory"))
+    /// 소켓 등 실행 중에만 필요한 파일을 둘 디렉토리를 반환합니다.
+    pub fn runtime_dir() -> Result<PathBuf> {
+        if let Some(runtime_dir) = dirs::runtime_dir() {
+            return Ok(runtime_dir.join("sterm"));
         }
+        // XDG_RUNTIME_DIR이 없으면 (macOS 등) 사용자별 임시 디렉토리 사용
+        #[cfg(unix)]
+        let user = unsafe { libc::getuid() }.to_string();
+        #[cfg(not(unix))]
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        Ok(std::env::temp_dir().join(format!("sterm-{}", user)))
     }
 
     /// 클립보드에 텍스트를 복사합니다.
@@ -65,6 +36,7 @@ impl Platform {
         #[cfg(not(target_os = "macos"))]
         {
             // 다른 플랫폼에서는 기본 구현
+            let _ = text;
             log::warn!("Clipboard functionality not implemented for this platform");
             Err(anyhow::anyhow!("Clipboard not supported"))
         }
@@ -83,6 +55,51 @@ impl Platform {
         }
     }
 
+    /// URL이나 파일을 여는 플랫폼 기본 명령을 반환합니다.
+    pub fn default_opener() -> &'static str {
+        if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(target_os = "windows") {
+            "explorer"
+        } else {
+            "xdg-open"
+        }
+    }
+
+    /// 명령(공백으로 구분된 인자 포함)에 대상을 붙여 백그라운드로 실행합니다.
+    pub fn open_with(command: &str, target: &str) -> Result<()> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Opener command is empty"))?;
+
+        std::process::Command::new(program)
+            .args(parts)
+            .arg(target)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+
+        Ok(())
+    }
+
+    /// 템플릿 명령을 공백으로 나눈 뒤 각 인자를 그대로 실행합니다.
+    pub fn spawn_command(args: &[String]) -> Result<()> {
+        let (program, rest) = args
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("Command is empty"))?;
+
+        std::process::Command::new(program)
+            .args(rest)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+
+        Ok(())
+    }
+
     /// 시스템 알림을 표시합니다.
     pub fn show_notification(title: &str, message: &str) -> Result<()> {
         #[cfg(target_os = "macos")]
@@ -95,13 +112,37 @@ impl Platform {
         }
     }
 
-    /// 시스템 테마를 확인합니다 (다크/라이트).
-    pub fn is_dark_mode() -> bool {
+    /// 프로세스 ID로 실행 파일 이름을 가져옵니다.
+    pub fn process_name(pid: u32) -> Option<String> {
         #[cfg(target_os = "macos")]
-        return macos::is_dark_mode();
+        return macos::process_name(pid);
 
-        #[cfg(not(target_os = "macos"))]
-        false
+        #[cfg(target_os = "linux")]
+        return std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            let _ = pid;
+            None
+        }
+    }
+
+    /// 프로세스의 현재 작업 디렉토리를 가져옵니다.
+    pub fn process_cwd(pid: u32) -> Option<PathBuf> {
+        #[cfg(target_os = "macos")]
+        return macos::process_cwd(pid);
+
+        #[cfg(target_os = "linux")]
+        return std::fs::read_link(format!("/proc/{}/cwd", pid)).ok();
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            let _ = pid;
+            None
+        }
     }
 }
 
@@ -146,86 +187,34 @@ mod macos {
         Ok(())
     }
 
-    pub fn is_dark_mode() -> bool {
-        let output = Command::new("defaults")
-            .args(&["read", "-g", "AppleInterfaceStyle"])
-            .output();
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                let style = String::from_utf8_lossy(&output.stdout);
-                return style.trim() == "Dark";
-            }
-        }
-
-        false
-    }
-}
-
-/// 시스템 정보를 가져오는 구조체
-pub struct SystemInfo {
-    pub os_name: String,
-    pub os_version: String,
-    pub kernel_version: String,
-    pub hostname: String,
-    pub username: String,
-}
-
-impl SystemInfo {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            os_name: Platform::name().to_string(),
-            os_version: Self::get_os_version()?,
-            kernel_version: Self::get_kernel_version()?,
-            hostname: Self::get_hostname()?,
-            username: Self::get_username()?,
-        })
-    }
-
-    fn get_os_version() -> Result<String> {
-        #[cfg(target_os = "macos")]
-        {
-            let output = std::process::Command::new("sw_vers")
-                .arg("-productVersion")
-                .output()?;
-
-            if output.status.success() {
-                Ok(String::from_utf8(output.stdout)?.trim().to_string())
-            } else {
-                Ok("Unknown".to_string())
-            }
+    pub fn process_name(pid: u32) -> Option<String> {
+        let output = Command::new("ps")
+            .args(["-o", "comm=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
         }
 
-        #[cfg(not(target_os = "macos"))]
-        Ok("Unknown".to_string())
+        // ps는 전체 경로를 출력하므로 파일 이름만 사용
+        let command = String::from_utf8_lossy(&output.stdout);
+        let name = command.trim().rsplit('/').next()?.trim_start_matches('-');
+        (!name.is_empty()).then(|| name.to_string())
     }
 
-    fn get_kernel_version() -> Result<String> {
-        let output = std::process::Command::new("uname").arg("-r").output()?;
-
-        if output.status.success() {
-            Ok(String::from_utf8(output.stdout)?.trim().to_string())
-        } else {
-            Ok("Unknown".to_string())
+    pub fn process_cwd(pid: u32) -> Option<std::path::PathBuf> {
+        let output = Command::new("lsof")
+            .args(["-a", "-d", "cwd", "-p", &pid.to_string(), "-Fn"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
         }
-    }
-
-    fn get_hostname() -> Result<String> {
-        std::env::var("HOSTNAME")
-            .or_else(|_| std::env::var("COMPUTERNAME"))
-            .or_else(|_| {
-                let output = std::process::Command::new("hostname").output()?;
-                if output.status.success() {
-                    Ok(String::from_utf8(output.stdout)?.trim().to_string())
-                } else {
-                    Err(anyhow::anyhow!("Failed to get hostname"))
-                }
-            })
-    }
 
-    fn get_username() -> Result<String> {
-        std::env::var("USER")
-            .or_else(|_| std::env::var("USERNAME"))
-            .map_err(|_| anyhow::anyhow!("Failed to get username"))
+        // -Fn 출력에서 'n'으로 시작하는 줄이 경로
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix('n'))
+            .map(std::path::PathBuf::from)
     }
 }